@@ -0,0 +1,279 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    adapters::session_csv::parse_session_csv,
+    domain::{
+        connection_status::LinkInfo, eeg_packet::EegPacket, eeg_reading::EegReading,
+        errors::AppError, ports::EegSource, source_event::SourceEvent,
+    },
+};
+
+// Longest single sleep inside next_event, so pause, seek and stop requests
+// are picked up promptly between widely spaced rows.
+const REPLAY_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Playback rate relative to the original recording.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReplaySpeed {
+    #[default]
+    #[serde(rename = "1x")]
+    RealTime,
+    #[serde(rename = "4x")]
+    Quadruple,
+    /// Emits rows back to back without waiting.
+    #[serde(rename = "max")]
+    Max,
+}
+
+impl ReplaySpeed {
+    /// To express the speed as a factor on recorded time; None for `max`.
+    pub fn multiplier(self) -> Option<f64> {
+        match self {
+            ReplaySpeed::RealTime => Some(1.0),
+            ReplaySpeed::Quadruple => Some(4.0),
+            ReplaySpeed::Max => None,
+        }
+    }
+}
+
+/// Snapshot of playback state returned to the UI for a seek bar.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplayStatus {
+    pub paused: bool,
+    pub speed: ReplaySpeed,
+    pub position_secs: f64,
+    pub duration_secs: f64,
+}
+
+struct ReplayControlState {
+    paused: bool,
+    speed: ReplaySpeed,
+    pending_seek_ms: Option<u64>,
+    position_ms: u64,
+    duration_ms: u64,
+    // Bumped on every pause/resume/seek/speed change so the source knows to
+    // re-anchor its playback clock.
+    revision: u64,
+}
+
+/// Shared handle through which Tauri commands steer a running replay.
+/// Cloning yields another handle to the same playback.
+#[derive(Clone)]
+pub struct ReplayControl {
+    state: Arc<Mutex<ReplayControlState>>,
+}
+
+impl ReplayControl {
+    pub fn new(speed: ReplaySpeed) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(ReplayControlState {
+                paused: false,
+                speed,
+                pending_seek_ms: None,
+                position_ms: 0,
+                duration_ms: 0,
+                revision: 0,
+            })),
+        }
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        let mut state = self.lock();
+        state.paused = paused;
+        state.revision += 1;
+    }
+
+    pub fn set_speed(&self, speed: ReplaySpeed) {
+        let mut state = self.lock();
+        state.speed = speed;
+        state.revision += 1;
+    }
+
+    /// To jump to `position_secs` from the start of the recording. Positions
+    /// past the end finish the replay on the next poll.
+    pub fn seek(&self, position_secs: f64) {
+        let mut state = self.lock();
+        state.pending_seek_ms = Some((position_secs.max(0.0) * 1_000.0) as u64);
+        state.revision += 1;
+    }
+
+    pub fn status(&self) -> ReplayStatus {
+        let state = self.lock();
+        ReplayStatus {
+            paused: state.paused,
+            speed: state.speed,
+            position_secs: state.position_ms as f64 / 1_000.0,
+            duration_secs: state.duration_ms as f64 / 1_000.0,
+        }
+    }
+
+    // A poisoned lock only means a command panicked mid-update; the plain
+    // flags inside are still usable, so recover rather than abort playback.
+    fn lock(&self) -> MutexGuard<'_, ReplayControlState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+// One recorded row, positioned relative to the first row of the file.
+struct ReplayRow {
+    offset_ms: u64,
+    packet: EegPacket,
+}
+
+/// EEG source that re-emits the rows of a recorder CSV as live packets,
+/// honouring the original row spacing at the selected speed.
+pub struct CsvReplaySource {
+    csv_path: PathBuf,
+    control: ReplayControl,
+    rows: Vec<ReplayRow>,
+    cursor: usize,
+    seen_revision: Option<u64>,
+    // Playback clock: `anchor_offset_ms` of the recording is due at `anchor_instant`.
+    anchor_instant: Instant,
+    anchor_offset_ms: u64,
+}
+
+impl CsvReplaySource {
+    pub fn new(csv_path: PathBuf, control: ReplayControl) -> Self {
+        Self {
+            csv_path,
+            control,
+            rows: Vec::new(),
+            cursor: 0,
+            seen_revision: None,
+            anchor_instant: Instant::now(),
+            anchor_offset_ms: 0,
+        }
+    }
+
+    // To apply any pause/seek/speed change issued since the last poll and
+    // restart the playback clock from the current row.
+    fn sync_with_control(&mut self) -> (bool, ReplaySpeed) {
+        let mut state = self.control.lock();
+        if let Some(seek_ms) = state.pending_seek_ms.take() {
+            self.cursor = self.rows.partition_point(|row| row.offset_ms < seek_ms);
+        }
+        if self.seen_revision != Some(state.revision) {
+            self.seen_revision = Some(state.revision);
+            self.anchor_instant = Instant::now();
+            self.anchor_offset_ms = self.current_offset_ms();
+        }
+        state.position_ms = self.current_offset_ms();
+        (state.paused, state.speed)
+    }
+
+    fn current_offset_ms(&self) -> u64 {
+        self.rows
+            .get(self.cursor)
+            .or(self.rows.last())
+            .map(|row| row.offset_ms)
+            .unwrap_or(0)
+    }
+}
+
+impl EegSource for CsvReplaySource {
+    fn start(&mut self) -> Result<LinkInfo, AppError> {
+        let content = std::fs::read_to_string(&self.csv_path).map_err(|error| {
+            AppError::AcquisitionFailure(format!("cannot read replay CSV: {error}"))
+        })?;
+        self.rows = parse_recorded_rows(&content)?;
+        self.cursor = 0;
+        self.control.lock().duration_ms = self.rows.last().map(|row| row.offset_ms).unwrap_or(0);
+        Ok(LinkInfo {
+            port: self.csv_path.display().to_string(),
+            baud: None,
+        })
+    }
+
+    fn next_event(&mut self) -> Result<SourceEvent, AppError> {
+        let (paused, speed) = self.sync_with_control();
+        if paused {
+            std::thread::sleep(REPLAY_POLL_INTERVAL);
+            return Ok(SourceEvent::Paused);
+        }
+        let Some(row) = self.rows.get(self.cursor) else {
+            return Ok(SourceEvent::Finished);
+        };
+
+        if let Some(multiplier) = speed.multiplier() {
+            let recorded_gap = row.offset_ms.saturating_sub(self.anchor_offset_ms) as f64;
+            let due_at =
+                self.anchor_instant + Duration::from_secs_f64(recorded_gap / multiplier / 1_000.0);
+            let wait = due_at.saturating_duration_since(Instant::now());
+            if !wait.is_zero() {
+                std::thread::sleep(wait.min(REPLAY_POLL_INTERVAL));
+                return Ok(SourceEvent::Idle);
+            }
+        }
+
+        let packet = row.packet.clone();
+        self.cursor += 1;
+        Ok(SourceEvent::Reading(EegReading::Packet(packet)))
+    }
+
+    fn stop(&mut self) {
+        self.rows.clear();
+    }
+}
+
+// To lay the rows of a recorder CSV out on the replay timeline. Parsing is
+// shared with the reindex, so replay accepts exactly the files it does.
+fn parse_recorded_rows(content: &str) -> Result<Vec<ReplayRow>, AppError> {
+    let recorded_rows = parse_session_csv(content)
+        .map_err(|error| AppError::AcquisitionFailure(format!("cannot replay CSV: {error}")))?;
+    let origin_ms = recorded_rows
+        .first()
+        .map_or(0, |row| row.timing.received_at_unix_ms);
+    let mut rows: Vec<ReplayRow> = Vec::with_capacity(recorded_rows.len());
+    for recorded_row in recorded_rows {
+        let previous_offset_ms = rows.last().map_or(0, |row| row.offset_ms);
+        // Clamp so a clock step backwards never reorders playback.
+        let offset_ms = recorded_row
+            .timing
+            .received_at_unix_ms
+            .saturating_sub(origin_ms)
+            .max(previous_offset_ms);
+        rows.push(ReplayRow {
+            offset_ms,
+            packet: recorded_row.packet,
+        });
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "timestamp,delta,theta,lowAlpha,highAlpha,lowBeta,highBeta,\
+                          lowGamma,midGamma,attention,meditation,poorSignalLevel,focusLabel";
+
+    #[test]
+    fn rows_are_offset_from_the_first_and_never_go_backwards() {
+        let content = format!(
+            "{HEADER}\n\
+             2024-03-01T10:00:00.000Z,1,2,3,4,5,6,7,8,50,40,0,1\n\
+             not-a-time,1,2,3,4,5,6,7,8,50,40,0,1\n\
+             2024-03-01T10:00:01.500Z,1,2,3,4,5,6,7,8,51,40,0,1\n\
+             2024-03-01T10:00:01.000Z,1,2,3,4,5,6,7,8,52,40,0,0\n"
+        );
+        let rows = parse_recorded_rows(&content).unwrap();
+
+        let offsets: Vec<u64> = rows.iter().map(|row| row.offset_ms).collect();
+        assert_eq!(offsets, vec![0, 1_500, 1_500]);
+        assert_eq!(rows[2].packet.attention, 52);
+    }
+
+    #[test]
+    fn files_that_are_not_recorder_csvs_do_not_replay() {
+        let error = parse_recorded_rows("timestamp,delta\n").err().unwrap();
+        assert!(matches!(error, AppError::AcquisitionFailure(_)));
+    }
+}
@@ -0,0 +1,266 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    mpsc, Arc, Mutex, MutexGuard,
+};
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::domain::{
+    device_command::{DeviceCommand, DeviceCommandAck},
+    errors::AppError,
+};
+
+// Wire form of a request: the command fields plus the id its ack will echo.
+#[derive(Serialize)]
+struct CommandFrame<'a> {
+    id: u32,
+    #[serde(flatten)]
+    command: &'a DeviceCommand,
+}
+
+struct ChannelState {
+    writer: Mutex<Option<Box<dyn Write + Send>>>,
+    next_id: AtomicU32,
+    pending: Mutex<HashMap<u32, mpsc::Sender<DeviceCommandAck>>>,
+}
+
+/// Shared handle through which Tauri commands talk to the bridge firmware
+/// while the reader thread owns the port. The source attaches a write handle
+/// whenever the link opens and hands every non-data line to `resolve_ack`;
+/// `send` blocks the caller until the matching ack arrives. Cloning yields
+/// another handle to the same channel.
+#[derive(Clone)]
+pub struct DeviceCommandChannel {
+    state: Arc<ChannelState>,
+}
+
+impl Default for DeviceCommandChannel {
+    fn default() -> Self {
+        Self {
+            state: Arc::new(ChannelState {
+                writer: Mutex::new(None),
+                next_id: AtomicU32::new(1),
+                pending: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+}
+
+impl DeviceCommandChannel {
+    /// To route commands to a freshly opened link.
+    pub fn attach(&self, writer: Box<dyn Write + Send>) {
+        *lock(&self.state.writer) = Some(writer);
+    }
+
+    /// To drop the write handle of a closed link. Commands still waiting for
+    /// an ack fail at once instead of running into their timeout.
+    pub fn detach(&self) {
+        *lock(&self.state.writer) = None;
+        lock(&self.state.pending).clear();
+    }
+
+    /// To write one command and wait up to `timeout` for its ack. Returns the
+    /// ack's `result`, or `null` for commands that report nothing.
+    pub fn send(
+        &self,
+        command: &DeviceCommand,
+        timeout: Duration,
+    ) -> Result<serde_json::Value, AppError> {
+        let id = self.state.next_id.fetch_add(1, Ordering::Relaxed);
+        let mut frame = serde_json::to_vec(&CommandFrame { id, command })
+            .map_err(|error| AppError::SerializationFailure(error.to_string()))?;
+        frame.push(b'\n');
+
+        let (ack_sender, ack_receiver) = mpsc::channel();
+        // Registered before writing so an ack cannot outrun its waiter.
+        lock(&self.state.pending).insert(id, ack_sender);
+        if let Err(error) = self.write_frame(&frame) {
+            lock(&self.state.pending).remove(&id);
+            return Err(error);
+        }
+
+        let ack = match ack_receiver.recv_timeout(timeout) {
+            Ok(ack) => ack,
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                lock(&self.state.pending).remove(&id);
+                return Err(AppError::DeviceCommandFailure(format!(
+                    "no ack for command {id} within {} ms",
+                    timeout.as_millis()
+                )));
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                return Err(AppError::DeviceCommandFailure(
+                    "the link closed before the command was acknowledged".to_string(),
+                ));
+            }
+        };
+        if !ack.ok {
+            let reason = ack
+                .error
+                .unwrap_or_else(|| "the bridge rejected the command".to_string());
+            return Err(AppError::DeviceCommandFailure(reason));
+        }
+        Ok(ack.result.unwrap_or(serde_json::Value::Null))
+    }
+
+    /// To deliver `line` to the command waiting for it, if it is an ack.
+    /// Returns false for anything else, including acks nobody waits for.
+    pub fn resolve_ack(&self, line: &str) -> bool {
+        let Ok(ack) = serde_json::from_str::<DeviceCommandAck>(line) else {
+            return false;
+        };
+        match lock(&self.state.pending).remove(&ack.id) {
+            Some(ack_sender) => ack_sender.send(ack).is_ok(),
+            None => false,
+        }
+    }
+
+    fn write_frame(&self, frame: &[u8]) -> Result<(), AppError> {
+        let mut writer = lock(&self.state.writer);
+        let writer = writer.as_mut().ok_or_else(|| {
+            AppError::DeviceCommandFailure("the bridge is not connected".to_string())
+        })?;
+        writer
+            .write_all(frame)
+            .and_then(|()| writer.flush())
+            .map_err(|error| AppError::DeviceCommandFailure(format!("write failed: {error}")))
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Stands in for the serial port: every written frame goes to the fake
+    // firmware on the other end of the channel.
+    struct FrameWriter(mpsc::Sender<Vec<u8>>);
+
+    impl Write for FrameWriter {
+        fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+            self.0
+                .send(bytes.to_vec())
+                .map_err(|_| std::io::ErrorKind::BrokenPipe)?;
+            Ok(bytes.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn attached_channel() -> (DeviceCommandChannel, mpsc::Receiver<Vec<u8>>) {
+        let (frame_sender, frames) = mpsc::channel();
+        let channel = DeviceCommandChannel::default();
+        channel.attach(Box::new(FrameWriter(frame_sender)));
+        (channel, frames)
+    }
+
+    fn next_frame(frames: &mpsc::Receiver<Vec<u8>>) -> serde_json::Value {
+        let frame = frames.recv_timeout(TIMEOUT).expect("no command written");
+        assert_eq!(frame.last(), Some(&b'\n'));
+        serde_json::from_slice(&frame).unwrap()
+    }
+
+    #[test]
+    fn ack_with_the_matching_id_resolves_the_command() {
+        let (channel, frames) = attached_channel();
+        std::thread::scope(|scope| {
+            let firmware_channel = &channel;
+            let firmware = scope.spawn(move || {
+                let frame = next_frame(&frames);
+                assert_eq!(frame["cmd"], "getFirmwareVersion");
+                let id = frame["id"].as_u64().unwrap();
+                // Data, other JSON and stray acks are not this command's ack.
+                assert!(!firmware_channel.resolve_ack(r#"{"delta":1}"#));
+                assert!(
+                    !firmware_channel.resolve_ack(&format!(r#"{{"ack":{},"ok":true}}"#, id + 1))
+                );
+                assert!(firmware_channel.resolve_ack(&format!(
+                    r#"{{"ack":{id},"ok":true,"result":{{"version":"1.4.0"}}}}"#
+                )));
+            });
+            let result = channel.send(&DeviceCommand::GetFirmwareVersion, TIMEOUT);
+            firmware.join().unwrap();
+            assert_eq!(result.unwrap(), serde_json::json!({ "version": "1.4.0" }));
+        });
+    }
+
+    #[test]
+    fn rejected_command_reports_the_firmware_error() {
+        let (channel, frames) = attached_channel();
+        std::thread::scope(|scope| {
+            let firmware_channel = &channel;
+            scope.spawn(move || {
+                let id = next_frame(&frames)["id"].as_u64().unwrap();
+                firmware_channel
+                    .resolve_ack(&format!(r#"{{"ack":{id},"ok":false,"error":"busy"}}"#));
+            });
+            let error = channel
+                .send(&DeviceCommand::ResetPairing, TIMEOUT)
+                .unwrap_err();
+            assert_eq!(error.to_string(), "Device command error: busy");
+        });
+    }
+
+    #[test]
+    fn acks_arriving_out_of_order_reach_their_own_commands() {
+        let (channel, frames) = attached_channel();
+        std::thread::scope(|scope| {
+            let rate =
+                scope.spawn(|| channel.send(&DeviceCommand::SetOutputRate { hz: 2 }, TIMEOUT));
+            let raw = scope
+                .spawn(|| channel.send(&DeviceCommand::SetRawOutput { enabled: false }, TIMEOUT));
+            let mut written = [next_frame(&frames), next_frame(&frames)];
+            // Answer the later command first, echoing each command back.
+            written.sort_by_key(|frame| std::cmp::Reverse(frame["id"].as_u64()));
+            for frame in &written {
+                let ack =
+                    serde_json::json!({ "ack": frame["id"], "ok": true, "result": frame["cmd"] });
+                assert!(channel.resolve_ack(&ack.to_string()));
+            }
+            assert_eq!(rate.join().unwrap().unwrap(), "setOutputRate");
+            assert_eq!(raw.join().unwrap().unwrap(), "setRawOutput");
+        });
+    }
+
+    #[test]
+    fn unanswered_command_times_out_and_its_late_ack_is_ignored() {
+        let (channel, frames) = attached_channel();
+        let error = channel
+            .send(
+                &DeviceCommand::GetFirmwareVersion,
+                Duration::from_millis(50),
+            )
+            .unwrap_err();
+        assert!(
+            matches!(error, AppError::DeviceCommandFailure(reason) if reason.starts_with("no ack"))
+        );
+
+        let id = next_frame(&frames)["id"].as_u64().unwrap();
+        assert!(!channel.resolve_ack(&format!(r#"{{"ack":{id},"ok":true}}"#)));
+    }
+
+    #[test]
+    fn commands_fail_at_once_without_a_link() {
+        let (channel, _frames) = attached_channel();
+        channel.detach();
+        let error = channel
+            .send(&DeviceCommand::GetFirmwareVersion, TIMEOUT)
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Device command error: the bridge is not connected"
+        );
+    }
+}
@@ -1,10 +1,22 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
 use serde::Deserialize;
+use serde_json::Value;
 
-use crate::domain::eeg_packet::EegPacket;
+use crate::domain::{
+    eeg_packet::EegPacket,
+    eeg_reading::EegReading,
+    field_mapping::FieldMapping,
+    link_diagnostics::{LineKind, LinkDiagnostics},
+    raw_eeg_sample::RawEegSample,
+};
 
 // Flat JSON packet emitted by the ESP32 sketch (~1 Hz for band-power packets,
 // 512 Hz for raw-only packets). Band-power fields are optional because raw
-// packets omit them — parse_esp32_line returns None for those.
+// packets omit them and carry only the `raw` waveform sample. `millis`, `seq`,
+// `blinkStrength` and the `ext` object of firmware-specific numbers are only
+// sent by newer sketches; a `blinkStrength` line without bands is a blink event.
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct Esp32JsonPacket {
@@ -19,16 +31,179 @@ struct Esp32JsonPacket {
     high_beta: Option<u32>,
     low_gamma: Option<u32>,
     mid_gamma: Option<u32>,
+    raw: Option<i16>,
+    millis: Option<u64>,
+    seq: Option<u32>,
+    blink_strength: Option<u8>,
+    #[serde(default)]
+    ext: BTreeMap<String, Value>,
+}
+
+/// What one line from an ESP32 link held, once decoded and tallied.
+pub enum Esp32Line {
+    /// One or two readings: a line may carry a band-power packet and a raw
+    /// sample at once, in that order.
+    Readings(Vec<EegReading>),
+    /// Well-formed JSON that is not EEG data, trimmed; command acks arrive so.
+    Other(String),
+    /// Blank lines and noise.
+    Ignored,
 }
 
-/// To deserialize a raw ESP32 JSON line into a complete EegPacket.
-/// Returns None for 512 Hz raw-only packets that omit band-power fields.
-pub fn parse_esp32_line(line: &str) -> Option<EegPacket> {
+/// To deserialize a raw ESP32 JSON line into a complete EegPacket, a single
+/// raw waveform sample, or both when the line carries both. Returns nothing
+/// for empty lines, boot noise and partial packets that carry neither.
+pub fn parse_esp32_line(line: &str) -> Vec<EegReading> {
     if line.is_empty() {
-        return None;
+        return Vec::new();
     }
-    let packet: Esp32JsonPacket = serde_json::from_str(line).ok()?;
+    serde_json::from_str(line)
+        .map(|packet| readings_from(&packet))
+        .unwrap_or_default()
+}
+
+/// To decode one line exactly as read from the link, terminator included,
+/// through `field_mapping`, and tally it in `diagnostics`. Bytes are decoded
+/// lossily so that noise becomes placeholders JSON parsing rejects, and the
+/// line is counted as lossy when that happened.
+pub fn decode_esp32_line(
+    raw_line: &[u8],
+    field_mapping: &FieldMapping,
+    diagnostics: &LinkDiagnostics,
+) -> Esp32Line {
+    let text = String::from_utf8_lossy(raw_line);
+    let line = text.trim();
+    let (decoded, kind) = if line.is_empty() {
+        (Esp32Line::Ignored, LineKind::Blank)
+    } else {
+        match parse_mapped_packet(line, field_mapping) {
+            Err(_) => (Esp32Line::Ignored, LineKind::Malformed),
+            Ok(packet) => {
+                let readings = readings_from(&packet);
+                match readings.first() {
+                    Some(EegReading::Packet(_)) => {
+                        (Esp32Line::Readings(readings), LineKind::Packet)
+                    }
+                    Some(EegReading::Raw(_)) => (Esp32Line::Readings(readings), LineKind::RawOnly),
+                    Some(EegReading::Blink(_)) => (Esp32Line::Readings(readings), LineKind::Blink),
+                    None => (Esp32Line::Other(line.to_string()), LineKind::Other),
+                }
+            }
+        }
+    };
+    diagnostics.record_line(raw_line.len(), matches!(text, Cow::Owned(_)), kind);
+    decoded
+}
+
+// To read a line as the standard schema, first rewriting it through the
+// mapping when the firmware speaks a different one.
+fn parse_mapped_packet(
+    line: &str,
+    field_mapping: &FieldMapping,
+) -> serde_json::Result<Esp32JsonPacket> {
+    if field_mapping.is_identity() {
+        return serde_json::from_str(line);
+    }
+    let mut value: Value = serde_json::from_str(line)?;
+    if let Value::Object(object) = &mut value {
+        apply_field_mapping(object, field_mapping);
+    }
+    serde_json::from_value(value)
+}
+
+// Standard fields that make a line a band-power packet.
+const BAND_FIELDS: [&str; 8] = [
+    "delta",
+    "theta",
+    "lowAlpha",
+    "highAlpha",
+    "lowBeta",
+    "highBeta",
+    "lowGamma",
+    "midGamma",
+];
+
+// To set every mapped standard field from its path, scaled and offset, or
+// from the rule's default. Defaults only fill gaps in a band-power line, so
+// raw-only, blink and ack lines are never turned into packets. Unmapped keys
+// stay as they are, so standard fields without a rule still parse. Values are
+// read before any key is overwritten, so rules may swap keys.
+fn apply_field_mapping(object: &mut serde_json::Map<String, Value>, field_mapping: &FieldMapping) {
+    let carries_bands = BAND_FIELDS.iter().any(|field| {
+        let path = field_mapping
+            .fields
+            .get(*field)
+            .map_or(*field, |rule| rule.path.as_str());
+        lookup_path(object, path).is_some()
+    });
+    let mapped: Vec<(&String, Option<Value>)> = field_mapping
+        .fields
+        .iter()
+        .map(|(field, rule)| {
+            let found = lookup_path(object, &rule.path);
+            let value = if field == "ext" {
+                found.filter(|value| value.is_object()).cloned()
+            } else {
+                found
+                    .and_then(numeric_value)
+                    .map(|number| number * rule.scale + rule.offset)
+                    .or(rule.default.filter(|_| carries_bands))
+                    .map(integer_value)
+            };
+            (field, value)
+        })
+        .collect();
+    for (field, value) in mapped {
+        match value {
+            Some(value) => object.insert(field.clone(), value),
+            None => object.remove(field),
+        };
+    }
+}
+
+fn lookup_path<'a>(object: &'a serde_json::Map<String, Value>, path: &str) -> Option<&'a Value> {
+    let mut segments = path.split('.');
+    let mut current = object.get(segments.next()?)?;
+    for segment in segments {
+        current = current.get(segment)?;
+    }
+    Some(current)
+}
+
+// Some sketches send numbers as strings.
+fn numeric_value(value: &Value) -> Option<f64> {
+    value
+        .as_f64()
+        .or_else(|| value.as_str()?.trim().parse().ok())
+}
+
+// Every standard numeric field is an integer; values outside a field's range
+// fail the typed parse and count as malformed lines.
+fn integer_value(number: f64) -> Value {
+    Value::from(number.round() as i64)
+}
 
+// A band-power packet carries its own blink strength, so a blink reading is
+// only produced for lines without bands.
+fn readings_from(packet: &Esp32JsonPacket) -> Vec<EegReading> {
+    let band_power_packet = build_band_power_packet(packet);
+    let blink = packet
+        .blink_strength
+        .filter(|_| band_power_packet.is_none());
+    band_power_packet
+        .map(EegReading::Packet)
+        .into_iter()
+        .chain(
+            packet
+                .raw
+                .map(|value| EegReading::Raw(RawEegSample { value })),
+        )
+        .chain(blink.map(EegReading::Blink))
+        .collect()
+}
+
+// To assemble the ~1 Hz packet only when all eight band powers are present.
+fn build_band_power_packet(packet: &Esp32JsonPacket) -> Option<EegPacket> {
     Some(EegPacket {
         delta: packet.delta?,
         theta: packet.theta?,
@@ -41,5 +216,115 @@ pub fn parse_esp32_line(line: &str) -> Option<EegPacket> {
         attention: packet.attention.unwrap_or(0),
         meditation: packet.meditation.unwrap_or(0),
         poor_signal_level: packet.poor_signal.unwrap_or(200),
+        device_millis: packet.millis,
+        device_seq: packet.seq,
+        blink_strength: packet.blink_strength,
+        // Non-numeric extension values have no CSV column to land in.
+        extensions: packet
+            .ext
+            .iter()
+            .filter_map(|(name, value)| Some((name.clone(), value.as_f64()?)))
+            .collect(),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BANDS: &str = r#""delta":1,"theta":2,"lowAlpha":3,"highAlpha":4,"lowBeta":5,"highBeta":6,"lowGamma":7,"midGamma":8"#;
+
+    #[test]
+    fn line_with_bands_and_raw_yields_both_readings() {
+        let line = format!(r#"{{{BANDS},"poorSignal":0,"raw":-42}}"#);
+        let readings = parse_esp32_line(&line);
+        assert!(matches!(
+            readings.as_slice(),
+            [
+                EegReading::Packet(_),
+                EegReading::Raw(RawEegSample { value: -42 })
+            ]
+        ));
+    }
+
+    #[test]
+    fn raw_only_line_yields_one_sample() {
+        let readings = parse_esp32_line(r#"{"raw":17}"#);
+        assert!(matches!(
+            readings.as_slice(),
+            [EegReading::Raw(RawEegSample { value: 17 })]
+        ));
+    }
+
+    #[test]
+    fn blink_rides_on_the_packet_when_bands_are_present() {
+        let line = format!(r#"{{{BANDS},"blinkStrength":90}}"#);
+        match parse_esp32_line(&line).as_slice() {
+            [EegReading::Packet(packet)] => assert_eq!(packet.blink_strength, Some(90)),
+            other => panic!("expected a lone packet, got {other:?}"),
+        }
+        assert!(matches!(
+            parse_esp32_line(r#"{"blinkStrength":90}"#).as_slice(),
+            [EegReading::Blink(90)]
+        ));
+    }
+
+    // Firmware that nests its bands and leaves the signal fields to defaults.
+    fn defaulting_mapping() -> FieldMapping {
+        serde_json::from_str(
+            r#"{ "fields": {
+                "delta": { "path": "eeg.delta", "default": 0 },
+                "theta": { "path": "eeg.theta", "default": 0 },
+                "poorSignal": { "path": "signal", "default": 0 },
+                "attention": { "path": "att", "default": 0 },
+                "raw": { "path": "sample" }
+            } }"#,
+        )
+        .unwrap()
+    }
+
+    fn decode(line: &str) -> Esp32Line {
+        let diagnostics = LinkDiagnostics::new(std::time::Instant::now());
+        decode_esp32_line(line.as_bytes(), &defaulting_mapping(), &diagnostics)
+    }
+
+    #[test]
+    fn mapping_defaults_fill_gaps_in_band_lines() {
+        let line = format!(r#"{{"eeg":{{"delta":5}},{BANDS}}}"#);
+        match decode(&line) {
+            Esp32Line::Readings(readings) => match readings.as_slice() {
+                [EegReading::Packet(packet)] => {
+                    assert_eq!((packet.delta, packet.theta), (5, 0));
+                    assert_eq!((packet.poor_signal_level, packet.attention), (0, 0));
+                }
+                other => panic!("expected one packet, got {other:?}"),
+            },
+            _ => panic!("band line did not decode"),
+        }
+    }
+
+    #[test]
+    fn mapping_defaults_leave_other_lines_alone() {
+        match decode(r#"{"sample":-3}"#) {
+            Esp32Line::Readings(readings) => {
+                assert_eq!(readings, vec![EegReading::Raw(RawEegSample { value: -3 })])
+            }
+            _ => panic!("raw line did not decode"),
+        }
+        match decode(r#"{"blinkStrength":80}"#) {
+            Esp32Line::Readings(readings) => assert_eq!(readings, vec![EegReading::Blink(80)]),
+            _ => panic!("blink line did not decode"),
+        }
+        match decode(r#"{"ack":3,"ok":true}"#) {
+            Esp32Line::Other(line) => assert_eq!(line, r#"{"ack":3,"ok":true}"#),
+            _ => panic!("ack line did not reach the command channel"),
+        }
+    }
+
+    #[test]
+    fn noise_and_acks_yield_nothing() {
+        assert!(parse_esp32_line("").is_empty());
+        assert!(parse_esp32_line("ets Jun  8 2016 rst:0x1").is_empty());
+        assert!(parse_esp32_line(r#"{"ack":3,"ok":true}"#).is_empty());
+    }
+}
@@ -0,0 +1,119 @@
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::domain::{errors::AppError, ports::RecordingJournal, session_recording::JournalEntry};
+
+/// Extension of recording journal files in the recordings directory.
+pub const JOURNAL_EXTENSION: &str = "journal";
+
+/// Recording journal kept as JSON Lines, one entry per line. Every entry is
+/// written in one unbuffered write and synced to disk before `append`
+/// returns, so a crash or power cut loses at most the entry in flight.
+pub struct FileRecordingJournal {
+    path: PathBuf,
+    file: File,
+}
+
+impl FileRecordingJournal {
+    /// To create a new journal; fails rather than append to an existing one.
+    pub fn create(path: PathBuf) -> Result<Self, AppError> {
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .map_err(|error| {
+                AppError::StorageFailure(format!(
+                    "cannot create recording journal {}: {error}",
+                    path.display()
+                ))
+            })?;
+        Ok(Self { path, file })
+    }
+
+    /// To append to a journal left by an earlier run, e.g. to mark it as
+    /// exported after recovery.
+    pub fn reopen(path: PathBuf) -> Result<Self, AppError> {
+        let file = OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .map_err(|error| {
+                AppError::StorageFailure(format!(
+                    "cannot open recording journal {}: {error}",
+                    path.display()
+                ))
+            })?;
+        Ok(Self { path, file })
+    }
+}
+
+impl RecordingJournal for FileRecordingJournal {
+    fn append(&mut self, entry: &JournalEntry) -> Result<(), AppError> {
+        let mut line = serde_json::to_vec(entry)
+            .map_err(|error| AppError::SerializationFailure(error.to_string()))?;
+        line.push(b'\n');
+        self.file
+            .write_all(&line)
+            .and_then(|()| self.file.sync_data())
+            .map_err(|error| {
+                AppError::StorageFailure(format!(
+                    "cannot write recording journal {}: {error}",
+                    self.path.display()
+                ))
+            })
+    }
+}
+
+/// To read every complete entry of a journal, in order. A last line cut
+/// short by a crash is dropped rather than failing the whole journal.
+pub fn read_recording_journal(path: &Path) -> Result<Vec<JournalEntry>, AppError> {
+    let content = std::fs::read_to_string(path).map_err(|error| {
+        AppError::StorageFailure(format!(
+            "cannot read recording journal {}: {error}",
+            path.display()
+        ))
+    })?;
+    let mut lines = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .peekable();
+    let mut entries = Vec::new();
+    while let Some(line) = lines.next() {
+        match serde_json::from_str(line) {
+            Ok(entry) => entries.push(entry),
+            Err(_) if lines.peek().is_none() => break,
+            Err(error) => {
+                return Err(AppError::SerializationFailure(format!(
+                    "corrupt entry in recording journal {}: {error}",
+                    path.display()
+                )))
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// To list the journals in the recordings directory, oldest recording first.
+/// A directory that does not exist yet holds no journals.
+pub fn list_recording_journals(directory: &Path) -> Result<Vec<PathBuf>, AppError> {
+    let entries = match std::fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => {
+            return Err(AppError::StorageFailure(format!(
+                "cannot list recordings in {}: {error}",
+                directory.display()
+            )))
+        }
+    };
+    let mut journals: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == JOURNAL_EXTENSION)
+        })
+        .collect();
+    // Recording ids start with the start time, so name order is start order.
+    journals.sort();
+    Ok(journals)
+}
@@ -0,0 +1,41 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::domain::{errors::AppError, ports::SerialConfigRepository, serial_config::SerialConfig};
+
+/// Serial line settings per port, persisted as a JSON object keyed by port
+/// name so each headset bridge keeps its own baud rate and DTR behaviour.
+pub struct FileSerialConfigRepository {
+    store_path: PathBuf,
+}
+
+impl FileSerialConfigRepository {
+    pub fn new(store_path: PathBuf) -> Self {
+        Self { store_path }
+    }
+
+    fn load_all(&self) -> Result<BTreeMap<String, SerialConfig>, AppError> {
+        if !self.store_path.exists() {
+            return Ok(BTreeMap::new());
+        }
+        let raw = std::fs::read_to_string(&self.store_path)
+            .map_err(|error| AppError::StorageFailure(error.to_string()))?;
+        // A corrupt store only costs the remembered settings, never a session.
+        Ok(serde_json::from_str(&raw).unwrap_or_default())
+    }
+}
+
+impl SerialConfigRepository for FileSerialConfigRepository {
+    fn save(&mut self, port_name: &str, config: SerialConfig) -> Result<(), AppError> {
+        let mut configs = self.load_all()?;
+        configs.insert(port_name.to_string(), config);
+        let serialized = serde_json::to_string_pretty(&configs)
+            .map_err(|error| AppError::SerializationFailure(error.to_string()))?;
+        std::fs::write(&self.store_path, serialized)
+            .map_err(|error| AppError::StorageFailure(error.to_string()))
+    }
+
+    fn load(&self, port_name: &str) -> Result<Option<SerialConfig>, AppError> {
+        Ok(self.load_all()?.remove(port_name))
+    }
+}
@@ -23,10 +23,7 @@ impl SessionRepository for FileSessionRepository {
     fn save(&mut self, summary: SessionSummary) -> Result<(), AppError> {
         let mut registry = self.load_all()?;
         registry.push(summary);
-        let serialized = serde_json::to_string_pretty(&registry)
-            .map_err(|error| AppError::SerializationFailure(error.to_string()))?;
-        std::fs::write(&self.index_path, serialized)
-            .map_err(|error| AppError::StorageFailure(error.to_string()))
+        self.replace_all(registry)
     }
 
     fn load_all(&self) -> Result<Vec<SessionSummary>, AppError> {
@@ -39,4 +36,11 @@ impl SessionRepository for FileSessionRepository {
         // never prevents future sessions from being saved.
         Ok(serde_json::from_str(&raw).unwrap_or_default())
     }
+
+    fn replace_all(&mut self, summaries: Vec<SessionSummary>) -> Result<(), AppError> {
+        let serialized = serde_json::to_string_pretty(&summaries)
+            .map_err(|error| AppError::SerializationFailure(error.to_string()))?;
+        std::fs::write(&self.index_path, serialized)
+            .map_err(|error| AppError::StorageFailure(error.to_string()))
+    }
 }
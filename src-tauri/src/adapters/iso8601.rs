@@ -0,0 +1,115 @@
+// Minimal ISO 8601 handling for the timestamps the recorder writes
+// (`new Date().toISOString()`, e.g. `2025-03-14T09:26:53.589Z`), kept local to
+// avoid pulling in a date-time dependency for one format.
+
+/// To convert an ISO 8601 UTC timestamp into milliseconds since the Unix
+/// epoch. Accepts an optional fractional second and a `Z` or `±hh:mm` suffix.
+/// Returns None for anything that is not a well-formed timestamp.
+pub fn parse_iso8601_millis(text: &str) -> Option<i64> {
+    let text = text.trim();
+    let (date, time) = text.split_once(['T', ' '])?;
+
+    let mut date_parts = date.splitn(3, '-');
+    let year: i64 = date_parts.next()?.parse().ok()?;
+    let month: u32 = date_parts.next()?.parse().ok()?;
+    let day: u32 = date_parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let (clock, offset_minutes) = split_utc_offset(time)?;
+    let (hms, fraction) = match clock.split_once('.') {
+        Some((hms, fraction)) => (hms, fraction),
+        None => (clock, ""),
+    };
+    let mut hms_parts = hms.splitn(3, ':');
+    let hours: i64 = hms_parts.next()?.parse().ok()?;
+    let minutes: i64 = hms_parts.next()?.parse().ok()?;
+    let seconds: i64 = hms_parts.next().unwrap_or("0").parse().ok()?;
+    if hours > 23 || minutes > 59 || seconds > 60 {
+        return None;
+    }
+    let millis = parse_fraction_millis(fraction)?;
+
+    let days = days_from_civil(year, month, day);
+    let seconds_of_day = hours * 3_600 + minutes * 60 + seconds - offset_minutes * 60;
+    Some((days * 86_400 + seconds_of_day) * 1_000 + millis)
+}
+
+/// To format milliseconds since the Unix epoch the way the recorder always
+/// has, e.g. `2025-03-14T09:26:53.589Z`.
+pub fn format_iso8601_millis(unix_ms: i64) -> String {
+    let days = unix_ms.div_euclid(86_400_000);
+    let millis_of_day = unix_ms.rem_euclid(86_400_000);
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        millis_of_day / 3_600_000,
+        millis_of_day / 60_000 % 60,
+        millis_of_day / 1_000 % 60,
+        millis_of_day % 1_000
+    )
+}
+
+// To split `HH:MM:SS.fffZ` / `HH:MM:SS+02:00` into the clock part and the
+// offset from UTC in minutes. A missing suffix is treated as UTC.
+fn split_utc_offset(time: &str) -> Option<(&str, i64)> {
+    if let Some(clock) = time.strip_suffix('Z') {
+        return Some((clock, 0));
+    }
+    match time.rfind(['+', '-']) {
+        Some(index) => {
+            let (clock, offset) = time.split_at(index);
+            let sign = if offset.starts_with('-') { -1 } else { 1 };
+            let (offset_hours, offset_minutes) = offset[1..].split_once(':')?;
+            let offset_hours: i64 = offset_hours.parse().ok()?;
+            let offset_minutes: i64 = offset_minutes.parse().ok()?;
+            Some((clock, sign * (offset_hours * 60 + offset_minutes)))
+        }
+        None => Some((time, 0)),
+    }
+}
+
+// Fractions are truncated to millisecond precision ("5" -> 500, "5891" -> 589).
+fn parse_fraction_millis(fraction: &str) -> Option<i64> {
+    if fraction.is_empty() {
+        return Some(0);
+    }
+    if !fraction.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    let padded = format!("{fraction:0<3}");
+    padded[..3].parse().ok()
+}
+
+// Days since 1970-01-01 for a proleptic Gregorian date (Howard Hinnant's
+// days_from_civil algorithm).
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let shifted_month = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * shifted_month + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+// Inverse of days_from_civil: the proleptic Gregorian date of a day count.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
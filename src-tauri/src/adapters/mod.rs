@@ -1,3 +1,20 @@
+pub mod csv_replay_source;
+pub mod device_command_channel;
 pub mod esp32_packet_parser;
+pub mod file_recording_journal;
+pub mod file_serial_config_repository;
 pub mod file_session_repository;
+pub mod iso8601;
+pub mod network_eeg_source;
 pub mod onnx_inference_runner;
+pub mod reconnect_backoff;
+pub mod serial_byte_capture;
+pub mod serial_capture_replay_source;
+pub mod serial_eeg_source;
+pub mod serial_port_probe;
+pub mod session_csv;
+pub mod session_csv_scan;
+pub mod synthetic_eeg_source;
+pub mod thinkgear_connector_source;
+pub mod thinkgear_json_parser;
+pub mod thinkgear_packet_parser;
@@ -0,0 +1,381 @@
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader};
+use std::net::{TcpStream, ToSocketAddrs, UdpSocket};
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+use crate::{
+    adapters::{
+        esp32_packet_parser::{decode_esp32_line, Esp32Line},
+        reconnect_backoff::{ReconnectBackoff, ReconnectPolicy, ReconnectStep},
+    },
+    domain::{
+        connection_status::{ConnectionStatus, LinkInfo},
+        eeg_reading::EegReading,
+        errors::AppError,
+        field_mapping::FieldMapping,
+        link_diagnostics::LinkDiagnostics,
+        ports::EegSource,
+        source_event::SourceEvent,
+    },
+};
+
+// Same read timeout as the serial link, so stop requests are noticed promptly.
+const NETWORK_READ_TIMEOUT: Duration = Duration::from_millis(500);
+const TCP_CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+// Largest possible UDP payload; the ESP32 sends far smaller datagrams.
+const MAX_DATAGRAM_BYTES: usize = 65_507;
+
+/// How a WiFi-mode ESP32 delivers its newline-delimited JSON. `Tcp` connects
+/// out to the board's server at `address`; `Udp` binds `address` locally and
+/// accepts datagrams the board sends to it.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NetworkTransport {
+    #[default]
+    Tcp,
+    Udp,
+}
+
+enum NetworkLink {
+    Tcp(BufReader<TcpStream>),
+    Udp(UdpSocket),
+}
+
+/// EEG source fed over the network by an ESP32 in WiFi mode. Speaks the same
+/// JSON as the serial sketch and supervises the link the same way: a dropped
+/// connection is reopened with backoff rather than ending the source.
+pub struct NetworkEegSource {
+    transport: NetworkTransport,
+    address: String,
+    link: Option<NetworkLink>,
+    // Bytes of a TCP line that has not seen its newline yet; kept across read
+    // timeouts because WiFi bursts routinely split a line.
+    line_buffer: Vec<u8>,
+    datagram_buffer: Vec<u8>,
+    pending: VecDeque<EegReading>,
+    reconnect: ReconnectBackoff,
+    field_mapping: FieldMapping,
+    diagnostics: LinkDiagnostics,
+}
+
+impl NetworkEegSource {
+    pub fn new(
+        transport: NetworkTransport,
+        address: String,
+        reconnect_policy: ReconnectPolicy,
+        field_mapping: FieldMapping,
+    ) -> Self {
+        Self {
+            transport,
+            address,
+            link: None,
+            line_buffer: Vec::with_capacity(256),
+            datagram_buffer: vec![0; MAX_DATAGRAM_BYTES],
+            pending: VecDeque::new(),
+            reconnect: ReconnectBackoff::new(reconnect_policy),
+            field_mapping,
+            // Replaced by the session's counters in attach_link_diagnostics.
+            diagnostics: LinkDiagnostics::new(Instant::now()),
+        }
+    }
+
+    // To drop the dead link and schedule the first reopen attempt.
+    fn begin_reconnect(&mut self, reason: String) -> Result<SourceEvent, AppError> {
+        eprintln!("[IO] {reason}");
+        self.link = None;
+        self.line_buffer.clear();
+        let status = self
+            .reconnect
+            .begin(&self.address, reason, Instant::now())?;
+        Ok(SourceEvent::Status(status))
+    }
+
+    // To advance the reconnect backoff by one poll window.
+    fn poll_reconnect(&mut self) -> Result<SourceEvent, AppError> {
+        let address = &self.address;
+        let transport = self.transport;
+        let step = self.reconnect.poll(address, Instant::now(), || {
+            open_network_link(address, transport)
+        })?;
+        Ok(match step {
+            ReconnectStep::Waiting => SourceEvent::Idle,
+            ReconnectStep::Retrying(status) => SourceEvent::Status(status),
+            ReconnectStep::Reopened(link) => {
+                self.link = Some(link);
+                SourceEvent::Status(ConnectionStatus::Reconnected)
+            }
+        })
+    }
+}
+
+// To connect to (TCP) or bind (UDP) the configured address. Used for the
+// first connect and for every reconnect attempt.
+fn open_network_link(address: &str, transport: NetworkTransport) -> Result<NetworkLink, AppError> {
+    if address.is_empty() {
+        return Err(AppError::AcquisitionFailure(
+            "no network address was specified".to_string(),
+        ));
+    }
+    let open_error = |error: std::io::Error| {
+        AppError::AcquisitionFailure(format!("failed to open {address}: {error}"))
+    };
+    match transport {
+        NetworkTransport::Tcp => {
+            let socket_address = address
+                .to_socket_addrs()
+                .map_err(open_error)?
+                .next()
+                .ok_or_else(|| {
+                    AppError::AcquisitionFailure(format!("{address} did not resolve"))
+                })?;
+            let stream = TcpStream::connect_timeout(&socket_address, TCP_CONNECT_TIMEOUT)
+                .map_err(open_error)?;
+            stream
+                .set_read_timeout(Some(NETWORK_READ_TIMEOUT))
+                .map_err(open_error)?;
+            Ok(NetworkLink::Tcp(BufReader::new(stream)))
+        }
+        NetworkTransport::Udp => {
+            let socket = UdpSocket::bind(address).map_err(open_error)?;
+            socket
+                .set_read_timeout(Some(NETWORK_READ_TIMEOUT))
+                .map_err(open_error)?;
+            Ok(NetworkLink::Udp(socket))
+        }
+    }
+}
+
+impl EegSource for NetworkEegSource {
+    fn start(&mut self) -> Result<LinkInfo, AppError> {
+        self.link = Some(open_network_link(&self.address, self.transport)?);
+        let scheme = match self.transport {
+            NetworkTransport::Tcp => "tcp",
+            NetworkTransport::Udp => "udp",
+        };
+        Ok(LinkInfo {
+            port: format!("{scheme}://{}", self.address),
+            baud: None,
+        })
+    }
+
+    fn next_event(&mut self) -> Result<SourceEvent, AppError> {
+        if let Some(reading) = self.pending.pop_front() {
+            return Ok(SourceEvent::Reading(reading));
+        }
+        let Some(link) = self.link.as_mut() else {
+            return self.poll_reconnect();
+        };
+
+        let read_result = match link {
+            NetworkLink::Tcp(reader) => read_tcp_line(
+                reader,
+                &mut self.line_buffer,
+                &mut self.pending,
+                &self.field_mapping,
+                &self.diagnostics,
+            ),
+            NetworkLink::Udp(socket) => read_datagram(
+                socket,
+                &mut self.datagram_buffer,
+                &mut self.pending,
+                &self.field_mapping,
+                &self.diagnostics,
+            ),
+        };
+        match read_result {
+            Ok(0) if matches!(self.transport, NetworkTransport::Tcp) => {
+                self.begin_reconnect(format!("{} closed the connection", self.address))
+            }
+            Ok(_) => Ok(self
+                .pending
+                .pop_front()
+                .map(SourceEvent::Reading)
+                .unwrap_or(SourceEvent::Idle)),
+            Err(error)
+                if error.kind() == std::io::ErrorKind::TimedOut
+                    || error.kind() == std::io::ErrorKind::WouldBlock =>
+            {
+                Ok(SourceEvent::Idle)
+            }
+            Err(error) => {
+                self.begin_reconnect(format!("network read error on {}: {error}", self.address))
+            }
+        }
+    }
+
+    fn stop(&mut self) {
+        self.link = None;
+        self.line_buffer.clear();
+        self.reconnect.cancel();
+    }
+
+    fn attach_link_diagnostics(&mut self, diagnostics: LinkDiagnostics) {
+        self.diagnostics = diagnostics;
+    }
+}
+
+// To read until the next newline and parse the completed line. A timeout
+// leaves the partial line in `line_buffer` for the next call.
+fn read_tcp_line(
+    reader: &mut impl BufRead,
+    line_buffer: &mut Vec<u8>,
+    readings: &mut VecDeque<EegReading>,
+    field_mapping: &FieldMapping,
+    diagnostics: &LinkDiagnostics,
+) -> std::io::Result<usize> {
+    let bytes_read = reader.read_until(b'\n', line_buffer)?;
+    if line_buffer.ends_with(b"\n") {
+        if let Esp32Line::Readings(decoded) =
+            decode_esp32_line(line_buffer, field_mapping, diagnostics)
+        {
+            readings.extend(decoded);
+        }
+        line_buffer.clear();
+    }
+    Ok(bytes_read)
+}
+
+// To receive one datagram and parse every line in it; the ESP32 may batch
+// several readings into a single send.
+fn read_datagram(
+    socket: &UdpSocket,
+    datagram_buffer: &mut [u8],
+    readings: &mut VecDeque<EegReading>,
+    field_mapping: &FieldMapping,
+    diagnostics: &LinkDiagnostics,
+) -> std::io::Result<usize> {
+    let bytes_read = socket.recv(datagram_buffer)?;
+    for raw_line in datagram_buffer[..bytes_read].split_inclusive(|&byte| byte == b'\n') {
+        if let Esp32Line::Readings(decoded) =
+            decode_esp32_line(raw_line, field_mapping, diagnostics)
+        {
+            readings.extend(decoded);
+        }
+    }
+    Ok(bytes_read)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::net::TcpListener;
+
+    use super::*;
+    use crate::domain::raw_eeg_sample::RawEegSample;
+
+    const BAND_LINE: &str =
+        "{\"delta\":11,\"theta\":2,\"lowAlpha\":3,\"highAlpha\":4,\"lowBeta\":5,\
+                             \"highBeta\":6,\"lowGamma\":7,\"midGamma\":8,\"poorSignal\":0}\n";
+
+    fn source(transport: NetworkTransport, address: String) -> NetworkEegSource {
+        let policy = ReconnectPolicy {
+            max_attempts: 5,
+            initial_delay_ms: 10,
+            max_delay_ms: 50,
+        };
+        NetworkEegSource::new(transport, address, policy, FieldMapping::default())
+    }
+
+    // Polls until `count` readings have arrived, returning them with every
+    // status the source reported on the way.
+    fn collect(
+        source: &mut NetworkEegSource,
+        count: usize,
+    ) -> (Vec<EegReading>, Vec<ConnectionStatus>) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let (mut readings, mut statuses) = (Vec::new(), Vec::new());
+        while readings.len() < count {
+            assert!(Instant::now() < deadline, "timed out after {readings:?}");
+            match source.next_event().unwrap() {
+                SourceEvent::Reading(reading) => readings.push(reading),
+                SourceEvent::Status(status) => statuses.push(status),
+                SourceEvent::Idle => {}
+                other => panic!("unexpected event {other:?}"),
+            }
+        }
+        (readings, statuses)
+    }
+
+    fn raw_values(readings: &[EegReading]) -> Vec<i16> {
+        readings
+            .iter()
+            .filter_map(|reading| match reading {
+                EegReading::Raw(RawEegSample { value }) => Some(*value),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // To poll until the source reports a status, which the closed peer and the
+    // reconnect produce without any readings.
+    fn collect_status(source: &mut NetworkEegSource) -> ConnectionStatus {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            assert!(Instant::now() < deadline, "no status reported");
+            match source.next_event().unwrap() {
+                SourceEvent::Status(status) => return status,
+                SourceEvent::Idle => {}
+                other => panic!("unexpected event {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn tcp_lines_split_across_writes_decode_and_the_link_reconnects() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let mut source = source(NetworkTransport::Tcp, address.clone());
+        let link = source.start().unwrap();
+        assert_eq!(link.port, format!("tcp://{address}"));
+        let (mut peer, _) = listener.accept().unwrap();
+
+        peer.write_all(format!("{BAND_LINE}{{\"raw\":1}}\n{{\"ra").as_bytes())
+            .unwrap();
+        let (readings, _) = collect(&mut source, 2);
+        assert!(matches!(&readings[0], EegReading::Packet(packet) if packet.delta == 11));
+        assert_eq!(raw_values(&readings), vec![1]);
+        // The half line times out in the source's buffer until the rest arrives.
+        assert!(matches!(source.next_event().unwrap(), SourceEvent::Idle));
+        peer.write_all(b"w\":2}\n").unwrap();
+        assert_eq!(raw_values(&collect(&mut source, 1).0), vec![2]);
+
+        drop(peer);
+        let (mut peer, _) = std::thread::scope(|scope| {
+            let accepted = scope.spawn(|| listener.accept().unwrap());
+            let mut statuses = Vec::new();
+            while !statuses.contains(&ConnectionStatus::Reconnected) {
+                statuses.push(collect_status(&mut source));
+            }
+            assert!(matches!(statuses[0], ConnectionStatus::Reconnecting { .. }));
+            accepted.join().unwrap()
+        });
+        peer.write_all(b"{\"raw\":3}\n").unwrap();
+        assert_eq!(raw_values(&collect(&mut source, 1).0), vec![3]);
+    }
+
+    #[test]
+    fn udp_datagrams_carrying_several_lines_all_decode() {
+        // Reserve a free port, then let the source bind it as the board's target.
+        let address = UdpSocket::bind("127.0.0.1:0")
+            .and_then(|socket| socket.local_addr())
+            .unwrap()
+            .to_string();
+        let mut source = source(NetworkTransport::Udp, address.clone());
+        source.start().unwrap();
+
+        let board = UdpSocket::bind("127.0.0.1:0").unwrap();
+        board
+            .send_to(b"{\"raw\":-5}\n{\"raw\":6}\n", &address)
+            .unwrap();
+        board.send_to(BAND_LINE.as_bytes(), &address).unwrap();
+        // A trailing line without its newline still decodes; datagrams are whole.
+        board.send_to(b"{\"raw\":7}", &address).unwrap();
+
+        let (readings, statuses) = collect(&mut source, 4);
+        assert!(statuses.is_empty());
+        assert_eq!(raw_values(&readings), vec![-5, 6, 7]);
+        assert!(matches!(&readings[2], EegReading::Packet(packet) if packet.delta == 11));
+    }
+}
@@ -5,7 +5,7 @@ use serde::Deserialize;
 use crate::domain::{
     eeg_packet::EegPacket,
     errors::AppError,
-    focus_reading::{focus_label_name, FocusReading},
+    focus_reading::{focus_label_name, FocusReading, InferenceHistory},
     ports::InferenceRunner,
 };
 
@@ -28,14 +28,13 @@ pub struct ModelFilePaths {
     pub scaler_path: String,
 }
 
-/// Concrete inference runner backed by an ONNX runtime session.
-/// `prev_delta_relative` carries the previous packet's delta proportion so
-/// the temporal-Δdelta feature stays coherent across successive inference calls.
+/// Concrete inference runner backed by an ONNX runtime session. Holds no
+/// per-stream state: the previous packet's delta proportion behind the
+/// temporal-Δdelta feature arrives with each call.
 pub struct OnnxInferenceRunner {
     session: Session,
     mean: Vec<f32>,
     scale: Vec<f32>,
-    prev_delta_relative: f32,
 }
 
 impl OnnxInferenceRunner {
@@ -59,14 +58,17 @@ impl OnnxInferenceRunner {
             session,
             mean: params.mean_,
             scale: params.scale_,
-            prev_delta_relative: 0.0,
         })
     }
 }
 
 impl InferenceRunner for OnnxInferenceRunner {
-    fn predict(&mut self, packet: &EegPacket) -> Result<FocusReading, AppError> {
-        let features = extract_feature_vector(packet, &mut self.prev_delta_relative);
+    fn predict(
+        &mut self,
+        packet: &EegPacket,
+        history: &mut InferenceHistory,
+    ) -> Result<FocusReading, AppError> {
+        let features = extract_feature_vector(packet, &mut history.prev_delta_relative);
         let normalized = apply_notebook_scaler(&features, &self.mean, &self.scale);
         run_onnx_session(&mut self.session, normalized).map_err(AppError::InferenceFailure)
     }
@@ -107,7 +109,7 @@ fn extract_feature_vector(packet: &EegPacket, prev_delta_relative: &mut f32) ->
         band_relatives[7],
         ratio_feature(beta, theta), // Matches notebook: log1p(clip(β/θ, 0, 12))
         ratio_feature(alpha, beta), // Matches notebook: log1p(clip(α/β, 0, 12))
-        temporal_delta,         // Δdelta: stationarity marker across windows
+        temporal_delta,             // Δdelta: stationarity marker across windows
         packet.attention as f32,
         packet.meditation as f32,
     ]
@@ -0,0 +1,318 @@
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+use crate::domain::{connection_status::ConnectionStatus, errors::AppError};
+
+// Granularity of the backoff wait, so a stop request is honoured promptly
+// even while waiting out a multi-second delay.
+const BACKOFF_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Retry budget applied when an open link drops mid-session (EOF or a read
+/// error). Delays double after every failed reopen, capped at `max_delay_ms`.
+/// A `max_attempts` of 0 disables reconnection entirely.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ReconnectPolicy {
+    pub max_attempts: u32,
+    pub initial_delay_ms: u64,
+    pub max_delay_ms: u64,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        // ~1 minute of total retrying covers an ESP32 brown-out and re-enumeration.
+        Self {
+            max_attempts: 10,
+            initial_delay_ms: 250,
+            max_delay_ms: 8_000,
+        }
+    }
+}
+
+/// Outcome of one poll of an in-progress reconnect.
+pub enum ReconnectStep<T> {
+    /// Still inside the current backoff delay.
+    Waiting,
+    /// The attempt failed and another one has been scheduled; carries the
+    /// `Reconnecting` status to publish.
+    Retrying(ConnectionStatus),
+    /// The link is back.
+    Reopened(T),
+}
+
+// Progress through the retry budget after the link dropped.
+struct ReconnectAttempt {
+    attempt: u32,
+    delay: Duration,
+    next_attempt_at: Instant,
+    last_error: String,
+}
+
+/// Non-blocking exponential backoff shared by every source that owns a
+/// reopenable link. Each poll sleeps at most one short slice, so sources can
+/// keep returning `Idle` and the acquisition loop stays stoppable.
+pub struct ReconnectBackoff {
+    policy: ReconnectPolicy,
+    attempt: Option<ReconnectAttempt>,
+}
+
+impl ReconnectBackoff {
+    pub fn new(policy: ReconnectPolicy) -> Self {
+        Self {
+            policy,
+            attempt: None,
+        }
+    }
+
+    /// To schedule the first reopen attempt after `link_name` dropped for
+    /// `reason` at `now`, returning the `Reconnecting` status to publish.
+    /// Fails immediately when reconnection is disabled.
+    pub fn begin(
+        &mut self,
+        link_name: &str,
+        reason: String,
+        now: Instant,
+    ) -> Result<ConnectionStatus, AppError> {
+        if self.policy.max_attempts == 0 {
+            return Err(AppError::PortVanished(link_name.to_string()));
+        }
+        let delay = Duration::from_millis(self.policy.initial_delay_ms);
+        let attempt = ReconnectAttempt {
+            attempt: 1,
+            delay,
+            next_attempt_at: now + delay,
+            last_error: reason,
+        };
+        let status = reconnecting_status(&attempt, self.policy.max_attempts);
+        self.attempt = Some(attempt);
+        Ok(status)
+    }
+
+    /// To wait out the current delay in a short slice and, once it has
+    /// elapsed at `now`, call `reopen`. A failed attempt doubles the delay;
+    /// an exhausted budget returns the last error so the source ends.
+    pub fn poll<T>(
+        &mut self,
+        link_name: &str,
+        now: Instant,
+        reopen: impl FnOnce() -> Result<T, AppError>,
+    ) -> Result<ReconnectStep<T>, AppError> {
+        let Some(reconnect) = self.attempt.as_mut() else {
+            return Ok(ReconnectStep::Waiting);
+        };
+        let remaining = reconnect.next_attempt_at.saturating_duration_since(now);
+        if !remaining.is_zero() {
+            std::thread::sleep(remaining.min(BACKOFF_POLL_INTERVAL));
+            return Ok(ReconnectStep::Waiting);
+        }
+
+        eprintln!(
+            "[IO] Reconnecting to {link_name} (attempt {}/{})",
+            reconnect.attempt, self.policy.max_attempts
+        );
+        match reopen() {
+            Ok(link) => {
+                self.attempt = None;
+                Ok(ReconnectStep::Reopened(link))
+            }
+            Err(error) if reconnect.attempt >= self.policy.max_attempts => {
+                self.attempt = None;
+                Err(error)
+            }
+            Err(error) => {
+                eprintln!("[IO] {error}");
+                reconnect.attempt += 1;
+                reconnect.delay =
+                    (reconnect.delay * 2).min(Duration::from_millis(self.policy.max_delay_ms));
+                reconnect.next_attempt_at = now + reconnect.delay;
+                reconnect.last_error = error.to_string();
+                Ok(ReconnectStep::Retrying(reconnecting_status(
+                    reconnect,
+                    self.policy.max_attempts,
+                )))
+            }
+        }
+    }
+
+    /// To drop any reconnect in progress, e.g. when the source stops.
+    pub fn cancel(&mut self) {
+        self.attempt = None;
+    }
+}
+
+// To describe an in-progress reconnect for the UI, including why the link
+// dropped or why the previous attempt failed.
+fn reconnecting_status(reconnect: &ReconnectAttempt, max_attempts: u32) -> ConnectionStatus {
+    ConnectionStatus::Reconnecting {
+        attempt: reconnect.attempt,
+        max_attempts,
+        last_error: reconnect.last_error.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Backoff driven on a virtual clock starting at `start`.
+    struct Harness {
+        start: Instant,
+        backoff: ReconnectBackoff,
+        reopen_calls: u32,
+    }
+
+    impl Harness {
+        fn new(max_attempts: u32) -> Self {
+            Self {
+                start: Instant::now(),
+                backoff: ReconnectBackoff::new(ReconnectPolicy {
+                    max_attempts,
+                    initial_delay_ms: 100,
+                    max_delay_ms: 300,
+                }),
+                reopen_calls: 0,
+            }
+        }
+
+        fn at(&self, ms: u64) -> Instant {
+            self.start + Duration::from_millis(ms)
+        }
+
+        fn begin(&mut self) -> Result<ConnectionStatus, AppError> {
+            self.backoff.begin(
+                "COM3",
+                "serial port COM3 reached EOF".to_string(),
+                self.start,
+            )
+        }
+
+        // To poll at `ms` with a reopen that succeeds only when `reopens`.
+        fn poll(
+            &mut self,
+            ms: u64,
+            reopens: bool,
+        ) -> Result<ReconnectStep<&'static str>, AppError> {
+            let now = self.at(ms);
+            let reopen_calls = &mut self.reopen_calls;
+            self.backoff.poll("COM3", now, || {
+                *reopen_calls += 1;
+                if reopens {
+                    Ok("port")
+                } else {
+                    Err(AppError::PortBusy("COM3".to_string()))
+                }
+            })
+        }
+    }
+
+    fn attempt_of(step: Result<ReconnectStep<&'static str>, AppError>) -> u32 {
+        match step {
+            Ok(ReconnectStep::Retrying(ConnectionStatus::Reconnecting {
+                attempt,
+                max_attempts: 10,
+                ..
+            })) => attempt,
+            _ => panic!("expected a scheduled retry"),
+        }
+    }
+
+    #[test]
+    fn first_status_carries_the_drop_reason() {
+        let mut harness = Harness::new(10);
+        match harness.begin() {
+            Ok(ConnectionStatus::Reconnecting {
+                attempt: 1,
+                max_attempts: 10,
+                last_error,
+            }) => assert_eq!(last_error, "serial port COM3 reached EOF"),
+            _ => panic!("expected the first reconnecting status"),
+        }
+    }
+
+    #[test]
+    fn delay_doubles_from_initial_and_caps_at_max() {
+        let mut harness = Harness::new(10);
+        harness.begin().unwrap();
+        // Attempts fall due 100, 200, 300 and then 300 ms after each failure.
+        let mut due_ms = 0;
+        for (delay_ms, next_attempt) in [(100, 2), (200, 3), (300, 4), (300, 5)] {
+            due_ms += delay_ms;
+            assert!(matches!(
+                harness.poll(due_ms - 1, false),
+                Ok(ReconnectStep::Waiting)
+            ));
+            assert_eq!(attempt_of(harness.poll(due_ms, false)), next_attempt);
+        }
+        assert_eq!(harness.reopen_calls, 4);
+    }
+
+    #[test]
+    fn retry_status_reports_why_the_last_attempt_failed() {
+        let mut harness = Harness::new(10);
+        harness.begin().unwrap();
+        match harness.poll(100, false) {
+            Ok(ReconnectStep::Retrying(ConnectionStatus::Reconnecting { last_error, .. })) => {
+                assert_eq!(
+                    last_error,
+                    AppError::PortBusy("COM3".to_string()).to_string()
+                )
+            }
+            _ => panic!("expected a scheduled retry"),
+        }
+    }
+
+    #[test]
+    fn exhausted_budget_returns_the_last_error() {
+        let mut harness = Harness::new(2);
+        harness.begin().unwrap();
+        assert!(matches!(
+            harness.poll(100, false),
+            Ok(ReconnectStep::Retrying(_))
+        ));
+        assert!(matches!(
+            harness.poll(300, false),
+            Err(AppError::PortBusy(port)) if port == "COM3"
+        ));
+        // Nothing is left to retry.
+        assert!(matches!(
+            harness.poll(10_000, true),
+            Ok(ReconnectStep::Waiting)
+        ));
+        assert_eq!(harness.reopen_calls, 2);
+    }
+
+    #[test]
+    fn disabled_policy_fails_at_once() {
+        let mut harness = Harness::new(0);
+        assert!(matches!(
+            harness.begin(),
+            Err(AppError::PortVanished(port)) if port == "COM3"
+        ));
+    }
+
+    #[test]
+    fn reopening_and_cancel_reset_the_backoff() {
+        let mut harness = Harness::new(10);
+        harness.begin().unwrap();
+        harness.poll(100, false).unwrap();
+        assert!(matches!(
+            harness.poll(300, true),
+            Ok(ReconnectStep::Reopened("port"))
+        ));
+        // The next drop starts over from the initial delay.
+        harness.begin().unwrap();
+        assert!(matches!(
+            harness.poll(100, true),
+            Ok(ReconnectStep::Reopened("port"))
+        ));
+
+        harness.begin().unwrap();
+        harness.backoff.cancel();
+        assert!(matches!(
+            harness.poll(10_000, true),
+            Ok(ReconnectStep::Waiting)
+        ));
+        assert_eq!(harness.reopen_calls, 3);
+    }
+}
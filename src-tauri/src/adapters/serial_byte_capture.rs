@@ -0,0 +1,187 @@
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crate::{adapters::serial_eeg_source::WireFormat, domain::errors::AppError};
+
+// File layout, integers little-endian:
+//   header  magic, wire format (u8: 0 ESP32 JSON, 1 ThinkGear),
+//           capture start as Unix ms (u64), port read timeout in ms (u32)
+//   chunk   offset from capture start in ns (u64), length (u32), bytes
+// One chunk per successful read from the port, so replay reproduces the
+// original read boundaries as well as the bytes; the read timeout lets it
+// reproduce where reads timed out between chunks.
+const CAPTURE_MAGIC: &[u8; 8] = b"CRBYTES1";
+const HEADER_LEN: usize = 21;
+const CHUNK_HEADER_LEN: usize = 12;
+
+/// One read from the port as it was captured.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedChunk {
+    pub offset_ns: u64,
+    pub bytes: Vec<u8>,
+}
+
+/// A whole capture file, loaded for replay.
+#[derive(Debug, Clone)]
+pub struct SerialCapture {
+    pub wire_format: WireFormat,
+    pub started_at_unix_ms: u64,
+    pub read_timeout_ms: u32,
+    pub chunks: Vec<CapturedChunk>,
+}
+
+struct CaptureFile {
+    // None once a write failed; the session carries on uncaptured.
+    file: Option<File>,
+    path: String,
+    started: Instant,
+}
+
+/// Append-only binary log of every byte read from a serial port, with a
+/// timestamp per read. Kept across reconnects, so one file covers the whole
+/// session. Cloning yields another handle to the same file.
+#[derive(Clone)]
+pub struct SerialByteCapture {
+    state: Arc<Mutex<CaptureFile>>,
+}
+
+impl SerialByteCapture {
+    /// To create (or truncate) the capture file and write its header.
+    pub fn create(
+        path: &Path,
+        wire_format: WireFormat,
+        read_timeout_ms: u32,
+    ) -> Result<Self, AppError> {
+        let capture_error = |error: std::io::Error| {
+            AppError::AcquisitionFailure(format!(
+                "cannot create serial capture {}: {error}",
+                path.display()
+            ))
+        };
+        let mut file = File::create(path).map_err(capture_error)?;
+        let started_at_unix_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64);
+        let mut header = Vec::with_capacity(HEADER_LEN);
+        header.extend_from_slice(CAPTURE_MAGIC);
+        header.push(match wire_format {
+            WireFormat::Esp32Json => 0,
+            WireFormat::ThinkGear => 1,
+        });
+        header.extend_from_slice(&started_at_unix_ms.to_le_bytes());
+        header.extend_from_slice(&read_timeout_ms.to_le_bytes());
+        file.write_all(&header).map_err(capture_error)?;
+        Ok(Self {
+            state: Arc::new(Mutex::new(CaptureFile {
+                file: Some(file),
+                path: path.display().to_string(),
+                started: Instant::now(),
+            })),
+        })
+    }
+
+    /// To append one chunk read from the port. Each chunk goes out in a
+    /// single unbuffered write, so a crash loses at most the chunk in flight.
+    pub fn record(&self, bytes: &[u8]) {
+        let mut state = self.lock();
+        let offset_ns = state.started.elapsed().as_nanos() as u64;
+        let Some(file) = state.file.as_mut() else {
+            return;
+        };
+        let mut chunk = Vec::with_capacity(CHUNK_HEADER_LEN + bytes.len());
+        chunk.extend_from_slice(&offset_ns.to_le_bytes());
+        chunk.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+        chunk.extend_from_slice(bytes);
+        if let Err(error) = file.write_all(&chunk) {
+            eprintln!("[IO] serial capture {} stopped: {error}", state.path);
+            state.file = None;
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, CaptureFile> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Reader that hands every chunk it reads to a capture before returning it.
+pub struct CapturingReader<R> {
+    inner: R,
+    capture: SerialByteCapture,
+}
+
+impl<R> CapturingReader<R> {
+    pub fn new(inner: R, capture: SerialByteCapture) -> Self {
+        Self { inner, capture }
+    }
+}
+
+impl<R: Read> Read for CapturingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let bytes_read = self.inner.read(buf)?;
+        if bytes_read > 0 {
+            self.capture.record(&buf[..bytes_read]);
+        }
+        Ok(bytes_read)
+    }
+}
+
+/// To load a capture file for replay. A chunk cut short by a crash ends the
+/// capture rather than failing it.
+pub fn read_serial_capture(path: &Path) -> Result<SerialCapture, AppError> {
+    let content = std::fs::read(path).map_err(|error| {
+        AppError::AcquisitionFailure(format!(
+            "cannot read serial capture {}: {error}",
+            path.display()
+        ))
+    })?;
+    if content.len() < HEADER_LEN || &content[..CAPTURE_MAGIC.len()] != CAPTURE_MAGIC {
+        return Err(AppError::AcquisitionFailure(format!(
+            "{} is not a serial capture",
+            path.display()
+        )));
+    }
+    let wire_format = match content[8] {
+        0 => WireFormat::Esp32Json,
+        1 => WireFormat::ThinkGear,
+        other => {
+            return Err(AppError::AcquisitionFailure(format!(
+                "serial capture has unknown wire format {other}"
+            )))
+        }
+    };
+    let started_at_unix_ms = u64::from_le_bytes(content[9..17].try_into().unwrap_or_default());
+    let read_timeout_ms =
+        u32::from_le_bytes(content[17..HEADER_LEN].try_into().unwrap_or_default());
+
+    let mut chunks = Vec::new();
+    let mut cursor = HEADER_LEN;
+    while content.len() - cursor >= CHUNK_HEADER_LEN {
+        let offset_ns =
+            u64::from_le_bytes(content[cursor..cursor + 8].try_into().unwrap_or_default());
+        let length = u32::from_le_bytes(
+            content[cursor + 8..cursor + 12]
+                .try_into()
+                .unwrap_or_default(),
+        ) as usize;
+        let start = cursor + CHUNK_HEADER_LEN;
+        let Some(bytes) = content.get(start..start + length) else {
+            break;
+        };
+        chunks.push(CapturedChunk {
+            offset_ns,
+            bytes: bytes.to_vec(),
+        });
+        cursor = start + length;
+    }
+    Ok(SerialCapture {
+        wire_format,
+        started_at_unix_ms,
+        read_timeout_ms,
+        chunks,
+    })
+}
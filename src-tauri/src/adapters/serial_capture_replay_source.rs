@@ -0,0 +1,256 @@
+use std::collections::VecDeque;
+use std::io::{BufReader, Read};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::{
+    adapters::{
+        csv_replay_source::ReplaySpeed,
+        serial_byte_capture::{read_serial_capture, CapturedChunk},
+        serial_eeg_source::SerialStreamDecoder,
+    },
+    domain::{
+        connection_status::LinkInfo, errors::AppError, field_mapping::FieldMapping,
+        link_diagnostics::LinkDiagnostics, ports::EegSource, source_event::SourceEvent,
+    },
+};
+
+// Hands captured chunks back one read at a time, on a virtual clock in
+// recorded time. A read that would have waited longer than the captured read
+// timeout times out at the same point it did live, so a line split across a
+// timeout is decoded (or lost) exactly as it was.
+struct CapturedChunkReader {
+    chunks: VecDeque<CapturedChunk>,
+    read_timeout_ns: u64,
+    multiplier: Option<f64>,
+    started: Instant,
+    // Recorded time at which the next read begins.
+    clock_ns: u64,
+}
+
+impl CapturedChunkReader {
+    // To sleep until `offset_ns` of the recording is due at the replay speed.
+    fn wait_until(&self, offset_ns: u64) {
+        let Some(multiplier) = self.multiplier else {
+            return;
+        };
+        let due_at = self.started + Duration::from_secs_f64(offset_ns as f64 / multiplier / 1e9);
+        std::thread::sleep(due_at.saturating_duration_since(Instant::now()));
+    }
+}
+
+impl Read for CapturedChunkReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let Some(chunk_offset_ns) = self.chunks.front().map(|chunk| chunk.offset_ns) else {
+            return Ok(0);
+        };
+        let timeout_at_ns = self.clock_ns + self.read_timeout_ns;
+        if chunk_offset_ns > timeout_at_ns {
+            self.wait_until(timeout_at_ns);
+            self.clock_ns = timeout_at_ns;
+            return Err(std::io::ErrorKind::TimedOut.into());
+        }
+        let offset_ns = chunk_offset_ns.max(self.clock_ns);
+        self.wait_until(offset_ns);
+        self.clock_ns = offset_ns;
+
+        let Some(chunk) = self.chunks.front_mut() else {
+            return Ok(0);
+        };
+        // A buffer smaller than the chunk gets the rest on the next read.
+        let bytes_read = buf.len().min(chunk.bytes.len());
+        buf[..bytes_read].copy_from_slice(&chunk.bytes[..bytes_read]);
+        chunk.bytes.drain(..bytes_read);
+        if chunk.bytes.is_empty() {
+            self.chunks.pop_front();
+        }
+        Ok(bytes_read)
+    }
+}
+
+/// EEG source that feeds a serial byte capture back through the same
+/// decoder the live serial source uses, chunk by chunk, honouring the
+/// original timing at the selected speed. Turns a misbehaving setup into a
+/// reproducible fixture.
+pub struct SerialCaptureReplaySource {
+    capture_path: PathBuf,
+    speed: ReplaySpeed,
+    field_mapping: FieldMapping,
+    reader: Option<BufReader<CapturedChunkReader>>,
+    stream: Option<SerialStreamDecoder>,
+    diagnostics: LinkDiagnostics,
+}
+
+impl SerialCaptureReplaySource {
+    pub fn new(capture_path: PathBuf, speed: ReplaySpeed, field_mapping: FieldMapping) -> Self {
+        Self {
+            capture_path,
+            speed,
+            field_mapping,
+            reader: None,
+            stream: None,
+            // Replaced by the session's counters in attach_link_diagnostics.
+            diagnostics: LinkDiagnostics::new(Instant::now()),
+        }
+    }
+}
+
+impl EegSource for SerialCaptureReplaySource {
+    fn start(&mut self) -> Result<LinkInfo, AppError> {
+        let capture = read_serial_capture(&self.capture_path)?;
+        self.stream = Some(SerialStreamDecoder::new(
+            capture.wire_format,
+            self.field_mapping.clone(),
+        ));
+        self.reader = Some(BufReader::new(CapturedChunkReader {
+            chunks: capture.chunks.into(),
+            // A zero timeout would stall the virtual clock between chunks.
+            read_timeout_ns: u64::from(capture.read_timeout_ms.max(1)) * 1_000_000,
+            multiplier: self.speed.multiplier(),
+            started: Instant::now(),
+            clock_ns: 0,
+        }));
+        Ok(LinkInfo {
+            port: self.capture_path.display().to_string(),
+            baud: None,
+        })
+    }
+
+    fn next_event(&mut self) -> Result<SourceEvent, AppError> {
+        let (Some(reader), Some(stream)) = (self.reader.as_mut(), self.stream.as_mut()) else {
+            return Ok(SourceEvent::Finished);
+        };
+        if let Some(reading) = stream.next_reading() {
+            return Ok(SourceEvent::Reading(reading));
+        }
+        match stream.read_from(reader, None, &self.diagnostics) {
+            Ok(0) => Ok(SourceEvent::Finished),
+            Ok(_) => Ok(stream
+                .next_reading()
+                .map(SourceEvent::Reading)
+                .unwrap_or(SourceEvent::Idle)),
+            Err(error) if error.kind() == std::io::ErrorKind::TimedOut => Ok(SourceEvent::Idle),
+            Err(error) => Err(AppError::AcquisitionFailure(format!(
+                "serial capture replay failed: {error}"
+            ))),
+        }
+    }
+
+    fn stop(&mut self) {
+        self.reader = None;
+        self.stream = None;
+    }
+
+    fn attach_link_diagnostics(&mut self, diagnostics: LinkDiagnostics) {
+        self.diagnostics = diagnostics;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::{
+        serial_byte_capture::{CapturingReader, SerialByteCapture},
+        serial_eeg_source::WireFormat,
+    };
+    use crate::domain::{eeg_reading::EegReading, raw_eeg_sample::RawEegSample};
+
+    // Port that returns one scripted chunk per read, then EOF.
+    struct ScriptedPort(VecDeque<&'static [u8]>);
+
+    impl Read for ScriptedPort {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let Some(chunk) = self.0.pop_front() else {
+                return Ok(0);
+            };
+            buf[..chunk.len()].copy_from_slice(chunk);
+            Ok(chunk.len())
+        }
+    }
+
+    #[test]
+    fn capture_replays_to_the_readings_decoded_live() {
+        let capture_path = std::env::temp_dir().join(format!(
+            "cerebro-capture-round-trip-{}.bin",
+            std::process::id()
+        ));
+        let capture = SerialByteCapture::create(&capture_path, WireFormat::Esp32Json, 500).unwrap();
+        // Reads end mid-line, as they do on a real port.
+        let port = ScriptedPort(VecDeque::from([
+            b"{\"raw\":1}\n{\"ra".as_slice(),
+            b"w\":2}\n{\"delta\":1,\"theta\":2,\"lowAlpha\":3,\"highAlpha\":4,".as_slice(),
+            b"\"lowBeta\":5,\"highBeta\":6,\"lowGamma\":7,\"midGamma\":8,\"poorSignal\":0}\n"
+                .as_slice(),
+            b"{\"ack\":1,\"ok\":true}\n{\"blinkStrength\":60}\n".as_slice(),
+        ]));
+        let mut live_reader = BufReader::new(CapturingReader::new(port, capture));
+        let mut live_stream =
+            SerialStreamDecoder::new(WireFormat::Esp32Json, FieldMapping::default());
+        let diagnostics = LinkDiagnostics::new(Instant::now());
+        let mut live = Vec::new();
+        while live_stream
+            .read_from(&mut live_reader, None, &diagnostics)
+            .unwrap()
+            > 0
+        {
+            live.extend(std::iter::from_fn(|| live_stream.next_reading()));
+        }
+
+        let mut source = SerialCaptureReplaySource::new(
+            capture_path.clone(),
+            ReplaySpeed::Max,
+            FieldMapping::default(),
+        );
+        source.start().unwrap();
+        let mut replayed = Vec::new();
+        loop {
+            match source.next_event().unwrap() {
+                SourceEvent::Reading(reading) => replayed.push(reading),
+                SourceEvent::Idle => {}
+                SourceEvent::Finished => break,
+                other => panic!("unexpected event {other:?}"),
+            }
+        }
+        std::fs::remove_file(&capture_path).unwrap();
+
+        assert_eq!(live.len(), 4);
+        assert_eq!(live[1], EegReading::Raw(RawEegSample { value: 2 }));
+        assert!(matches!(&live[2], EegReading::Packet(packet) if packet.delta == 1));
+        assert_eq!(replayed, live);
+    }
+
+    #[test]
+    fn attached_counters_tally_only_their_own_session() {
+        let capture_path = std::env::temp_dir().join(format!(
+            "cerebro-capture-diagnostics-{}.bin",
+            std::process::id()
+        ));
+        let capture = SerialByteCapture::create(&capture_path, WireFormat::Esp32Json, 500).unwrap();
+        capture.record(b"{\"raw\":1}\nnoise\n{\"raw\":2}\n");
+        drop(capture);
+
+        let mut source = SerialCaptureReplaySource::new(
+            capture_path.clone(),
+            ReplaySpeed::Max,
+            FieldMapping::default(),
+        );
+        let mut replay_session = |diagnostics: &LinkDiagnostics| {
+            source.attach_link_diagnostics(diagnostics.clone());
+            source.start().unwrap();
+            while !matches!(source.next_event().unwrap(), SourceEvent::Finished) {}
+            source.stop();
+        };
+        let first = LinkDiagnostics::new(Instant::now());
+        replay_session(&first);
+        let restarted = LinkDiagnostics::new(Instant::now());
+        replay_session(&restarted);
+        std::fs::remove_file(&capture_path).unwrap();
+
+        for diagnostics in [first, restarted] {
+            let stats = diagnostics.snapshot(Instant::now());
+            assert_eq!(stats.lines_read, 3);
+            assert_eq!(stats.raw_only_lines, 2);
+            assert_eq!(stats.json_parse_failures, 1);
+        }
+    }
+}
@@ -0,0 +1,380 @@
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Read};
+use std::path::PathBuf;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    adapters::{
+        device_command_channel::DeviceCommandChannel,
+        esp32_packet_parser::{decode_esp32_line, Esp32Line},
+        reconnect_backoff::{ReconnectBackoff, ReconnectPolicy, ReconnectStep},
+        serial_byte_capture::{CapturingReader, SerialByteCapture},
+        thinkgear_packet_parser::ThinkGearParser,
+    },
+    domain::{
+        connection_status::{ConnectionStatus, LinkInfo},
+        eeg_reading::EegReading,
+        errors::AppError,
+        field_mapping::FieldMapping,
+        link_diagnostics::LinkDiagnostics,
+        ports::EegSource,
+        serial_config::{
+            SerialConfig, SerialDataBits, SerialFlowControl, SerialParity, SerialStopBits,
+        },
+        source_event::SourceEvent,
+    },
+};
+
+/// Byte format spoken on the serial link. `Esp32Json` is the newline-delimited
+/// JSON re-encoded by our ESP32 sketch; `ThinkGear` is the headset's native
+/// binary protocol, read from a USB dongle or an ESP32 acting as a
+/// transparent UART bridge.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WireFormat {
+    #[default]
+    Esp32Json,
+    ThinkGear,
+}
+
+impl WireFormat {
+    /// ThinkGear modules ship configured for 57600 baud; the ESP32 sketch uses 115200.
+    pub fn default_baud_rate(self) -> u32 {
+        match self {
+            WireFormat::Esp32Json => 115_200,
+            WireFormat::ThinkGear => 57_600,
+        }
+    }
+}
+
+// Per-format decoding state carried across reads. The JSON path is stateless
+// per line; the ThinkGear parser must remember partial frames between chunks.
+enum WireDecoder {
+    Esp32Json,
+    ThinkGear(ThinkGearParser),
+}
+
+impl WireDecoder {
+    fn for_format(wire_format: WireFormat) -> Self {
+        match wire_format {
+            WireFormat::Esp32Json => WireDecoder::Esp32Json,
+            WireFormat::ThinkGear => WireDecoder::ThinkGear(ThinkGearParser::default()),
+        }
+    }
+}
+
+/// Turns the bytes of a serial link into readings. Shared by the live source
+/// and the capture replay, so a replayed capture is decoded exactly as the
+/// port's bytes were. The field mapping only applies to ESP32 JSON.
+pub struct SerialStreamDecoder {
+    wire_format: WireFormat,
+    field_mapping: FieldMapping,
+    decoder: WireDecoder,
+    line_buffer: Vec<u8>,
+    pending: VecDeque<EegReading>,
+}
+
+impl SerialStreamDecoder {
+    pub fn new(wire_format: WireFormat, field_mapping: FieldMapping) -> Self {
+        Self {
+            wire_format,
+            field_mapping,
+            decoder: WireDecoder::for_format(wire_format),
+            line_buffer: Vec::with_capacity(256),
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// To forget partial frames, e.g. after the link dropped mid-frame.
+    pub fn reset(&mut self) {
+        self.decoder = WireDecoder::for_format(self.wire_format);
+    }
+
+    /// To take the next reading decoded so far, oldest first.
+    pub fn next_reading(&mut self) -> Option<EegReading> {
+        self.pending.pop_front()
+    }
+
+    /// To perform one blocking read and decode whatever it yields. Returns
+    /// the number of bytes consumed so the caller can detect EOF. JSON lines
+    /// that are not EEG data are offered to `commands` as acks, if given.
+    /// The JSON path uses read_until rather than lines() because ESP32 boot
+    /// sequences can emit non-UTF-8 bytes that would terminate a lines()
+    /// iterator prematurely. Everything read is tallied in `diagnostics`.
+    pub fn read_from(
+        &mut self,
+        reader: &mut impl BufRead,
+        commands: Option<&DeviceCommandChannel>,
+        diagnostics: &LinkDiagnostics,
+    ) -> std::io::Result<usize> {
+        match &mut self.decoder {
+            WireDecoder::Esp32Json => {
+                self.line_buffer.clear();
+                let bytes_read = reader.read_until(b'\n', &mut self.line_buffer)?;
+                if bytes_read == 0 {
+                    return Ok(0);
+                }
+                match decode_esp32_line(&self.line_buffer, &self.field_mapping, diagnostics) {
+                    Esp32Line::Readings(readings) => self.pending.extend(readings),
+                    Esp32Line::Other(line) => {
+                        if let Some(commands) = commands {
+                            commands.resolve_ack(&line);
+                        }
+                    }
+                    Esp32Line::Ignored => {}
+                }
+                Ok(bytes_read)
+            }
+            WireDecoder::ThinkGear(parser) => {
+                let chunk = reader.fill_buf()?;
+                let bytes_read = chunk.len();
+                diagnostics.record_bytes(bytes_read);
+                let mut decoded = Vec::new();
+                parser.push_bytes(chunk, &mut decoded);
+                self.pending.extend(decoded);
+                reader.consume(bytes_read);
+                Ok(bytes_read)
+            }
+        }
+    }
+}
+
+/// EEG source backed by a USB serial port. Supervises the link for the whole
+/// session: a dropped port is reopened with backoff rather than ending the
+/// source, so the frontend keeps its session context across a USB glitch.
+/// With the ESP32 JSON format the same port also carries firmware commands.
+pub struct SerialEegSource {
+    port_name: String,
+    wire_format: WireFormat,
+    serial_config: SerialConfig,
+    reader: Option<BufReader<Box<dyn Read + Send>>>,
+    stream: SerialStreamDecoder,
+    reconnect: ReconnectBackoff,
+    commands: DeviceCommandChannel,
+    diagnostics: LinkDiagnostics,
+    capture_path: Option<PathBuf>,
+    capture: Option<SerialByteCapture>,
+}
+
+impl SerialEegSource {
+    /// With a `capture_path`, every byte read from the port is also logged
+    /// there for `SerialCaptureReplaySource`.
+    pub fn new(
+        port_name: String,
+        wire_format: WireFormat,
+        serial_config: SerialConfig,
+        reconnect_policy: ReconnectPolicy,
+        capture_path: Option<PathBuf>,
+        field_mapping: FieldMapping,
+    ) -> Self {
+        Self {
+            port_name,
+            wire_format,
+            serial_config,
+            reader: None,
+            stream: SerialStreamDecoder::new(wire_format, field_mapping),
+            reconnect: ReconnectBackoff::new(reconnect_policy),
+            commands: DeviceCommandChannel::default(),
+            // Replaced by the session's counters in attach_link_diagnostics.
+            diagnostics: LinkDiagnostics::new(Instant::now()),
+            capture_path,
+            capture: None,
+        }
+    }
+
+    /// To hand out the firmware command channel. Only the ESP32 sketch
+    /// understands commands; a ThinkGear link has none.
+    pub fn command_channel(&self) -> Option<DeviceCommandChannel> {
+        (self.wire_format == WireFormat::Esp32Json).then(|| self.commands.clone())
+    }
+
+    // To adopt a freshly opened port as the reader, behind the capture if
+    // one is running, and a clone of it as the command writer.
+    fn install_port(&mut self, serial_port: Box<dyn serialport::SerialPort>) {
+        if self.wire_format == WireFormat::Esp32Json {
+            match serial_port.try_clone() {
+                Ok(writer) => self.commands.attach(Box::new(writer)),
+                // Data still flows; only commands are unavailable.
+                Err(error) => eprintln!("[IO] no command writer for {}: {error}", self.port_name),
+            }
+        }
+        let reader: Box<dyn Read + Send> = match &self.capture {
+            Some(capture) => Box::new(CapturingReader::new(serial_port, capture.clone())),
+            None => Box::new(serial_port),
+        };
+        self.reader = Some(BufReader::new(reader));
+    }
+
+    // To drop the dead handle and schedule the first reopen attempt.
+    fn begin_reconnect(&mut self, reason: String) -> Result<SourceEvent, AppError> {
+        eprintln!("[IO] {reason}");
+        self.reader = None;
+        self.commands.detach();
+        self.stream.reset();
+        let status = self
+            .reconnect
+            .begin(&self.port_name, reason, Instant::now())?;
+        Ok(SourceEvent::Status(status))
+    }
+
+    // To advance the reconnect backoff by one poll window.
+    fn poll_reconnect(&mut self) -> Result<SourceEvent, AppError> {
+        let port_name = &self.port_name;
+        let wire_format = self.wire_format;
+        let serial_config = &self.serial_config;
+        let step = self.reconnect.poll(port_name, Instant::now(), || {
+            open_serial_port(port_name, wire_format, serial_config)
+        })?;
+        Ok(match step {
+            ReconnectStep::Waiting => SourceEvent::Idle,
+            ReconnectStep::Retrying(status) => SourceEvent::Status(status),
+            ReconnectStep::Reopened(serial_port) => {
+                self.install_port(serial_port);
+                SourceEvent::Status(ConnectionStatus::Reconnected)
+            }
+        })
+    }
+}
+
+// To acquire an exclusive handle to the named COM port with the configured
+// line settings. Used for the first connect and for every reconnect attempt.
+fn open_serial_port(
+    port_name: &str,
+    wire_format: WireFormat,
+    serial_config: &SerialConfig,
+) -> Result<Box<dyn serialport::SerialPort>, AppError> {
+    if port_name.is_empty() {
+        return Err(AppError::AcquisitionFailure(
+            "no serial port was specified".to_string(),
+        ));
+    }
+    let line = serial_config.validate()?;
+    let mut builder = serialport::new(port_name, baud_rate(wire_format, serial_config))
+        .data_bits(match line.data_bits {
+            SerialDataBits::Five => serialport::DataBits::Five,
+            SerialDataBits::Six => serialport::DataBits::Six,
+            SerialDataBits::Seven => serialport::DataBits::Seven,
+            SerialDataBits::Eight => serialport::DataBits::Eight,
+        })
+        .parity(match serial_config.parity {
+            SerialParity::None => serialport::Parity::None,
+            SerialParity::Odd => serialport::Parity::Odd,
+            SerialParity::Even => serialport::Parity::Even,
+        })
+        .stop_bits(match line.stop_bits {
+            SerialStopBits::One => serialport::StopBits::One,
+            SerialStopBits::Two => serialport::StopBits::Two,
+        })
+        .flow_control(match serial_config.flow_control {
+            SerialFlowControl::None => serialport::FlowControl::None,
+            SerialFlowControl::Software => serialport::FlowControl::Software,
+            SerialFlowControl::Hardware => serialport::FlowControl::Hardware,
+        })
+        .timeout(line.read_timeout);
+    // DTR must be set by the builder: driving it after open() is too late to
+    // avoid the reset pulse some boards see when the port opens.
+    if let Some(level) = serial_config.dtr.level() {
+        builder = builder.dtr_on_open(level);
+    }
+    let mut serial_port = builder
+        .open()
+        .map_err(|error| classify_open_error(port_name, error))?;
+    if let Some(level) = serial_config.rts.level() {
+        serial_port.write_request_to_send(level).map_err(|error| {
+            AppError::AcquisitionFailure(format!("failed to set RTS on {port_name}: {error}"))
+        })?;
+    }
+    Ok(serial_port)
+}
+
+// To resolve the baud rate: the configured one, else the format's standard.
+fn baud_rate(wire_format: WireFormat, serial_config: &SerialConfig) -> u32 {
+    serial_config
+        .baud_rate
+        .unwrap_or_else(|| wire_format.default_baud_rate())
+}
+
+// To tell a busy port from a missing one. Drivers report both as NoDevice or
+// a bare errno, so the OS port list decides: a port that is still listed but
+// will not open is held by someone else.
+fn classify_open_error(port_name: &str, error: serialport::Error) -> AppError {
+    let still_listed = serialport::available_ports()
+        .map(|ports| ports.iter().any(|port| port.port_name == port_name))
+        .unwrap_or(true);
+    match error.kind() {
+        serialport::ErrorKind::Io(std::io::ErrorKind::NotFound) => {
+            AppError::PortVanished(port_name.to_string())
+        }
+        serialport::ErrorKind::NoDevice
+        | serialport::ErrorKind::Unknown
+        | serialport::ErrorKind::Io(std::io::ErrorKind::PermissionDenied) => {
+            if still_listed {
+                AppError::PortBusy(port_name.to_string())
+            } else {
+                AppError::PortVanished(port_name.to_string())
+            }
+        }
+        _ => AppError::AcquisitionFailure(format!("failed to open {port_name}: {error}")),
+    }
+}
+
+impl EegSource for SerialEegSource {
+    fn start(&mut self) -> Result<LinkInfo, AppError> {
+        if let Some(capture_path) = &self.capture_path {
+            self.capture = Some(SerialByteCapture::create(
+                capture_path,
+                self.wire_format,
+                self.serial_config.read_timeout_ms.min(u64::from(u32::MAX)) as u32,
+            )?);
+        }
+        let serial_port = open_serial_port(&self.port_name, self.wire_format, &self.serial_config)?;
+        self.install_port(serial_port);
+        Ok(LinkInfo {
+            port: self.port_name.clone(),
+            baud: Some(baud_rate(self.wire_format, &self.serial_config)),
+        })
+    }
+
+    fn next_event(&mut self) -> Result<SourceEvent, AppError> {
+        if let Some(reading) = self.stream.next_reading() {
+            return Ok(SourceEvent::Reading(reading));
+        }
+        let Some(reader) = self.reader.as_mut() else {
+            return self.poll_reconnect();
+        };
+
+        match self
+            .stream
+            .read_from(reader, Some(&self.commands), &self.diagnostics)
+        {
+            Ok(0) => self.begin_reconnect(format!("serial port {} reached EOF", self.port_name)),
+            Ok(_) => Ok(self
+                .stream
+                .next_reading()
+                .map(SourceEvent::Reading)
+                .unwrap_or(SourceEvent::Idle)),
+            Err(error)
+                if error.kind() == std::io::ErrorKind::TimedOut
+                    || error.kind() == std::io::ErrorKind::WouldBlock =>
+            {
+                Ok(SourceEvent::Idle)
+            }
+            Err(error) => {
+                self.begin_reconnect(format!("serial read error on {}: {error}", self.port_name))
+            }
+        }
+    }
+
+    fn stop(&mut self) {
+        self.reader = None;
+        self.commands.detach();
+        self.reconnect.cancel();
+        self.capture = None;
+    }
+
+    fn attach_link_diagnostics(&mut self, diagnostics: LinkDiagnostics) {
+        self.diagnostics = diagnostics;
+    }
+}
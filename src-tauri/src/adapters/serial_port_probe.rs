@@ -0,0 +1,245 @@
+use std::io::Read;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serialport::{SerialPortInfo, SerialPortType};
+
+use crate::{
+    adapters::{
+        esp32_packet_parser::parse_esp32_line, serial_eeg_source::WireFormat,
+        thinkgear_packet_parser::ThinkGearParser,
+    },
+    domain::eeg_reading::EegReading,
+};
+
+// USB vendor IDs of the UART bridges found on ESP32 dev boards and ThinkGear
+// dongles. A match is only a hint; decoded traffic always outranks it.
+const KNOWN_BRIDGE_VENDOR_IDS: [(u16, &str); 5] = [
+    (0x10C4, "Silicon Labs CP210x"),
+    (0x1A86, "WCH CH340"),
+    (0x0403, "FTDI"),
+    (0x303A, "Espressif native USB"),
+    (0x067B, "Prolific PL2303"),
+];
+const PROBE_READ_TIMEOUT: Duration = Duration::from_millis(100);
+// A stray checksum-valid ThinkGear frame in line noise is plausible; three
+// decoded readings are not.
+const MIN_READINGS_FOR_DETECTION: u32 = 3;
+// Longest plausible JSON line; anything longer is noise at the wrong baud.
+const MAX_PROBE_LINE_BYTES: usize = 1_024;
+const DECODED_READING_SCORE: u32 = 10;
+const KNOWN_BRIDGE_SCORE: u32 = 5;
+
+/// Everything the OS reports about one serial port, so the selector can show
+/// "CP210x (COM7)" instead of a bare name.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SerialPortDescriptor {
+    pub port_name: String,
+    /// `usb`, `bluetooth`, `pci` or `unknown`.
+    pub port_type: String,
+    pub vid: Option<u16>,
+    pub pid: Option<u16>,
+    pub manufacturer: Option<String>,
+    pub product: Option<String>,
+    pub serial_number: Option<String>,
+}
+
+impl From<SerialPortInfo> for SerialPortDescriptor {
+    fn from(info: SerialPortInfo) -> Self {
+        let mut descriptor = Self {
+            port_name: info.port_name,
+            port_type: "unknown".to_string(),
+            vid: None,
+            pid: None,
+            manufacturer: None,
+            product: None,
+            serial_number: None,
+        };
+        match info.port_type {
+            SerialPortType::UsbPort(usb) => {
+                descriptor.port_type = "usb".to_string();
+                descriptor.vid = Some(usb.vid);
+                descriptor.pid = Some(usb.pid);
+                descriptor.manufacturer = usb.manufacturer;
+                descriptor.product = usb.product;
+                descriptor.serial_number = usb.serial_number;
+            }
+            SerialPortType::BluetoothPort => descriptor.port_type = "bluetooth".to_string(),
+            SerialPortType::PciPort => descriptor.port_type = "pci".to_string(),
+            SerialPortType::Unknown => {}
+        }
+        descriptor
+    }
+}
+
+/// Options for `probe_ports`. Bluetooth ports are skipped by default because
+/// opening an unpaired SPP port can block for several seconds.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PortProbeRequest {
+    /// Ports to probe; every listed port when empty.
+    pub port_names: Vec<String>,
+    /// How long to listen per wire format.
+    pub listen_ms: u64,
+    pub include_bluetooth: bool,
+}
+
+impl Default for PortProbeRequest {
+    fn default() -> Self {
+        Self {
+            port_names: Vec::new(),
+            listen_ms: 1_500,
+            include_bluetooth: false,
+        }
+    }
+}
+
+/// What probing one port found, ranked by `score` (highest first).
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PortProbeResult {
+    pub port: SerialPortDescriptor,
+    /// Wire format whose frames were decoded, if any.
+    pub detected_format: Option<WireFormat>,
+    pub readings_decoded: u32,
+    /// Name of the recognised USB bridge chip, if any.
+    pub known_bridge: Option<String>,
+    pub score: u32,
+    /// Why the port could not be opened, e.g. because another app holds it.
+    pub error: Option<String>,
+}
+
+/// To enumerate every serial port visible to the OS with its USB details.
+pub fn list_serial_port_descriptors() -> Vec<SerialPortDescriptor> {
+    serialport::available_ports()
+        .unwrap_or_default()
+        .into_iter()
+        .map(SerialPortDescriptor::from)
+        .collect()
+}
+
+/// To briefly open each candidate port, listen for ESP32 JSON and then for
+/// ThinkGear frames, and rank the ports by how convincingly they spoke
+/// either. Ports are probed in parallel, so the whole probe takes about two
+/// listen windows regardless of how many ports exist. `held_ports` are left
+/// alone, since a running reader already owns them.
+pub fn probe_serial_ports(
+    request: &PortProbeRequest,
+    held_ports: &[String],
+) -> Vec<PortProbeResult> {
+    let listen = Duration::from_millis(request.listen_ms);
+    let candidates: Vec<SerialPortDescriptor> = list_serial_port_descriptors()
+        .into_iter()
+        .filter(|port| {
+            request.port_names.is_empty() || request.port_names.contains(&port.port_name)
+        })
+        .filter(|port| request.include_bluetooth || port.port_type != "bluetooth")
+        .filter(|port| !held_ports.contains(&port.port_name))
+        .collect();
+
+    let mut results: Vec<PortProbeResult> = std::thread::scope(|scope| {
+        let probes: Vec<_> = candidates
+            .into_iter()
+            .map(|port| scope.spawn(move || probe_port(port, listen)))
+            .collect();
+        probes
+            .into_iter()
+            .filter_map(|probe| probe.join().ok())
+            .collect()
+    });
+    results.sort_by_key(|result| std::cmp::Reverse(result.score));
+    results
+}
+
+fn probe_port(port: SerialPortDescriptor, listen: Duration) -> PortProbeResult {
+    let known_bridge = port.vid.and_then(|vid| {
+        KNOWN_BRIDGE_VENDOR_IDS
+            .iter()
+            .find(|(known_vid, _)| *known_vid == vid)
+            .map(|(_, name)| name.to_string())
+    });
+    let mut result = PortProbeResult {
+        port,
+        detected_format: None,
+        readings_decoded: 0,
+        score: if known_bridge.is_some() {
+            KNOWN_BRIDGE_SCORE
+        } else {
+            0
+        },
+        known_bridge,
+        error: None,
+    };
+
+    for wire_format in [WireFormat::Esp32Json, WireFormat::ThinkGear] {
+        match listen_for_readings(&result.port.port_name, wire_format, listen) {
+            Ok(readings_decoded) if readings_decoded < MIN_READINGS_FOR_DETECTION => {}
+            Ok(readings_decoded) => {
+                result.detected_format = Some(wire_format);
+                result.readings_decoded = readings_decoded;
+                result.score += readings_decoded * DECODED_READING_SCORE;
+                break;
+            }
+            Err(error) => {
+                result.error = Some(error);
+                break;
+            }
+        }
+    }
+    result
+}
+
+// To count the readings one wire format decodes from the port within the
+// listen window. Raw samples count as well, since a headset that is not yet
+// worn may stream nothing else.
+fn listen_for_readings(
+    port_name: &str,
+    wire_format: WireFormat,
+    listen: Duration,
+) -> Result<u32, String> {
+    // DTR held low from open, so listening never pulses an ESP32 dev board
+    // into reset the way a terminal opening the port would.
+    let mut serial_port = serialport::new(port_name, wire_format.default_baud_rate())
+        .timeout(PROBE_READ_TIMEOUT)
+        .dtr_on_open(false)
+        .open()
+        .map_err(|error| error.to_string())?;
+
+    let deadline = Instant::now() + listen;
+    let mut thinkgear_parser = ThinkGearParser::default();
+    let mut line_buffer: Vec<u8> = Vec::new();
+    let mut readings: Vec<EegReading> = Vec::new();
+    let mut chunk = [0u8; 1024];
+    let mut readings_decoded = 0u32;
+    while Instant::now() < deadline {
+        let bytes_read = match serial_port.read(&mut chunk) {
+            Ok(bytes_read) => bytes_read,
+            Err(error) if error.kind() == std::io::ErrorKind::TimedOut => continue,
+            Err(error) => return Err(error.to_string()),
+        };
+        match wire_format {
+            WireFormat::Esp32Json => {
+                for &byte in &chunk[..bytes_read] {
+                    if byte != b'\n' {
+                        if line_buffer.len() < MAX_PROBE_LINE_BYTES {
+                            line_buffer.push(byte);
+                        }
+                        continue;
+                    }
+                    let line = String::from_utf8_lossy(&line_buffer);
+                    if !parse_esp32_line(line.trim()).is_empty() {
+                        readings_decoded += 1;
+                    }
+                    line_buffer.clear();
+                }
+            }
+            WireFormat::ThinkGear => {
+                thinkgear_parser.push_bytes(&chunk[..bytes_read], &mut readings);
+                readings_decoded += readings.len() as u32;
+                readings.clear();
+            }
+        }
+    }
+    Ok(readings_decoded)
+}
@@ -0,0 +1,410 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::{
+    adapters::iso8601::{format_iso8601_millis, parse_iso8601_millis},
+    domain::{
+        eeg_packet::{EegPacket, PacketTiming},
+        errors::AppError,
+        raw_eeg_sample::RAW_SAMPLE_RATE_HZ,
+        session_recording::{RecordedRawBatch, RecordedRow, RecordingMarker},
+    },
+};
+
+/// Header of the recorder CSV. Earlier columns never move, so older readers
+/// (and the replay source) keep working as columns are appended.
+pub const SESSION_CSV_HEADER: &str =
+    "timestamp,delta,theta,lowAlpha,highAlpha,lowBeta,highBeta,lowGamma,midGamma,\
+attention,meditation,poorSignalLevel,focusLabel,focusPrediction,\
+sequence,monotonicNs,deviceMillis,deviceSeq,blinkStrength,extensions";
+
+/// To render recorded rows as the recorder CSV, header first.
+pub fn session_csv(rows: &[RecordedRow]) -> String {
+    let mut csv = String::from(SESSION_CSV_HEADER);
+    for row in rows {
+        csv.push('\n');
+        csv.push_str(&csv_line(row));
+    }
+    csv
+}
+
+fn csv_line(row: &RecordedRow) -> String {
+    let packet = &row.packet;
+    let optional = |value: Option<String>| value.unwrap_or_default();
+    [
+        format_iso8601_millis(row.timing.received_at_unix_ms as i64),
+        packet.delta.to_string(),
+        packet.theta.to_string(),
+        packet.low_alpha.to_string(),
+        packet.high_alpha.to_string(),
+        packet.low_beta.to_string(),
+        packet.high_beta.to_string(),
+        packet.low_gamma.to_string(),
+        packet.mid_gamma.to_string(),
+        packet.attention.to_string(),
+        packet.meditation.to_string(),
+        packet.poor_signal_level.to_string(),
+        row.focus_label.to_string(),
+        row.focus_prediction.clone(),
+        row.timing.sequence.to_string(),
+        row.timing.monotonic_ns.to_string(),
+        optional(packet.device_millis.map(|millis| millis.to_string())),
+        optional(packet.device_seq.map(|seq| seq.to_string())),
+        optional(packet.blink_strength.map(|strength| strength.to_string())),
+        extensions_cell(row),
+    ]
+    .join(",")
+}
+
+// Extensions share one cell as `name=value;name=value`, so names are kept
+// free of the separators.
+fn extensions_cell(row: &RecordedRow) -> String {
+    row.packet
+        .extensions
+        .iter()
+        .map(|(name, value)| {
+            let name: String = name
+                .chars()
+                .map(|character| match character {
+                    ',' | ';' | '=' => '_',
+                    other if other.is_whitespace() => '_',
+                    other => other,
+                })
+                .collect();
+            format!("{name}={value}")
+        })
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+// Columns a recorder CSV must have for its rows to be read back; the ones
+// appended later default when an older file lacks them.
+const REQUIRED_COLUMNS: [&str; 13] = [
+    "timestamp",
+    "delta",
+    "theta",
+    "lowAlpha",
+    "highAlpha",
+    "lowBeta",
+    "highBeta",
+    "lowGamma",
+    "midGamma",
+    "attention",
+    "meditation",
+    "poorSignalLevel",
+    "focusLabel",
+];
+
+/// To read the rows of a recorder CSV back, e.g. to recompute its summary.
+/// Columns are located by header name, so older files without the later
+/// columns still parse; rows that do not parse are skipped.
+pub fn parse_session_csv(content: &str) -> Result<Vec<RecordedRow>, AppError> {
+    let mut lines = content.lines();
+    let header: Vec<&str> = lines
+        .next()
+        .ok_or_else(|| AppError::SerializationFailure("session CSV is empty".to_string()))?
+        .split(',')
+        .map(str::trim)
+        .collect();
+    if let Some(column) = REQUIRED_COLUMNS
+        .iter()
+        .find(|column| !header.contains(column))
+    {
+        return Err(AppError::SerializationFailure(format!(
+            "session CSV is missing the `{column}` column"
+        )));
+    }
+    Ok(lines
+        .filter(|line| !line.trim().is_empty())
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            parse_session_row(&header, &fields, index as u64)
+        })
+        .collect())
+}
+
+// `index` stands in for the sequence of files written before it had a column.
+fn parse_session_row(header: &[&str], fields: &[&str], index: u64) -> Option<RecordedRow> {
+    let cell = |column: &str| {
+        let position = header.iter().position(|name| *name == column)?;
+        fields
+            .get(position)
+            .copied()
+            .filter(|field| !field.is_empty())
+    };
+    let received_at_unix_ms = parse_iso8601_millis(cell("timestamp")?)?;
+    Some(RecordedRow {
+        packet: EegPacket {
+            delta: parse_cell(cell("delta"))?,
+            theta: parse_cell(cell("theta"))?,
+            low_alpha: parse_cell(cell("lowAlpha"))?,
+            high_alpha: parse_cell(cell("highAlpha"))?,
+            low_beta: parse_cell(cell("lowBeta"))?,
+            high_beta: parse_cell(cell("highBeta"))?,
+            low_gamma: parse_cell(cell("lowGamma"))?,
+            mid_gamma: parse_cell(cell("midGamma"))?,
+            attention: parse_cell(cell("attention"))?,
+            meditation: parse_cell(cell("meditation"))?,
+            poor_signal_level: parse_cell(cell("poorSignalLevel"))?,
+            device_millis: parse_cell(cell("deviceMillis")),
+            device_seq: parse_cell(cell("deviceSeq")),
+            blink_strength: parse_cell(cell("blinkStrength")),
+            extensions: cell("extensions")
+                .map(parse_extensions_cell)
+                .unwrap_or_default(),
+        },
+        timing: PacketTiming {
+            sequence: parse_cell(cell("sequence")).unwrap_or(index),
+            monotonic_ns: parse_cell(cell("monotonicNs")).unwrap_or_default(),
+            received_at_unix_ms: received_at_unix_ms.max(0) as u64,
+        },
+        focus_label: parse_cell(cell("focusLabel"))?,
+        focus_prediction: cell("focusPrediction").unwrap_or("N/A").to_string(),
+    })
+}
+
+fn parse_cell<T: FromStr>(field: Option<&str>) -> Option<T> {
+    field?.parse().ok()
+}
+
+/// To read the recorder's `name=value;name=value` extensions cell. Pairs
+/// that do not parse are dropped.
+pub fn parse_extensions_cell(field: &str) -> BTreeMap<String, f64> {
+    field
+        .split(';')
+        .filter_map(|pair| {
+            let (name, value) = pair.split_once('=')?;
+            Some((name.trim().to_string(), value.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+/// Suffix of the sibling CSV holding the 512 Hz raw waveform.
+pub const RAW_CSV_SUFFIX: &str = "raw";
+/// Suffix of the sibling CSV listing the session's markers.
+pub const MARKERS_CSV_SUFFIX: &str = "markers";
+
+/// To derive a companion CSV path from the session CSV path so the files
+/// always sit side by side: `Subject_<date>.csv` -> `Subject_<date>_raw.csv`.
+pub fn sibling_csv_path_for(csv_path: &str, suffix: &str) -> String {
+    let path = Path::new(csv_path);
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!("{stem}_{suffix}.csv"))
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// To fingerprint a session CSV, so the same recording is recognised under
+/// another path. 64-bit FNV-1a over the content with line endings
+/// normalised, as hex.
+pub fn session_csv_hash(content: &str) -> String {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+    let hash = content
+        .lines()
+        .flat_map(|line| line.bytes().chain([b'\n']))
+        .fold(OFFSET_BASIS, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+        });
+    format!("{hash:016x}")
+}
+
+/// Header of the raw-waveform CSV written beside the recorder CSV. `sample`
+/// counts samples from the start of the recording and `sequence` is the last
+/// row recorded before the sample, to line the two files up. Only batch
+/// arrival is timed, so each `timestamp` is worked back from it at 512 Hz.
+pub const RAW_CSV_HEADER: &str = "sample,timestamp,sequence,raw";
+
+/// To render a session's raw samples as the raw-waveform CSV, header first.
+pub fn raw_csv(batches: &[RecordedRawBatch]) -> String {
+    let mut csv = String::from(RAW_CSV_HEADER);
+    let mut sample_index: u64 = 0;
+    let sample_period_ms = 1_000.0 / f64::from(RAW_SAMPLE_RATE_HZ);
+    for batch in batches {
+        let sequence = batch
+            .after_sequence
+            .map(|sequence| sequence.to_string())
+            .unwrap_or_default();
+        let last_position = batch.samples.len().saturating_sub(1);
+        for (position, sample) in batch.samples.iter().enumerate() {
+            let offset_ms = ((last_position - position) as f64 * sample_period_ms).round() as i64;
+            let timestamp = format_iso8601_millis(batch.received_at_unix_ms as i64 - offset_ms);
+            csv.push('\n');
+            csv.push_str(&format!(
+                "{sample_index},{timestamp},{sequence},{}",
+                sample.value
+            ));
+            sample_index += 1;
+        }
+    }
+    csv
+}
+
+/// Header of the markers CSV written beside the recorder CSV. `sequence`
+/// is the last row recorded before the marker, to line the two files up.
+pub const MARKERS_CSV_HEADER: &str = "timestamp,sequence,label,category,note";
+
+/// To render a session's markers as the markers CSV, header first.
+pub fn markers_csv(markers: &[RecordingMarker]) -> String {
+    let mut csv = String::from(MARKERS_CSV_HEADER);
+    for marker in markers {
+        let sequence = marker
+            .after_sequence
+            .map(|sequence| sequence.to_string())
+            .unwrap_or_default();
+        csv.push('\n');
+        csv.push_str(
+            &[
+                format_iso8601_millis(marker.at_unix_ms as i64),
+                sequence,
+                quoted_cell(&marker.label),
+                quoted_cell(marker.category.as_deref().unwrap_or_default()),
+                quoted_cell(marker.note.as_deref().unwrap_or_default()),
+            ]
+            .join(","),
+        );
+    }
+    csv
+}
+
+// Labels and notes are typed by the operator, so they are quoted whenever
+// they could break the row apart.
+fn quoted_cell(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// To count the markers in a markers CSV, allowing for quoted cells that
+/// span lines.
+pub fn count_markers_csv_rows(content: &str) -> u32 {
+    let mut in_quotes = false;
+    let mut rows = 0;
+    let mut row_has_content = false;
+    for character in content.chars().chain(['\n']) {
+        match character {
+            '"' => in_quotes = !in_quotes,
+            '\n' if !in_quotes => {
+                rows += u32::from(row_has_content);
+                row_has_content = false;
+                continue;
+            }
+            _ => {}
+        }
+        row_has_content |= !character.is_whitespace();
+    }
+    // The header is not a marker.
+    rows.saturating_sub(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::raw_eeg_sample::RawEegSample;
+
+    // To split CSV content into records of unquoted cells, as a spreadsheet
+    // would read it.
+    fn read_csv_records(content: &str) -> Vec<Vec<String>> {
+        let mut records = vec![vec![String::new()]];
+        let mut in_quotes = false;
+        let mut characters = content.chars().peekable();
+        while let Some(character) = characters.next() {
+            let record = records.last_mut().unwrap();
+            match character {
+                '"' if in_quotes && characters.peek() == Some(&'"') => {
+                    characters.next();
+                    record.last_mut().unwrap().push('"');
+                }
+                '"' => in_quotes = !in_quotes,
+                ',' if !in_quotes => record.push(String::new()),
+                '\n' if !in_quotes => records.push(vec![String::new()]),
+                _ => record.last_mut().unwrap().push(character),
+            }
+        }
+        records
+    }
+
+    fn marker(label: &str, note: Option<&str>, after_sequence: Option<u64>) -> RecordingMarker {
+        RecordingMarker {
+            label: label.to_string(),
+            category: Some("stimulus".to_string()),
+            note: note.map(str::to_string),
+            at_unix_ms: 1_500,
+            after_sequence,
+        }
+    }
+
+    #[test]
+    fn markers_csv_quotes_cells_that_would_break_the_row() {
+        let markers = [
+            marker("plain", None, None),
+            marker("math, then reading", Some("said \"done\""), Some(3)),
+            marker("two\nlines", Some("a,\"b\"\r\nc"), Some(9)),
+        ];
+        let csv = markers_csv(&markers);
+        let records = read_csv_records(&csv);
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[0].join(","), MARKERS_CSV_HEADER);
+        for (record, marker) in records[1..].iter().zip(&markers) {
+            assert_eq!(record.len(), 5);
+            assert_eq!(record[0], "1970-01-01T00:00:01.500Z");
+            assert_eq!(
+                record[1],
+                marker
+                    .after_sequence
+                    .map_or(String::new(), |sequence| sequence.to_string())
+            );
+            assert_eq!(record[2], marker.label);
+            assert_eq!(record[3], "stimulus");
+            assert_eq!(record[4], marker.note.clone().unwrap_or_default());
+        }
+        assert_eq!(count_markers_csv_rows(&csv), 3);
+    }
+
+    #[test]
+    fn markers_csv_rows_are_counted_without_header_or_blank_lines() {
+        assert_eq!(count_markers_csv_rows(""), 0);
+        assert_eq!(count_markers_csv_rows(MARKERS_CSV_HEADER), 0);
+        assert_eq!(count_markers_csv_rows(&markers_csv(&[])), 0);
+        let csv = format!("{}\n\n", markers_csv(&[marker("a\n\nb", None, None)]));
+        assert_eq!(count_markers_csv_rows(&csv), 1);
+        let crlf =
+            markers_csv(&[marker("x", None, None), marker("y", None, None)]).replace('\n', "\r\n");
+        assert_eq!(count_markers_csv_rows(&crlf), 2);
+    }
+
+    fn batch(
+        received_at_unix_ms: u64,
+        after_sequence: Option<u64>,
+        values: &[i16],
+    ) -> RecordedRawBatch {
+        RecordedRawBatch {
+            received_at_unix_ms,
+            after_sequence,
+            samples: values.iter().map(|&value| RawEegSample { value }).collect(),
+        }
+    }
+
+    #[test]
+    fn raw_csv_numbers_samples_and_works_timestamps_back_from_arrival() {
+        let csv = raw_csv(&[
+            batch(1_000, None, &[10, -20, 30]),
+            batch(2_000, Some(4), &[7]),
+        ]);
+        assert_eq!(
+            csv,
+            "sample,timestamp,sequence,raw\n\
+             0,1970-01-01T00:00:00.996Z,,10\n\
+             1,1970-01-01T00:00:00.998Z,,-20\n\
+             2,1970-01-01T00:00:01.000Z,,30\n\
+             3,1970-01-01T00:00:02.000Z,4,7"
+        );
+    }
+}
@@ -0,0 +1,387 @@
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::{
+    adapters::{
+        iso8601::format_iso8601_millis,
+        session_csv::{
+            count_markers_csv_rows, parse_session_csv, session_csv_hash, sibling_csv_path_for,
+            MARKERS_CSV_SUFFIX, RAW_CSV_SUFFIX,
+        },
+    },
+    domain::session_summary::{
+        ScannedSessionCsv, SessionCsvScan, SessionSummary, SkippedSessionCsv,
+    },
+};
+
+/// To read every `.csv` directly inside `directories` for a reindex. Raw and
+/// markers sidecars are attached to the session CSV beside them rather than
+/// read as sessions; files that cannot be read, or are not recorder CSVs,
+/// are reported as skipped.
+pub fn scan_session_csvs(directories: &[PathBuf]) -> SessionCsvScan {
+    let mut scan = SessionCsvScan::default();
+    for directory in directories {
+        let entries = match std::fs::read_dir(directory) {
+            Ok(entries) => entries,
+            Err(error) => {
+                scan.skipped.push(skipped(
+                    directory,
+                    format!("cannot list directory: {error}"),
+                ));
+                continue;
+            }
+        };
+        let mut csv_paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension.eq_ignore_ascii_case("csv"))
+            })
+            .collect();
+        csv_paths.sort();
+        for csv_path in csv_paths.iter().filter(|path| !is_sidecar(path)) {
+            match scan_session_csv(csv_path) {
+                Ok(found) => scan.found.push(found),
+                Err(reason) => scan.skipped.push(skipped(csv_path, reason)),
+            }
+        }
+    }
+    scan
+}
+
+/// To give indexed sessions exported before content hashing their
+/// `content_hash`, read from their CSV where it still exists, so a reindex
+/// can recognise their copies. Returns whether any summary gained a hash.
+pub fn backfill_content_hashes(summaries: &mut [SessionSummary]) -> bool {
+    let mut backfilled = false;
+    for summary in summaries
+        .iter_mut()
+        .filter(|summary| summary.content_hash.is_none())
+    {
+        if let Ok(content) = std::fs::read_to_string(&summary.csv_path) {
+            summary.content_hash = Some(session_csv_hash(&content));
+            backfilled = true;
+        }
+    }
+    backfilled
+}
+
+fn skipped(path: &Path, reason: String) -> SkippedSessionCsv {
+    SkippedSessionCsv {
+        csv_path: path.to_string_lossy().into_owned(),
+        reason,
+    }
+}
+
+// A sidecar is `<stem>_raw.csv` or `<stem>_markers.csv` beside `<stem>.csv`;
+// one whose session CSV is gone is read like any other file.
+fn is_sidecar(path: &Path) -> bool {
+    let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) else {
+        return false;
+    };
+    [RAW_CSV_SUFFIX, MARKERS_CSV_SUFFIX].iter().any(|suffix| {
+        stem.strip_suffix(suffix)
+            .and_then(|stem| stem.strip_suffix('_'))
+            .is_some_and(|session_stem| {
+                path.with_file_name(format!("{session_stem}.csv")).is_file()
+            })
+    })
+}
+
+// Fills in everything the file can tell; the aggregates are left to the
+// reindex so they come from the same function as every other summary.
+fn scan_session_csv(csv_path: &Path) -> Result<ScannedSessionCsv, String> {
+    let content =
+        std::fs::read_to_string(csv_path).map_err(|error| format!("cannot read file: {error}"))?;
+    let rows = parse_session_csv(&content).map_err(|error| error.to_string())?;
+    let (Some(first_row), Some(last_row)) = (rows.first(), rows.last()) else {
+        return Err("no rows could be read".to_string());
+    };
+    let csv_path_text = csv_path.to_string_lossy().into_owned();
+    let existing_sibling = |suffix: &str| {
+        Some(sibling_csv_path_for(&csv_path_text, suffix))
+            .filter(|sibling| Path::new(sibling).is_file())
+    };
+    let markers_csv_path = existing_sibling(MARKERS_CSV_SUFFIX);
+    let marker_count = markers_csv_path
+        .as_deref()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .map_or(0, |markers| count_markers_csv_rows(&markers));
+    // Exported when the file was last written, failing that at its last row.
+    let exported_at_ms = std::fs::metadata(csv_path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map_or(last_row.timing.received_at_unix_ms, |since| {
+            since.as_millis() as u64
+        });
+    let content_hash = session_csv_hash(&content);
+    let summary = SessionSummary {
+        id: format!("reindexed-{content_hash}"),
+        subject_name: subject_name_from(csv_path),
+        exported_at: format_iso8601_millis(exported_at_ms as i64),
+        csv_path: csv_path_text.clone(),
+        sample_count: 0,
+        // Pauses are not in the CSV, so this spans first row to last.
+        duration_secs: last_row
+            .timing
+            .received_at_unix_ms
+            .saturating_sub(first_row.timing.received_at_unix_ms) as f64
+            / 1_000.0,
+        focused_count: 0,
+        unfocused_count: 0,
+        mean_alpha: 0.0,
+        mean_theta: 0.0,
+        mean_attention: 0.0,
+        mean_meditation: 0.0,
+        signal_quality_pct: 0.0,
+        raw_csv_path: existing_sibling(RAW_CSV_SUFFIX),
+        marker_count,
+        markers_csv_path,
+        recovered: false,
+        content_hash: Some(content_hash),
+    };
+    Ok(ScannedSessionCsv { summary, rows })
+}
+
+// Exports are named `<Subject>_<YYYY-MM-DD>_<HH-MM-SS>.csv` with spaces in
+// the subject turned into underscores; any other name is kept whole.
+fn subject_name_from(csv_path: &Path) -> String {
+    let stem = csv_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let is_stamp = |part: &str, length: usize| {
+        part.len() == length
+            && part
+                .chars()
+                .all(|character| character.is_ascii_digit() || character == '-')
+    };
+    let subject = match stem.rsplitn(3, '_').collect::<Vec<_>>().as_slice() {
+        [time, date, subject] if is_stamp(time, 8) && is_stamp(date, 10) => {
+            Some(subject.replace('_', " "))
+        }
+        _ => None,
+    };
+    subject.unwrap_or(stem)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        adapters::session_csv::{markers_csv, session_csv},
+        domain::{
+            eeg_packet::{EegPacket, PacketTiming},
+            errors::AppError,
+            ports::SessionRepository,
+            session_recording::{RecordedRow, RecordingMarker},
+        },
+        use_cases::manage_session_records::reindex_session_summaries,
+    };
+
+    // Directory under the system temp dir, removed when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path =
+                std::env::temp_dir().join(format!("cerebro-reindex-{name}-{}", std::process::id()));
+            let _ = std::fs::remove_dir_all(&path);
+            std::fs::create_dir_all(&path).unwrap();
+            Self(path)
+        }
+
+        fn write(&self, file_name: &str, content: &str) -> PathBuf {
+            let path = self.0.join(file_name);
+            std::fs::write(&path, content).unwrap();
+            path
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[derive(Default)]
+    struct MemoryRepository(Vec<SessionSummary>);
+
+    impl SessionRepository for MemoryRepository {
+        fn save(&mut self, summary: SessionSummary) -> Result<(), AppError> {
+            self.0.push(summary);
+            Ok(())
+        }
+
+        fn load_all(&self) -> Result<Vec<SessionSummary>, AppError> {
+            Ok(self.0.clone())
+        }
+
+        fn replace_all(&mut self, summaries: Vec<SessionSummary>) -> Result<(), AppError> {
+            self.0 = summaries;
+            Ok(())
+        }
+    }
+
+    fn recorder_csv(received_at_unix_ms: &[u64]) -> String {
+        let rows: Vec<RecordedRow> = received_at_unix_ms
+            .iter()
+            .zip(0..)
+            .map(|(&received_at_unix_ms, sequence)| RecordedRow {
+                packet: EegPacket {
+                    delta: 1,
+                    theta: 2,
+                    low_alpha: 3,
+                    high_alpha: 4,
+                    low_beta: 5,
+                    high_beta: 6,
+                    low_gamma: 7,
+                    mid_gamma: 8,
+                    attention: 60,
+                    meditation: 40,
+                    poor_signal_level: 0,
+                    device_millis: None,
+                    device_seq: None,
+                    blink_strength: None,
+                    extensions: Default::default(),
+                },
+                timing: PacketTiming {
+                    sequence,
+                    monotonic_ns: 0,
+                    received_at_unix_ms,
+                },
+                focus_label: 1,
+                focus_prediction: "N/A".to_string(),
+            })
+            .collect();
+        session_csv(&rows)
+    }
+
+    fn marker(label: &str) -> RecordingMarker {
+        RecordingMarker {
+            label: label.to_string(),
+            category: None,
+            note: None,
+            at_unix_ms: 1_000,
+            after_sequence: None,
+        }
+    }
+
+    fn legacy_summary(csv_path: &Path) -> SessionSummary {
+        SessionSummary {
+            id: "1700000000-a".to_string(),
+            subject_name: "Ada".to_string(),
+            exported_at: "2024-03-01T10:00:00.000Z".to_string(),
+            csv_path: csv_path.to_string_lossy().into_owned(),
+            sample_count: 2,
+            duration_secs: 2.0,
+            focused_count: 2,
+            unfocused_count: 0,
+            mean_alpha: 3.5,
+            mean_theta: 2.0,
+            mean_attention: 60.0,
+            mean_meditation: 40.0,
+            signal_quality_pct: 100.0,
+            raw_csv_path: None,
+            marker_count: 0,
+            markers_csv_path: None,
+            recovered: false,
+            content_hash: None,
+        }
+    }
+
+    #[test]
+    fn scan_attaches_sidecars_and_skips_files_that_are_not_sessions() {
+        let directory = TempDir::new("sidecars");
+        let content = recorder_csv(&[10_000, 12_500]);
+        let session_path = directory.write("Ada_Lovelace_2024-03-01_10-00-00.csv", &content);
+        let raw_path = directory.write(
+            "Ada_Lovelace_2024-03-01_10-00-00_raw.csv",
+            "sample,timestamp,sequence,raw",
+        );
+        directory.write(
+            "Ada_Lovelace_2024-03-01_10-00-00_markers.csv",
+            &markers_csv(&[marker("start, then\nstop"), marker("end")]),
+        );
+        let notes_path = directory.write("notes.csv", "when,what\nnow,tea");
+        // A sidecar whose session CSV is gone is read like any other file.
+        let orphan_path = directory.write("Gone_raw.csv", "sample,timestamp,sequence,raw");
+
+        let scan = scan_session_csvs(std::slice::from_ref(&directory.0));
+        assert_eq!(scan.found.len(), 1);
+        let found = &scan.found[0].summary;
+        assert_eq!(found.csv_path, session_path.to_string_lossy());
+        assert_eq!(found.subject_name, "Ada Lovelace");
+        assert_eq!(found.duration_secs, 2.5);
+        assert_eq!(found.raw_csv_path.as_deref(), raw_path.to_str());
+        assert_eq!(found.marker_count, 2);
+        assert_eq!(found.content_hash, Some(session_csv_hash(&content)));
+        assert_eq!(scan.found[0].rows.len(), 2);
+        let skipped: Vec<String> = scan
+            .skipped
+            .iter()
+            .map(|skipped| skipped.csv_path.clone())
+            .collect();
+        assert_eq!(
+            skipped,
+            vec![
+                orphan_path.to_string_lossy().into_owned(),
+                notes_path.to_string_lossy().into_owned(),
+            ]
+        );
+    }
+
+    #[test]
+    fn subject_is_read_from_stamped_export_names_only() {
+        let subject = |file_name: &str| subject_name_from(Path::new(file_name));
+        assert_eq!(
+            subject("Ada_Lovelace_2024-03-01_10-00-00.csv"),
+            "Ada Lovelace"
+        );
+        assert_eq!(subject("Grace_2024-03-01_10-00-00.csv"), "Grace");
+        assert_eq!(subject("pilot_run_2.csv"), "pilot_run_2");
+        assert_eq!(
+            subject("Ada_2024-3-1_10-00-00.csv"),
+            "Ada_2024-3-1_10-00-00"
+        );
+    }
+
+    #[test]
+    fn legacy_entries_are_hashed_from_their_csv_when_it_still_exists() {
+        let directory = TempDir::new("backfill");
+        let content = recorder_csv(&[10_000, 12_000]);
+        let kept_path = directory.write("kept.csv", &content);
+        let mut summaries = vec![
+            legacy_summary(&kept_path),
+            legacy_summary(&directory.0.join("deleted.csv")),
+        ];
+        assert!(backfill_content_hashes(&mut summaries));
+        assert_eq!(summaries[0].content_hash, Some(session_csv_hash(&content)));
+        assert_eq!(summaries[1].content_hash, None);
+        assert!(!backfill_content_hashes(&mut summaries));
+    }
+
+    #[test]
+    fn copy_of_a_legacy_entry_is_skipped_once_backfilled() {
+        let directory = TempDir::new("legacy-copy");
+        let content = recorder_csv(&[10_000, 12_000]);
+        let original_path = directory.write("original.csv", &content);
+        let copies = TempDir::new("legacy-copy-backup");
+        copies.write("original.csv", &content);
+        let mut repository = MemoryRepository(vec![legacy_summary(&original_path)]);
+
+        backfill_content_hashes(&mut repository.0);
+        let report = reindex_session_summaries(
+            scan_session_csvs(std::slice::from_ref(&copies.0)),
+            &mut repository,
+        )
+        .unwrap();
+        assert!(report.added.is_empty());
+        assert_eq!(
+            report.skipped[0].reason,
+            format!("same content as {}", original_path.display())
+        );
+        assert_eq!(repository.0.len(), 1);
+    }
+}
@@ -0,0 +1,425 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+use crate::domain::{
+    connection_status::LinkInfo,
+    eeg_packet::EegPacket,
+    eeg_reading::EegReading,
+    errors::AppError,
+    ground_truth::{GroundTruth, SimulatedState},
+    ports::EegSource,
+    source_event::SourceEvent,
+};
+
+// Longest single sleep inside next_event, so a stop request is honoured
+// promptly even with a slow packet interval.
+const SIMULATOR_POLL_INTERVAL: Duration = Duration::from_millis(100);
+// Octaves summed by the Voss-McCartney pink-noise generator.
+const PINK_NOISE_OCTAVES: usize = 8;
+// Poor-signal level TGAM reports when the electrode loses contact.
+const NO_CONTACT_SIGNAL_LEVEL: u8 = 200;
+
+/// Mean band powers and eSense values generated while in one state.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateProfile {
+    pub delta: f64,
+    pub theta: f64,
+    pub low_alpha: f64,
+    pub high_alpha: f64,
+    pub low_beta: f64,
+    pub high_beta: f64,
+    pub low_gamma: f64,
+    pub mid_gamma: f64,
+    pub attention: f64,
+    pub meditation: f64,
+}
+
+impl StateProfile {
+    fn band_means(&self) -> [f64; 8] {
+        [
+            self.delta,
+            self.theta,
+            self.low_alpha,
+            self.high_alpha,
+            self.low_beta,
+            self.high_beta,
+            self.low_gamma,
+            self.mid_gamma,
+        ]
+    }
+}
+
+/// Tunable parameters of the synthetic headset. Every field has a default,
+/// so `{}` or a partial object is a valid configuration.
+/// `transitions[i][j]` is the per-packet probability of moving from state i to
+/// state j, in the order focused, unfocused, drowsy; rows are normalised.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SimulatorConfig {
+    pub seed: u64,
+    pub packet_interval_ms: u64,
+    pub initial_state: SimulatedState,
+    pub focused: StateProfile,
+    pub unfocused: StateProfile,
+    pub drowsy: StateProfile,
+    pub transitions: [[f64; 3]; 3],
+    /// Spread of the multiplicative 1/f noise applied to every band (0 = none).
+    pub noise_amplitude: f64,
+    /// Per-packet probability of a motion/blink artifact.
+    pub artifact_probability: f64,
+    /// Per-packet probability that electrode contact drops out.
+    pub dropout_probability: f64,
+    /// Number of consecutive packets a dropout lasts.
+    pub dropout_packets: u32,
+}
+
+impl Default for SimulatorConfig {
+    fn default() -> Self {
+        Self {
+            seed: 0x00C0_FFEE,
+            packet_interval_ms: 1_000,
+            initial_state: SimulatedState::Unfocused,
+            // Beta-dominant, matching the original focused mock profile.
+            focused: StateProfile {
+                delta: 150_000.0,
+                theta: 80_000.0,
+                low_alpha: 60_000.0,
+                high_alpha: 50_000.0,
+                low_beta: 300_000.0,
+                high_beta: 250_000.0,
+                low_gamma: 40_000.0,
+                mid_gamma: 30_000.0,
+                attention: 75.0,
+                meditation: 40.0,
+            },
+            // Alpha-dominant, matching the original unfocused mock profile.
+            unfocused: StateProfile {
+                delta: 200_000.0,
+                theta: 90_000.0,
+                low_alpha: 350_000.0,
+                high_alpha: 300_000.0,
+                low_beta: 80_000.0,
+                high_beta: 70_000.0,
+                low_gamma: 30_000.0,
+                mid_gamma: 20_000.0,
+                attention: 35.0,
+                meditation: 65.0,
+            },
+            // Delta/theta-dominant with suppressed beta.
+            drowsy: StateProfile {
+                delta: 400_000.0,
+                theta: 220_000.0,
+                low_alpha: 120_000.0,
+                high_alpha: 60_000.0,
+                low_beta: 40_000.0,
+                high_beta: 30_000.0,
+                low_gamma: 15_000.0,
+                mid_gamma: 10_000.0,
+                attention: 20.0,
+                meditation: 70.0,
+            },
+            transitions: [[0.92, 0.06, 0.02], [0.08, 0.86, 0.06], [0.03, 0.12, 0.85]],
+            noise_amplitude: 0.25,
+            artifact_probability: 0.02,
+            dropout_probability: 0.005,
+            dropout_packets: 3,
+        }
+    }
+}
+
+// SplitMix64: tiny, seedable and good enough for test signals, which keeps
+// the simulator free of an external rand dependency.
+struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut mixed = self.state;
+        mixed = (mixed ^ (mixed >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        mixed = (mixed ^ (mixed >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        mixed ^ (mixed >> 31)
+    }
+
+    /// Uniform in [0, 1).
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Uniform in [-1, 1).
+    fn next_signed(&mut self) -> f64 {
+        self.next_f64() * 2.0 - 1.0
+    }
+}
+
+// Voss-McCartney 1/f noise: octave k is redrawn every 2^k samples, so slow
+// octaves dominate and the spectrum falls off at ~3 dB per octave.
+struct PinkNoise {
+    octaves: [f64; PINK_NOISE_OCTAVES],
+    counter: u32,
+}
+
+impl PinkNoise {
+    fn new(rng: &mut SeededRng) -> Self {
+        let mut octaves = [0.0; PINK_NOISE_OCTAVES];
+        for octave in octaves.iter_mut() {
+            *octave = rng.next_signed();
+        }
+        Self {
+            octaves,
+            counter: 0,
+        }
+    }
+
+    /// Next sample, roughly in [-1, 1].
+    fn next(&mut self, rng: &mut SeededRng) -> f64 {
+        self.counter = self.counter.wrapping_add(1);
+        let octave = (self.counter.trailing_zeros() as usize).min(PINK_NOISE_OCTAVES - 1);
+        self.octaves[octave] = rng.next_signed();
+        let white = rng.next_signed();
+        (self.octaves.iter().sum::<f64>() + white) / (PINK_NOISE_OCTAVES + 1) as f64 * 2.0
+    }
+}
+
+/// Deterministic packet generator: the same seed and configuration always
+/// produce the same sequence of states and packets.
+pub struct SyntheticEegGenerator {
+    config: SimulatorConfig,
+    rng: SeededRng,
+    band_noise: Vec<PinkNoise>,
+    esense_noise: [PinkNoise; 2],
+    state: SimulatedState,
+    dropout_remaining: u32,
+}
+
+impl SyntheticEegGenerator {
+    pub fn new(config: SimulatorConfig) -> Self {
+        let mut rng = SeededRng::new(config.seed);
+        let band_noise = (0..8).map(|_| PinkNoise::new(&mut rng)).collect();
+        let esense_noise = [PinkNoise::new(&mut rng), PinkNoise::new(&mut rng)];
+        Self {
+            state: config.initial_state,
+            config,
+            rng,
+            band_noise,
+            esense_noise,
+            dropout_remaining: 0,
+        }
+    }
+
+    /// To advance the Markov chain by one packet and generate that packet.
+    /// Returns the state the packet was generated in.
+    pub fn next_packet(&mut self) -> (SimulatedState, EegPacket) {
+        self.state = self.next_state();
+        let profile = match self.state {
+            SimulatedState::Focused => self.config.focused,
+            SimulatedState::Unfocused => self.config.unfocused,
+            SimulatedState::Drowsy => self.config.drowsy,
+        };
+
+        let mut bands = [0u32; 8];
+        for (index, mean) in profile.band_means().into_iter().enumerate() {
+            let noise = self.band_noise[index].next(&mut self.rng);
+            // Log-normal scaling keeps powers positive however large the noise.
+            bands[index] = (mean * (self.config.noise_amplitude * noise).exp()).max(0.0) as u32;
+        }
+        let attention_noise = self.esense_noise[0].next(&mut self.rng) * 10.0;
+        let meditation_noise = self.esense_noise[1].next(&mut self.rng) * 10.0;
+
+        let mut packet = EegPacket {
+            delta: bands[0],
+            theta: bands[1],
+            low_alpha: bands[2],
+            high_alpha: bands[3],
+            low_beta: bands[4],
+            high_beta: bands[5],
+            low_gamma: bands[6],
+            mid_gamma: bands[7],
+            attention: (profile.attention + attention_noise).clamp(0.0, 100.0) as u8,
+            meditation: (profile.meditation + meditation_noise).clamp(0.0, 100.0) as u8,
+            poor_signal_level: 0,
+            device_millis: None,
+            device_seq: None,
+            blink_strength: None,
+            extensions: BTreeMap::new(),
+        };
+        self.apply_artifacts(&mut packet);
+        (self.state, packet)
+    }
+
+    fn next_state(&mut self) -> SimulatedState {
+        let row = self.config.transitions[state_index(self.state)];
+        let total: f64 = row.iter().map(|weight| weight.max(0.0)).sum();
+        if total <= 0.0 {
+            return self.state;
+        }
+        let mut threshold = self.rng.next_f64() * total;
+        for (index, weight) in row.iter().enumerate() {
+            threshold -= weight.max(0.0);
+            if threshold < 0.0 {
+                return STATES[index];
+            }
+        }
+        self.state
+    }
+
+    // Artifacts mimic blinks and jaw/head motion: a broadband low-frequency
+    // surge with EMG leaking into gamma and a degraded (not lost) contact.
+    // Dropouts mimic the electrode lifting off the forehead.
+    fn apply_artifacts(&mut self, packet: &mut EegPacket) {
+        if self.dropout_remaining == 0 && self.rng.next_f64() < self.config.dropout_probability {
+            self.dropout_remaining = self.config.dropout_packets;
+        }
+        if self.dropout_remaining > 0 {
+            self.dropout_remaining -= 1;
+            packet.poor_signal_level = NO_CONTACT_SIGNAL_LEVEL;
+            packet.attention = 0;
+            packet.meditation = 0;
+            return;
+        }
+        if self.rng.next_f64() < self.config.artifact_probability {
+            packet.delta = packet.delta.saturating_mul(8);
+            packet.theta = packet.theta.saturating_mul(4);
+            packet.low_gamma = packet.low_gamma.saturating_mul(3);
+            packet.mid_gamma = packet.mid_gamma.saturating_mul(3);
+            packet.poor_signal_level = 25 + (self.rng.next_f64() * 26.0) as u8;
+        }
+    }
+}
+
+const STATES: [SimulatedState; 3] = [
+    SimulatedState::Focused,
+    SimulatedState::Unfocused,
+    SimulatedState::Drowsy,
+];
+
+fn state_index(state: SimulatedState) -> usize {
+    match state {
+        SimulatedState::Focused => 0,
+        SimulatedState::Unfocused => 1,
+        SimulatedState::Drowsy => 2,
+    }
+}
+
+/// EEG source that streams generator output at the configured packet rate
+/// and announces every state transition as ground truth.
+pub struct SyntheticEegSource {
+    generator: SyntheticEegGenerator,
+    packet_interval: Duration,
+    next_due: Instant,
+    last_state: Option<SimulatedState>,
+    pending: VecDeque<SourceEvent>,
+}
+
+impl SyntheticEegSource {
+    pub fn new(config: SimulatorConfig) -> Self {
+        Self {
+            packet_interval: Duration::from_millis(config.packet_interval_ms.max(1)),
+            generator: SyntheticEegGenerator::new(config),
+            next_due: Instant::now(),
+            last_state: None,
+            pending: VecDeque::new(),
+        }
+    }
+}
+
+impl EegSource for SyntheticEegSource {
+    fn start(&mut self) -> Result<LinkInfo, AppError> {
+        self.next_due = Instant::now();
+        Ok(LinkInfo {
+            port: "simulator".to_string(),
+            baud: None,
+        })
+    }
+
+    fn next_event(&mut self) -> Result<SourceEvent, AppError> {
+        if let Some(event) = self.pending.pop_front() {
+            return Ok(event);
+        }
+        let wait = self.next_due.saturating_duration_since(Instant::now());
+        if !wait.is_zero() {
+            std::thread::sleep(wait.min(SIMULATOR_POLL_INTERVAL));
+            return Ok(SourceEvent::Idle);
+        }
+        self.next_due += self.packet_interval;
+
+        let (state, packet) = self.generator.next_packet();
+        if self.last_state != Some(state) {
+            self.last_state = Some(state);
+            self.pending
+                .push_back(SourceEvent::GroundTruth(GroundTruth::from(state)));
+        }
+        self.pending
+            .push_back(SourceEvent::Reading(EegReading::Packet(packet)));
+        Ok(self.pending.pop_front().unwrap_or(SourceEvent::Idle))
+    }
+
+    fn stop(&mut self) {
+        self.pending.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packets(config: SimulatorConfig, count: usize) -> Vec<(SimulatedState, EegPacket)> {
+        let mut generator = SyntheticEegGenerator::new(config);
+        (0..count).map(|_| generator.next_packet()).collect()
+    }
+
+    #[test]
+    fn same_seed_yields_the_same_sequence() {
+        let first = packets(SimulatorConfig::default(), 500);
+        assert_eq!(first, packets(SimulatorConfig::default(), 500));
+
+        let reseeded = SimulatorConfig {
+            seed: 7,
+            ..SimulatorConfig::default()
+        };
+        assert_ne!(first, packets(reseeded, 500));
+    }
+
+    #[test]
+    fn ground_truth_matches_the_state_of_every_packet() {
+        let config = SimulatorConfig {
+            packet_interval_ms: 1,
+            ..SimulatorConfig::default()
+        };
+        let expected = packets(config.clone(), 300);
+        let mut source = SyntheticEegSource::new(config);
+        source.start().unwrap();
+
+        let mut announced: Option<SimulatedState> = None;
+        let mut transitions = 0;
+        let mut emitted = 0;
+        while emitted < expected.len() {
+            match source.next_event().unwrap() {
+                SourceEvent::GroundTruth(ground_truth) => {
+                    assert_eq!(ground_truth.focus_label, ground_truth.state.focus_label());
+                    assert_ne!(announced, Some(ground_truth.state), "repeated ground truth");
+                    announced = Some(ground_truth.state);
+                    transitions += 1;
+                }
+                SourceEvent::Reading(EegReading::Packet(packet)) => {
+                    let (state, expected_packet) = &expected[emitted];
+                    assert_eq!(announced, Some(*state), "packet {emitted}");
+                    assert_eq!(&packet, expected_packet);
+                    emitted += 1;
+                }
+                SourceEvent::Idle => {}
+                other => panic!("unexpected event {other:?}"),
+            }
+        }
+        // The default chain leaves its initial state well within 300 packets.
+        assert!(transitions > 1);
+    }
+}
@@ -0,0 +1,302 @@
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+use crate::{
+    adapters::{
+        reconnect_backoff::{ReconnectBackoff, ReconnectPolicy, ReconnectStep},
+        thinkgear_json_parser::parse_thinkgear_connector_message,
+    },
+    domain::{
+        connection_status::{ConnectionStatus, LinkInfo},
+        eeg_reading::EegReading,
+        errors::AppError,
+        link_diagnostics::{LineKind, LinkDiagnostics},
+        ports::EegSource,
+        source_event::SourceEvent,
+    },
+};
+
+const CONNECTOR_READ_TIMEOUT: Duration = Duration::from_millis(500);
+const CONNECTOR_CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Where the ThinkGear Connector listens and what to ask it for. Every field
+/// has a default, so `{ "type": "thinkGearConnector" }` reaches a connector
+/// running on this machine.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ThinkGearConnectorConfig {
+    pub address: String,
+    /// Ask for 512 Hz `rawEeg` samples as well as the ~1 Hz summaries.
+    pub enable_raw_output: bool,
+    pub reconnect: ReconnectPolicy,
+}
+
+impl Default for ThinkGearConnectorConfig {
+    fn default() -> Self {
+        Self {
+            address: "127.0.0.1:13854".to_string(),
+            enable_raw_output: true,
+            reconnect: ReconnectPolicy::default(),
+        }
+    }
+}
+
+/// EEG source that reads a MindWave through NeuroSky's ThinkGear Connector
+/// instead of our ESP32 bridge. The connector is a desktop app that can be
+/// restarted under us, so a closed socket is reconnected with backoff.
+pub struct ThinkGearConnectorSource {
+    config: ThinkGearConnectorConfig,
+    reader: Option<BufReader<TcpStream>>,
+    // Bytes of a message whose terminator has not arrived yet.
+    message_buffer: Vec<u8>,
+    pending: VecDeque<EegReading>,
+    reconnect: ReconnectBackoff,
+    diagnostics: LinkDiagnostics,
+}
+
+impl ThinkGearConnectorSource {
+    pub fn new(config: ThinkGearConnectorConfig) -> Self {
+        let reconnect = ReconnectBackoff::new(config.reconnect);
+        Self {
+            config,
+            reader: None,
+            message_buffer: Vec::with_capacity(512),
+            pending: VecDeque::new(),
+            reconnect,
+            // Replaced by the session's counters in attach_link_diagnostics.
+            diagnostics: LinkDiagnostics::new(Instant::now()),
+        }
+    }
+
+    // To drop the dead socket and schedule the first reopen attempt.
+    fn begin_reconnect(&mut self, reason: String) -> Result<SourceEvent, AppError> {
+        eprintln!("[IO] {reason}");
+        self.reader = None;
+        self.message_buffer.clear();
+        let status = self
+            .reconnect
+            .begin(&self.config.address, reason, Instant::now())?;
+        Ok(SourceEvent::Status(status))
+    }
+
+    // To advance the reconnect backoff by one poll window.
+    fn poll_reconnect(&mut self) -> Result<SourceEvent, AppError> {
+        let config = &self.config;
+        let step = self
+            .reconnect
+            .poll(&config.address, Instant::now(), || open_connector(config))?;
+        Ok(match step {
+            ReconnectStep::Waiting => SourceEvent::Idle,
+            ReconnectStep::Retrying(status) => SourceEvent::Status(status),
+            ReconnectStep::Reopened(reader) => {
+                self.reader = Some(reader);
+                SourceEvent::Status(ConnectionStatus::Reconnected)
+            }
+        })
+    }
+}
+
+// To connect to the connector and send the handshake that switches it from
+// its default binary output to JSON.
+fn open_connector(config: &ThinkGearConnectorConfig) -> Result<BufReader<TcpStream>, AppError> {
+    let address = &config.address;
+    let open_error = |error: std::io::Error| {
+        AppError::AcquisitionFailure(format!(
+            "failed to reach ThinkGear Connector at {address}: {error}"
+        ))
+    };
+    let socket_address = address
+        .to_socket_addrs()
+        .map_err(open_error)?
+        .next()
+        .ok_or_else(|| AppError::AcquisitionFailure(format!("{address} did not resolve")))?;
+    let mut stream = TcpStream::connect_timeout(&socket_address, CONNECTOR_CONNECT_TIMEOUT)
+        .map_err(open_error)?;
+    stream
+        .set_read_timeout(Some(CONNECTOR_READ_TIMEOUT))
+        .map_err(open_error)?;
+
+    let handshake = serde_json::json!({
+        "enableRawOutput": config.enable_raw_output,
+        "format": "Json",
+    });
+    stream
+        .write_all(handshake.to_string().as_bytes())
+        .map_err(open_error)?;
+    Ok(BufReader::new(stream))
+}
+
+impl EegSource for ThinkGearConnectorSource {
+    fn start(&mut self) -> Result<LinkInfo, AppError> {
+        self.reader = Some(open_connector(&self.config)?);
+        Ok(LinkInfo {
+            port: format!("tcp://{}", self.config.address),
+            baud: None,
+        })
+    }
+
+    fn next_event(&mut self) -> Result<SourceEvent, AppError> {
+        if let Some(reading) = self.pending.pop_front() {
+            return Ok(SourceEvent::Reading(reading));
+        }
+        let Some(reader) = self.reader.as_mut() else {
+            return self.poll_reconnect();
+        };
+
+        let read_result = read_connector_message(
+            reader,
+            &mut self.message_buffer,
+            &mut self.pending,
+            &self.diagnostics,
+        );
+        match read_result {
+            Ok(0) => self.begin_reconnect("ThinkGear Connector closed the connection".to_string()),
+            Ok(_) => Ok(self
+                .pending
+                .pop_front()
+                .map(SourceEvent::Reading)
+                .unwrap_or(SourceEvent::Idle)),
+            Err(error)
+                if error.kind() == std::io::ErrorKind::TimedOut
+                    || error.kind() == std::io::ErrorKind::WouldBlock =>
+            {
+                Ok(SourceEvent::Idle)
+            }
+            Err(error) => self.begin_reconnect(format!("ThinkGear Connector read error: {error}")),
+        }
+    }
+
+    fn stop(&mut self) {
+        self.reader = None;
+        self.message_buffer.clear();
+        self.reconnect.cancel();
+    }
+
+    fn attach_link_diagnostics(&mut self, diagnostics: LinkDiagnostics) {
+        self.diagnostics = diagnostics;
+    }
+}
+
+// To read one message and decode it. The connector terminates JSON messages
+// with `\r`; a trailing `\n` from other builds is trimmed before parsing. A
+// timeout leaves the partial message in `message_buffer` for the next call.
+// Each complete message is tallied in `diagnostics` as one line.
+fn read_connector_message(
+    reader: &mut impl BufRead,
+    message_buffer: &mut Vec<u8>,
+    readings: &mut VecDeque<EegReading>,
+    diagnostics: &LinkDiagnostics,
+) -> std::io::Result<usize> {
+    let bytes_read = reader.read_until(b'\r', message_buffer)?;
+    if message_buffer.ends_with(b"\r") {
+        let text = String::from_utf8_lossy(message_buffer);
+        let message = text.trim();
+        let mut decoded = Vec::new();
+        parse_thinkgear_connector_message(message, &mut decoded);
+        let kind = match decoded.first() {
+            Some(EegReading::Packet(_)) => LineKind::Packet,
+            Some(EegReading::Raw(_)) => LineKind::RawOnly,
+            Some(EegReading::Blink(_)) => LineKind::Blink,
+            None if message.is_empty() => LineKind::Blank,
+            // Status-only messages are JSON too; only noise counts as malformed.
+            None if serde_json::from_str::<serde_json::Value>(message).is_ok() => LineKind::Other,
+            None => LineKind::Malformed,
+        };
+        diagnostics.record_line(message_buffer.len(), matches!(text, Cow::Owned(_)), kind);
+        readings.extend(decoded);
+        message_buffer.clear();
+    }
+    Ok(bytes_read)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+    use std::net::TcpListener;
+
+    use super::*;
+    use crate::domain::raw_eeg_sample::RawEegSample;
+
+    // To read the handshake the source sends on connect; it carries no
+    // terminator, so read until the bytes form one JSON object.
+    fn read_handshake(peer: &mut TcpStream) -> serde_json::Value {
+        peer.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        let mut received = Vec::new();
+        let mut chunk = [0u8; 256];
+        loop {
+            if let Ok(handshake) = serde_json::from_slice(&received) {
+                return handshake;
+            }
+            let bytes_read = peer.read(&mut chunk).unwrap();
+            assert!(bytes_read > 0, "closed before the handshake");
+            received.extend_from_slice(&chunk[..bytes_read]);
+        }
+    }
+
+    #[test]
+    fn stub_connector_receives_the_handshake_and_its_messages_decode() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let mut source = ThinkGearConnectorSource::new(ThinkGearConnectorConfig {
+            address: address.clone(),
+            ..ThinkGearConnectorConfig::default()
+        });
+        let diagnostics = LinkDiagnostics::new(Instant::now());
+        source.attach_link_diagnostics(diagnostics.clone());
+        assert_eq!(source.start().unwrap().port, format!("tcp://{address}"));
+        let (mut peer, _) = listener.accept().unwrap();
+
+        assert_eq!(
+            read_handshake(&mut peer),
+            serde_json::json!({ "enableRawOutput": true, "format": "Json" })
+        );
+
+        let e_sense = r#"{"eSense":{"attention":61,"meditation":47},"eegPower":{"delta":10,"theta":2,"lowAlpha":3,"highAlpha":4,"lowBeta":5,"highBeta":6,"lowGamma":7,"highGamma":8},"poorSignalLevel":26}"#;
+        // The summary arrives split across two writes.
+        let (head, tail) = e_sense.split_at(40);
+        peer.write_all(head.as_bytes()).unwrap();
+        assert!(matches!(source.next_event().unwrap(), SourceEvent::Idle));
+        peer.write_all(format!("{tail}\r").as_bytes()).unwrap();
+        peer.write_all(b"{\"blinkStrength\":73}\r{\"rawEeg\":-12}\r")
+            .unwrap();
+        peer.write_all(b"{\"poorSignalLevel\":200,\"status\":\"scanning\"}\r")
+            .unwrap();
+        peer.write_all(b"garbage\r{\"rawEeg\":5}\r").unwrap();
+
+        let mut readings = Vec::new();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while readings.len() < 4 {
+            assert!(Instant::now() < deadline, "timed out after {readings:?}");
+            match source.next_event().unwrap() {
+                SourceEvent::Reading(reading) => readings.push(reading),
+                SourceEvent::Idle => {}
+                other => panic!("unexpected event {other:?}"),
+            }
+        }
+        let EegReading::Packet(packet) = &readings[0] else {
+            panic!("expected the eSense summary first, got {readings:?}");
+        };
+        assert_eq!((packet.attention, packet.meditation), (61, 47));
+        assert_eq!((packet.delta, packet.mid_gamma), (10, 8));
+        assert_eq!(packet.poor_signal_level, 26);
+        // The status-only and garbage messages yield nothing.
+        assert!(matches!(
+            readings[1..],
+            [
+                EegReading::Blink(73),
+                EegReading::Raw(RawEegSample { value: -12 }),
+                EegReading::Raw(RawEegSample { value: 5 })
+            ]
+        ));
+
+        let stats = diagnostics.snapshot(Instant::now());
+        assert_eq!(stats.lines_read, 6);
+        assert_eq!(stats.raw_only_lines, 2);
+        assert_eq!(stats.json_parse_failures, 1);
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::domain::{eeg_packet::EegPacket, eeg_reading::EegReading, raw_eeg_sample::RawEegSample};
+
+// One message from NeuroSky's ThinkGear Connector in JSON mode. The connector
+// sends the ~1 Hz `eSense`/`eegPower` summary, 512 Hz `rawEeg` samples and
+// occasional `blinkStrength` or `status` messages as separate objects, so
+// every field is optional.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConnectorMessage {
+    e_sense: Option<ConnectorESense>,
+    eeg_power: Option<ConnectorEegPower>,
+    poor_signal_level: Option<u8>,
+    raw_eeg: Option<i16>,
+    blink_strength: Option<u8>,
+}
+
+#[derive(Deserialize)]
+struct ConnectorESense {
+    attention: Option<u8>,
+    meditation: Option<u8>,
+}
+
+// The connector calls the 41-49.75 Hz band `highGamma`; it is the same band
+// the ESP32 sketch reports as `midGamma`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ConnectorEegPower {
+    delta: u32,
+    theta: u32,
+    low_alpha: u32,
+    high_alpha: u32,
+    low_beta: u32,
+    high_beta: u32,
+    low_gamma: u32,
+    high_gamma: u32,
+}
+
+/// To decode one ThinkGear Connector JSON message into the readings it
+/// carries. Status-only messages (e.g. `"status":"scanning"`) and malformed
+/// input produce nothing.
+pub fn parse_thinkgear_connector_message(message: &str, readings: &mut Vec<EegReading>) {
+    if message.is_empty() {
+        return;
+    }
+    let Ok(message) = serde_json::from_str::<ConnectorMessage>(message) else {
+        return;
+    };
+
+    if let Some(power) = message.eeg_power {
+        let e_sense = message.e_sense.as_ref();
+        readings.push(EegReading::Packet(EegPacket {
+            delta: power.delta,
+            theta: power.theta,
+            low_alpha: power.low_alpha,
+            high_alpha: power.high_alpha,
+            low_beta: power.low_beta,
+            high_beta: power.high_beta,
+            low_gamma: power.low_gamma,
+            mid_gamma: power.high_gamma,
+            attention: e_sense.and_then(|e_sense| e_sense.attention).unwrap_or(0),
+            meditation: e_sense.and_then(|e_sense| e_sense.meditation).unwrap_or(0),
+            poor_signal_level: message.poor_signal_level.unwrap_or(200),
+            device_millis: None,
+            device_seq: None,
+            blink_strength: None,
+            extensions: BTreeMap::new(),
+        }));
+    }
+    if let Some(value) = message.raw_eeg {
+        readings.push(EegReading::Raw(RawEegSample { value }));
+    }
+    if let Some(strength) = message.blink_strength {
+        readings.push(EegReading::Blink(strength));
+    }
+}
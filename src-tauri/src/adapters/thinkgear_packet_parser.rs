@@ -0,0 +1,323 @@
+use std::collections::BTreeMap;
+
+use crate::domain::{eeg_packet::EegPacket, eeg_reading::EegReading, raw_eeg_sample::RawEegSample};
+
+// NeuroSky ThinkGear serial packet layout:
+//   [0xAA][0xAA][PLENGTH][PAYLOAD × PLENGTH][CHKSUM]
+// CHKSUM is the one's complement of the low byte of the payload sum. The
+// payload is a sequence of data rows: [EXCODE 0x55]* [CODE] [VLENGTH] [VALUE],
+// where VLENGTH is only present for multi-byte codes (CODE >= 0x80).
+const SYNC_BYTE: u8 = 0xAA;
+const EXCODE_BYTE: u8 = 0x55;
+const MAX_PAYLOAD_LENGTH: usize = 169;
+
+const CODE_POOR_SIGNAL: u8 = 0x02;
+const CODE_ATTENTION: u8 = 0x04;
+const CODE_MEDITATION: u8 = 0x05;
+const CODE_BLINK: u8 = 0x16;
+const CODE_RAW: u8 = 0x80;
+const CODE_ASIC_EEG_POWER: u8 = 0x83;
+
+const ASIC_EEG_POWER_LENGTH: usize = 24; // eight 3-byte big-endian band powers
+
+enum ParserState {
+    AwaitingFirstSync,
+    AwaitingSecondSync,
+    AwaitingLength,
+    ReadingPayload { expected_length: usize },
+    AwaitingChecksum,
+}
+
+/// Incremental decoder for the ThinkGear binary protocol. Bytes can be fed in
+/// arbitrary chunks; the parser keeps its position across calls and silently
+/// resynchronizes on the next 0xAA 0xAA pair after a corrupt frame.
+pub struct ThinkGearParser {
+    state: ParserState,
+    payload: Vec<u8>,
+}
+
+impl Default for ThinkGearParser {
+    fn default() -> Self {
+        Self {
+            state: ParserState::AwaitingFirstSync,
+            payload: Vec::with_capacity(MAX_PAYLOAD_LENGTH),
+        }
+    }
+}
+
+impl ThinkGearParser {
+    /// To consume a chunk of serial bytes and append every reading decoded
+    /// from frames completed within it. Frames failing the checksum are dropped.
+    pub fn push_bytes(&mut self, bytes: &[u8], readings: &mut Vec<EegReading>) {
+        for &byte in bytes {
+            self.push_byte(byte, readings);
+        }
+    }
+
+    fn push_byte(&mut self, byte: u8, readings: &mut Vec<EegReading>) {
+        self.state = match self.state {
+            ParserState::AwaitingFirstSync if byte == SYNC_BYTE => ParserState::AwaitingSecondSync,
+            ParserState::AwaitingFirstSync => ParserState::AwaitingFirstSync,
+            ParserState::AwaitingSecondSync if byte == SYNC_BYTE => ParserState::AwaitingLength,
+            ParserState::AwaitingSecondSync => ParserState::AwaitingFirstSync,
+            // Extra sync bytes before PLENGTH are legal padding.
+            ParserState::AwaitingLength if byte == SYNC_BYTE => ParserState::AwaitingLength,
+            ParserState::AwaitingLength if usize::from(byte) > MAX_PAYLOAD_LENGTH => {
+                ParserState::AwaitingFirstSync
+            }
+            ParserState::AwaitingLength => {
+                self.payload.clear();
+                match usize::from(byte) {
+                    0 => ParserState::AwaitingChecksum,
+                    expected_length => ParserState::ReadingPayload { expected_length },
+                }
+            }
+            ParserState::ReadingPayload { expected_length } => {
+                self.payload.push(byte);
+                if self.payload.len() == expected_length {
+                    ParserState::AwaitingChecksum
+                } else {
+                    ParserState::ReadingPayload { expected_length }
+                }
+            }
+            ParserState::AwaitingChecksum => {
+                if payload_checksum(&self.payload) == byte {
+                    decode_payload(&self.payload, readings);
+                }
+                ParserState::AwaitingFirstSync
+            }
+        };
+    }
+}
+
+fn payload_checksum(payload: &[u8]) -> u8 {
+    !payload
+        .iter()
+        .fold(0u8, |sum, byte| sum.wrapping_add(*byte))
+}
+
+// Values collected from the rows of one payload. A band-power packet is only
+// produced when ASIC_EEG_POWER is present; the eSense values and signal
+// quality that accompany it in the same ~1 Hz frame are folded into it.
+#[derive(Default)]
+struct PayloadRows {
+    poor_signal: Option<u8>,
+    attention: Option<u8>,
+    meditation: Option<u8>,
+    band_powers: Option<[u32; 8]>,
+}
+
+// To walk the data rows of one verified payload. Unknown codes are skipped
+// using their declared length so firmware extensions never desync the walk.
+fn decode_payload(payload: &[u8], readings: &mut Vec<EegReading>) {
+    let mut rows = PayloadRows::default();
+    let mut cursor = 0;
+
+    while cursor < payload.len() {
+        while payload.get(cursor) == Some(&EXCODE_BYTE) {
+            cursor += 1;
+        }
+        let Some(&code) = payload.get(cursor) else {
+            break;
+        };
+        cursor += 1;
+
+        let value_length = if code >= 0x80 {
+            let Some(&length) = payload.get(cursor) else {
+                break;
+            };
+            cursor += 1;
+            usize::from(length)
+        } else {
+            1
+        };
+        let Some(value) = payload.get(cursor..cursor + value_length) else {
+            break;
+        };
+        cursor += value_length;
+
+        match code {
+            CODE_POOR_SIGNAL => rows.poor_signal = Some(value[0]),
+            CODE_ATTENTION => rows.attention = Some(value[0]),
+            CODE_MEDITATION => rows.meditation = Some(value[0]),
+            CODE_BLINK => readings.push(EegReading::Blink(value[0])),
+            CODE_RAW if value.len() == 2 => readings.push(EegReading::Raw(RawEegSample {
+                value: i16::from_be_bytes([value[0], value[1]]),
+            })),
+            CODE_ASIC_EEG_POWER if value.len() == ASIC_EEG_POWER_LENGTH => {
+                rows.band_powers = Some(decode_band_powers(value));
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(band_powers) = rows.band_powers {
+        readings.push(EegReading::Packet(build_band_power_packet(
+            &rows,
+            band_powers,
+        )));
+    }
+}
+
+// Each ASIC_EEG_POWER band is a 3-byte big-endian unsigned integer, in the
+// order delta, theta, lowAlpha, highAlpha, lowBeta, highBeta, lowGamma, midGamma.
+fn decode_band_powers(value: &[u8]) -> [u32; 8] {
+    let mut band_powers = [0u32; 8];
+    for (band, chunk) in band_powers.iter_mut().zip(value.chunks_exact(3)) {
+        *band = u32::from_be_bytes([0, chunk[0], chunk[1], chunk[2]]);
+    }
+    band_powers
+}
+
+// Defaults mirror parse_esp32_line so both wire formats yield identical packets.
+fn build_band_power_packet(rows: &PayloadRows, band_powers: [u32; 8]) -> EegPacket {
+    EegPacket {
+        delta: band_powers[0],
+        theta: band_powers[1],
+        low_alpha: band_powers[2],
+        high_alpha: band_powers[3],
+        low_beta: band_powers[4],
+        high_beta: band_powers[5],
+        low_gamma: band_powers[6],
+        mid_gamma: band_powers[7],
+        attention: rows.attention.unwrap_or(0),
+        meditation: rows.meditation.unwrap_or(0),
+        poor_signal_level: rows.poor_signal.unwrap_or(200),
+        device_millis: None,
+        device_seq: None,
+        blink_strength: None,
+        extensions: BTreeMap::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // To wrap a payload in sync bytes, length and a valid checksum.
+    fn frame(payload: &[u8]) -> Vec<u8> {
+        let mut bytes = vec![SYNC_BYTE, SYNC_BYTE, payload.len() as u8];
+        bytes.extend_from_slice(payload);
+        bytes.push(payload_checksum(payload));
+        bytes
+    }
+
+    // One ~1 Hz frame: signal quality, eSense values and the eight bands
+    // 1..=8, the first with all three bytes in use.
+    fn band_power_payload() -> Vec<u8> {
+        let mut payload = vec![CODE_POOR_SIGNAL, 0, CODE_ATTENTION, 61, CODE_MEDITATION, 42];
+        payload.extend_from_slice(&[CODE_ASIC_EEG_POWER, ASIC_EEG_POWER_LENGTH as u8]);
+        payload.extend_from_slice(&[0x01, 0x02, 0x03]);
+        for band in 2..=8u8 {
+            payload.extend_from_slice(&[0, 0, band]);
+        }
+        payload
+    }
+
+    fn parse(chunks: &[&[u8]]) -> Vec<EegReading> {
+        let mut parser = ThinkGearParser::default();
+        let mut readings = Vec::new();
+        for chunk in chunks {
+            parser.push_bytes(chunk, &mut readings);
+        }
+        readings
+    }
+
+    #[test]
+    fn checksum_is_the_complement_of_the_payload_sum() {
+        assert_eq!(payload_checksum(&[]), 0xFF);
+        assert_eq!(payload_checksum(&[0x80, 0x02, 0x00, 0x05]), !0x87);
+        // The sum wraps at one byte.
+        assert_eq!(payload_checksum(&[0xF0, 0x20]), !0x10);
+    }
+
+    #[test]
+    fn band_power_frame_folds_esense_values_into_one_packet() {
+        let readings = parse(&[&frame(&band_power_payload())]);
+        let [EegReading::Packet(packet)] = readings.as_slice() else {
+            panic!("expected one packet, got {readings:?}");
+        };
+        assert_eq!(packet.delta, 0x010203);
+        assert_eq!(
+            [packet.theta, packet.low_alpha, packet.mid_gamma],
+            [2, 3, 8]
+        );
+        assert_eq!(
+            (
+                packet.attention,
+                packet.meditation,
+                packet.poor_signal_level
+            ),
+            (61, 42, 0)
+        );
+    }
+
+    #[test]
+    fn raw_frame_decodes_a_signed_big_endian_sample() {
+        let readings = parse(&[&frame(&[CODE_RAW, 0x02, 0xFF, 0x38])]);
+        assert!(matches!(
+            readings.as_slice(),
+            [EegReading::Raw(RawEegSample { value: -200 })]
+        ));
+    }
+
+    #[test]
+    fn excode_prefixes_and_unknown_codes_are_skipped() {
+        let payload = [
+            EXCODE_BYTE,
+            EXCODE_BYTE,
+            0x07, // unknown single-byte code
+            9,
+            0x90, // unknown multi-byte code, skipped by its length
+            3,
+            0xAA,
+            0xBB,
+            0xCC,
+            CODE_BLINK,
+            120,
+        ];
+        assert!(matches!(
+            parse(&[&frame(&payload)]).as_slice(),
+            [EegReading::Blink(120)]
+        ));
+    }
+
+    #[test]
+    fn frame_with_a_bad_checksum_is_dropped() {
+        let mut corrupt = frame(&[CODE_BLINK, 77]);
+        *corrupt.last_mut().unwrap() ^= 0x01;
+        let good = frame(&[CODE_BLINK, 88]);
+        let readings = parse(&[&corrupt, &good]);
+        assert!(matches!(readings.as_slice(), [EegReading::Blink(88)]));
+    }
+
+    #[test]
+    fn frame_split_across_reads_is_reassembled() {
+        let bytes = frame(&band_power_payload());
+        for split in 1..bytes.len() {
+            let (head, tail) = bytes.split_at(split);
+            assert!(
+                matches!(parse(&[head, tail]).as_slice(), [EegReading::Packet(_)]),
+                "split at {split}"
+            );
+        }
+    }
+
+    #[test]
+    fn parser_resynchronizes_after_garbage() {
+        let mut bytes = vec![0x00, 0x13, SYNC_BYTE, 0x42, 0xFF];
+        // An oversized length is abandoned rather than waited out.
+        bytes.extend_from_slice(&[SYNC_BYTE, SYNC_BYTE, 0xFA, 0x01]);
+        bytes.extend(frame(&[CODE_RAW, 0x02, 0x00, 0x10]));
+        // Extra sync bytes before the length are legal padding.
+        bytes.push(SYNC_BYTE);
+        bytes.extend(frame(&[CODE_BLINK, 5]));
+        assert!(matches!(
+            parse(&[&bytes]).as_slice(),
+            [
+                EegReading::Raw(RawEegSample { value: 16 }),
+                EegReading::Blink(5)
+            ]
+        ));
+    }
+}
@@ -0,0 +1,58 @@
+use serde::Serialize;
+
+use crate::domain::errors::AppError;
+
+// What a source reports once its link is open: the port, address or file it
+// reads from, and the baud rate when the link is a serial port.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkInfo {
+    pub port: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baud: Option<u32>,
+}
+
+// Link state published on the `eeg-status` event, tagged by `state`, e.g.
+// `{ "state": "connected", "port": "COM3", "baud": 115200 }` or
+// `{ "state": "portBusy", "port": "COM3" }`. Failure variants carry enough
+// detail to diagnose a participant's setup without reading the console.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(tag = "state", rename_all = "camelCase")]
+pub enum ConnectionStatus {
+    Connecting,
+    Connected(LinkInfo),
+    /// The link is up but the headset has gone silent.
+    HeadsetNotDetected,
+    /// Another application holds the port open.
+    PortBusy {
+        port: String,
+    },
+    /// The port disappeared, e.g. the USB cable was pulled.
+    PortVanished {
+        port: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    Reconnecting {
+        attempt: u32,
+        max_attempts: u32,
+        last_error: String,
+    },
+    Reconnected,
+    Error {
+        reason: String,
+    },
+    /// The reader ended normally (stop requested or source exhausted).
+    Disconnected,
+}
+
+impl From<&AppError> for ConnectionStatus {
+    fn from(error: &AppError) -> Self {
+        match error {
+            AppError::PortBusy(port) => ConnectionStatus::PortBusy { port: port.clone() },
+            AppError::PortVanished(port) => ConnectionStatus::PortVanished { port: port.clone() },
+            other => ConnectionStatus::Error {
+                reason: other.to_string(),
+            },
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+/// Request the app can send to the ESP32 bridge firmware, tagged by `cmd`.
+/// Written as one JSON line with a host-assigned `id`, e.g.
+/// `{ "id": 4, "cmd": "setRawOutput", "enabled": false }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "cmd", rename_all = "camelCase")]
+pub enum DeviceCommand {
+    GetFirmwareVersion,
+    /// Forget the paired headset so the bridge pairs with the next one seen.
+    ResetPairing,
+    SetRawOutput {
+        enabled: bool,
+    },
+    /// Band-power output rate in Hz.
+    SetOutputRate {
+        hz: u16,
+    },
+}
+
+/// Firmware reply to one command, matched to it by `ack` == the request id,
+/// e.g. `{ "ack": 4, "ok": true }` or `{ "ack": 4, "ok": false, "error": "busy" }`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DeviceCommandAck {
+    #[serde(rename = "ack")]
+    pub id: u32,
+    pub ok: bool,
+    /// Command-specific payload, e.g. `{ "version": "1.4.0" }`.
+    #[serde(default)]
+    pub result: Option<serde_json::Value>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
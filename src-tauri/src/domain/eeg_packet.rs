@@ -1,9 +1,11 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 // One complete EEG measurement: eight frequency-band powers plus two eSense
 // metrics and a signal quality indicator, emitted at ~1 Hz by the ESP32 serial adapter.
 // Field names match the serde output consumed by the frontend `eeg-data` event.
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct EegPacket {
     pub delta: u32,
@@ -17,4 +19,30 @@ pub struct EegPacket {
     pub attention: u8,
     pub meditation: u8,
     pub poor_signal_level: u8, // 0 = perfect signal, 200 = no electrode contact
+    /// Firmware `millis()` when the packet was sent, if the sketch reports it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_millis: Option<u64>,
+    /// Firmware packet counter, if the sketch reports one; a gap means drops.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_seq: Option<u32>,
+    /// Strongest blink (0-255) detected since the previous packet, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blink_strength: Option<u8>,
+    /// Numeric fields some firmware adds beyond the standard set, e.g.
+    /// raw-signal markers, keyed by the name the firmware sent.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extensions: BTreeMap<String, f64>,
+}
+
+// When and in what order the reader received one packet, stamped as the
+// source hands it over rather than when the webview gets round to it.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PacketTiming {
+    /// Packets received since the reader started, counting from 0.
+    pub sequence: u64,
+    /// Nanoseconds since the reader started, on a clock that never steps.
+    pub monotonic_ns: u64,
+    /// Wall-clock receive time in milliseconds since the Unix epoch (UTC).
+    pub received_at_unix_ms: u64,
 }
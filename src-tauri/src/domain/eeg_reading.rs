@@ -0,0 +1,11 @@
+use crate::domain::{eeg_packet::EegPacket, raw_eeg_sample::RawEegSample};
+
+// Everything a wire-format parser can decode from the headset stream: a
+// complete ~1 Hz band-power packet, a single 512 Hz raw sample, or a blink
+// event carrying its strength (0–255). Every parser produces this same type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EegReading {
+    Packet(EegPacket),
+    Raw(RawEegSample),
+    Blink(u8),
+}
@@ -5,6 +5,16 @@ pub enum AppError {
     InferenceFailure(String),
     StorageFailure(String),
     SerializationFailure(String),
+    AcquisitionFailure(String),
+    /// The bridge firmware rejected a command, or never answered it.
+    DeviceCommandFailure(String),
+    /// The named port exists but another application holds it open.
+    PortBusy(String),
+    /// The named port or address is gone.
+    PortVanished(String),
+    /// A recording command did not fit the recording's state, e.g. pausing
+    /// when nothing is being recorded.
+    RecordingFailure(String),
 }
 
 impl fmt::Display for AppError {
@@ -15,6 +25,13 @@ impl fmt::Display for AppError {
             AppError::SerializationFailure(message) => {
                 write!(f, "Serialization error: {message}")
             }
+            AppError::AcquisitionFailure(message) => write!(f, "Acquisition error: {message}"),
+            AppError::DeviceCommandFailure(message) => {
+                write!(f, "Device command error: {message}")
+            }
+            AppError::PortBusy(port) => write!(f, "{port} is in use by another application"),
+            AppError::PortVanished(port) => write!(f, "{port} is no longer available"),
+            AppError::RecordingFailure(message) => write!(f, "Recording error: {message}"),
         }
     }
 }
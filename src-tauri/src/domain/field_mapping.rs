@@ -0,0 +1,100 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Keys of the standard ESP32 JSON schema that a mapping can target.
+pub const STANDARD_FIELDS: [&str; 16] = [
+    "poorSignal",
+    "attention",
+    "meditation",
+    "delta",
+    "theta",
+    "lowAlpha",
+    "highAlpha",
+    "lowBeta",
+    "highBeta",
+    "lowGamma",
+    "midGamma",
+    "raw",
+    "millis",
+    "seq",
+    "blinkStrength",
+    "ext",
+];
+
+/// Declarative profile that maps an alternate firmware's JSON onto the
+/// standard ESP32 schema, e.g. snake_case keys or a nested `eegPower`
+/// object. Standard fields without a rule keep their standard key, so the
+/// empty profile changes nothing. Validated when deserialized.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", try_from = "UncheckedFieldMapping")]
+pub struct FieldMapping {
+    /// Shown in the UI only, e.g. `"Lab B snake_case sketch"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Rules keyed by the standard field they produce.
+    pub fields: BTreeMap<String, FieldRule>,
+}
+
+/// Where one standard field comes from and how its value is converted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldRule {
+    /// Dot-separated path into the line's JSON, e.g. `eegPower.low_alpha`.
+    pub path: String,
+    /// Multiplier applied to the firmware value, e.g. for unit conversion.
+    #[serde(default = "unit_scale")]
+    pub scale: f64,
+    /// Added after scaling.
+    #[serde(default)]
+    pub offset: f64,
+    /// Used when the path is absent from a line.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<f64>,
+}
+
+// The shape a profile has on the wire, before its rules are checked.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UncheckedFieldMapping {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    fields: BTreeMap<String, FieldRule>,
+}
+
+impl TryFrom<UncheckedFieldMapping> for FieldMapping {
+    type Error = String;
+
+    fn try_from(unchecked: UncheckedFieldMapping) -> Result<Self, Self::Error> {
+        for (field, rule) in &unchecked.fields {
+            if !STANDARD_FIELDS.contains(&field.as_str()) {
+                return Err(format!(
+                    "unknown field `{field}` in mapping; expected one of {}",
+                    STANDARD_FIELDS.join(", ")
+                ));
+            }
+            if rule.path.split('.').any(str::is_empty) {
+                return Err(format!("field `{field}` has an empty path segment"));
+            }
+            if !rule.scale.is_finite() || !rule.offset.is_finite() {
+                return Err(format!("field `{field}` needs a finite scale and offset"));
+            }
+        }
+        Ok(FieldMapping {
+            name: unchecked.name,
+            fields: unchecked.fields,
+        })
+    }
+}
+
+impl FieldMapping {
+    /// To tell whether lines can be read as the standard schema directly.
+    pub fn is_identity(&self) -> bool {
+        self.fields.is_empty()
+    }
+}
+
+fn unit_scale() -> f64 {
+    1.0
+}
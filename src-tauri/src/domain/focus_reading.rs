@@ -16,3 +16,11 @@ pub fn focus_label_name(predicted_class: i64) -> String {
         _ => "N/A".to_string(),
     }
 }
+
+/// Feature state carried from one packet to the next of a single stream, so
+/// the temporal-Δdelta feature compares each packet with its own predecessor.
+/// Each stream keeps its own; the runner itself holds none.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InferenceHistory {
+    pub prev_delta_relative: f32,
+}
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+// Cognitive state a synthetic source is currently generating. Real headsets
+// never report this; it exists so classifier output can be scored against a
+// known answer.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SimulatedState {
+    Focused,
+    Unfocused,
+    Drowsy,
+}
+
+impl SimulatedState {
+    /// To map the state onto the binary label the classifier predicts:
+    /// only Focused counts as 1; Unfocused and Drowsy are both 0.
+    pub fn focus_label(self) -> i64 {
+        match self {
+            SimulatedState::Focused => 1,
+            SimulatedState::Unfocused | SimulatedState::Drowsy => 0,
+        }
+    }
+}
+
+// Known state behind the packets that follow, published on every transition.
+#[derive(Debug, Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct GroundTruth {
+    pub state: SimulatedState,
+    pub focus_label: i64,
+}
+
+impl From<SimulatedState> for GroundTruth {
+    fn from(state: SimulatedState) -> Self {
+        Self {
+            state,
+            focus_label: state.focus_label(),
+        }
+    }
+}
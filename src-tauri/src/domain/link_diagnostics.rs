@@ -0,0 +1,237 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
+
+use serde::Serialize;
+
+/// What one text line read from the link turned out to be.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    Blank,
+    /// A complete band-power packet.
+    Packet,
+    /// A line carrying only a 512 Hz `raw` sample.
+    RawOnly,
+    /// A blink event without band powers.
+    Blink,
+    /// Well-formed JSON that is not EEG data, e.g. a command ack.
+    Other,
+    /// Anything that does not parse as JSON, typically boot noise or a line
+    /// read at the wrong baud rate.
+    Malformed,
+}
+
+// Running tallies behind one reader session.
+#[derive(Debug)]
+struct LinkCounters {
+    started: Instant,
+    lines_read: u64,
+    bytes_read: u64,
+    lossy_utf8_lines: u64,
+    json_parse_failures: u64,
+    raw_only_lines: u64,
+    complete_packets: u64,
+    last_packet_at: Option<Instant>,
+    // Welford running mean and sum of squared deviations of the gaps between
+    // packets, so jitter needs no sample buffer.
+    interval_count: u64,
+    interval_mean_ms: f64,
+    interval_m2: f64,
+}
+
+/// Shared per-session link and parser counters. Sources tally what they read,
+/// the acquisition loop tallies complete packets, and commands read a
+/// snapshot. Cloning yields another handle to the same counters.
+#[derive(Debug, Clone)]
+pub struct LinkDiagnostics {
+    counters: Arc<Mutex<LinkCounters>>,
+}
+
+/// `link-stats` payload and `get_link_diagnostics` result.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkStats {
+    pub elapsed_secs: f64,
+    pub lines_read: u64,
+    pub bytes_read: u64,
+    /// Lines holding bytes that are not valid UTF-8.
+    pub lossy_utf8_lines: u64,
+    pub json_parse_failures: u64,
+    pub raw_only_lines: u64,
+    pub complete_packets: u64,
+    pub packets_per_second: f64,
+    pub mean_inter_packet_ms: f64,
+    /// Standard deviation of the gap between consecutive packets.
+    pub inter_packet_jitter_ms: f64,
+}
+
+impl LinkDiagnostics {
+    pub fn new(started: Instant) -> Self {
+        Self {
+            counters: Arc::new(Mutex::new(LinkCounters {
+                started,
+                lines_read: 0,
+                bytes_read: 0,
+                lossy_utf8_lines: 0,
+                json_parse_failures: 0,
+                raw_only_lines: 0,
+                complete_packets: 0,
+                last_packet_at: None,
+                interval_count: 0,
+                interval_mean_ms: 0.0,
+                interval_m2: 0.0,
+            })),
+        }
+    }
+
+    /// To tally bytes read outside line framing, e.g. ThinkGear binary chunks.
+    pub fn record_bytes(&self, bytes: usize) {
+        self.lock().bytes_read += bytes as u64;
+    }
+
+    /// To tally one line of `bytes` length, including its terminator.
+    pub fn record_line(&self, bytes: usize, lossy_utf8: bool, kind: LineKind) {
+        let mut counters = self.lock();
+        counters.lines_read += 1;
+        counters.bytes_read += bytes as u64;
+        counters.lossy_utf8_lines += u64::from(lossy_utf8);
+        match kind {
+            LineKind::Malformed => counters.json_parse_failures += 1,
+            LineKind::RawOnly => counters.raw_only_lines += 1,
+            LineKind::Blank | LineKind::Packet | LineKind::Blink | LineKind::Other => {}
+        }
+    }
+
+    /// To tally one complete packet and the gap since the previous one.
+    pub fn record_packet(&self, received: Instant) {
+        let mut counters = self.lock();
+        counters.complete_packets += 1;
+        if let Some(previous) = counters.last_packet_at {
+            let interval_ms = received.saturating_duration_since(previous).as_secs_f64() * 1_000.0;
+            counters.interval_count += 1;
+            let delta = interval_ms - counters.interval_mean_ms;
+            counters.interval_mean_ms += delta / counters.interval_count as f64;
+            counters.interval_m2 += delta * (interval_ms - counters.interval_mean_ms);
+        }
+        counters.last_packet_at = Some(received);
+    }
+
+    /// To read every counter plus the rates derived from them.
+    pub fn snapshot(&self, now: Instant) -> LinkStats {
+        let counters = self.lock();
+        let mean_inter_packet_ms = counters.interval_mean_ms;
+        LinkStats {
+            elapsed_secs: now
+                .saturating_duration_since(counters.started)
+                .as_secs_f64(),
+            lines_read: counters.lines_read,
+            bytes_read: counters.bytes_read,
+            lossy_utf8_lines: counters.lossy_utf8_lines,
+            json_parse_failures: counters.json_parse_failures,
+            raw_only_lines: counters.raw_only_lines,
+            complete_packets: counters.complete_packets,
+            packets_per_second: if mean_inter_packet_ms > 0.0 {
+                1_000.0 / mean_inter_packet_ms
+            } else {
+                0.0
+            },
+            mean_inter_packet_ms,
+            inter_packet_jitter_ms: if counters.interval_count > 1 {
+                (counters.interval_m2 / (counters.interval_count - 1) as f64).sqrt()
+            } else {
+                0.0
+            },
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, LinkCounters> {
+        self.counters
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn at(start: Instant, ms: u64) -> Instant {
+        start + Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn session_without_packets_reports_no_rate() {
+        let start = Instant::now();
+        let diagnostics = LinkDiagnostics::new(start);
+        diagnostics.record_line(12, false, LineKind::RawOnly);
+        diagnostics.record_line(9, true, LineKind::Malformed);
+        diagnostics.record_line(1, false, LineKind::Blank);
+
+        let stats = diagnostics.snapshot(at(start, 3_000));
+        assert_eq!(stats.elapsed_secs, 3.0);
+        assert_eq!(stats.lines_read, 3);
+        assert_eq!(stats.bytes_read, 22);
+        assert_eq!(stats.lossy_utf8_lines, 1);
+        assert_eq!(stats.json_parse_failures, 1);
+        assert_eq!(stats.raw_only_lines, 1);
+        assert_eq!(stats.complete_packets, 0);
+        assert_eq!(stats.packets_per_second, 0.0);
+        assert_eq!(stats.mean_inter_packet_ms, 0.0);
+        assert_eq!(stats.inter_packet_jitter_ms, 0.0);
+    }
+
+    #[test]
+    fn rate_starts_at_the_first_interval_and_jitter_at_the_second() {
+        let start = Instant::now();
+        let diagnostics = LinkDiagnostics::new(start);
+        // A lone packet has no interval yet.
+        diagnostics.record_packet(at(start, 400));
+        let stats = diagnostics.snapshot(at(start, 500));
+        assert_eq!(stats.complete_packets, 1);
+        assert_eq!(stats.packets_per_second, 0.0);
+
+        diagnostics.record_packet(at(start, 900));
+        let stats = diagnostics.snapshot(at(start, 1_000));
+        assert_eq!(stats.mean_inter_packet_ms, 500.0);
+        assert_eq!(stats.packets_per_second, 2.0);
+        // One interval has no spread to measure.
+        assert_eq!(stats.inter_packet_jitter_ms, 0.0);
+    }
+
+    #[test]
+    fn jitter_is_the_sample_deviation_of_the_gaps() {
+        let start = Instant::now();
+        let diagnostics = LinkDiagnostics::new(start);
+        for ms in [0, 900, 2_000, 2_900, 4_000] {
+            diagnostics.record_packet(at(start, ms));
+        }
+
+        let stats = diagnostics.snapshot(at(start, 4_000));
+        assert_eq!(stats.complete_packets, 5);
+        assert!((stats.mean_inter_packet_ms - 1_000.0).abs() < 1e-9);
+        assert!((stats.packets_per_second - 1.0).abs() < 1e-9);
+        // Gaps of 900, 1100, 900, 1100 ms: sqrt(4 * 100² / 3).
+        let expected = (40_000.0_f64 / 3.0).sqrt();
+        assert!((stats.inter_packet_jitter_ms - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn clones_share_counters_and_a_new_session_starts_from_zero() {
+        let start = Instant::now();
+        let previous = LinkDiagnostics::new(start);
+        let handle = previous.clone();
+        handle.record_bytes(64);
+        handle.record_packet(at(start, 0));
+        handle.record_packet(at(start, 1_000));
+        assert_eq!(previous.snapshot(at(start, 1_000)).bytes_read, 64);
+
+        let restarted = LinkDiagnostics::new(at(start, 5_000));
+        restarted.record_packet(at(start, 5_500));
+        let stats = restarted.snapshot(at(start, 6_000));
+        assert_eq!(stats.elapsed_secs, 1.0);
+        assert_eq!(stats.bytes_read, 0);
+        assert_eq!(stats.complete_packets, 1);
+        // The gap back to the previous session's last packet is not counted.
+        assert_eq!(stats.mean_inter_packet_ms, 0.0);
+    }
+}
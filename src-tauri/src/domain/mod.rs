@@ -1,5 +1,16 @@
+pub mod connection_status;
+pub mod device_command;
 pub mod eeg_packet;
+pub mod eeg_reading;
 pub mod errors;
+pub mod field_mapping;
 pub mod focus_reading;
+pub mod ground_truth;
+pub mod link_diagnostics;
 pub mod ports;
+pub mod raw_eeg_sample;
+pub mod serial_config;
+pub mod session_recording;
 pub mod session_summary;
+pub mod signal_quality;
+pub mod source_event;
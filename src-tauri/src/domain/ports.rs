@@ -1,6 +1,16 @@
 use crate::domain::{
-    eeg_packet::EegPacket, errors::AppError, focus_reading::FocusReading,
+    connection_status::{ConnectionStatus, LinkInfo},
+    eeg_packet::EegPacket,
+    errors::AppError,
+    focus_reading::{FocusReading, InferenceHistory},
+    ground_truth::GroundTruth,
+    link_diagnostics::{LinkDiagnostics, LinkStats},
+    raw_eeg_sample::RawEegBatch,
+    serial_config::SerialConfig,
+    session_recording::JournalEntry,
     session_summary::SessionSummary,
+    signal_quality::{GatedEegPacket, SignalQualityUpdate},
+    source_event::SourceEvent,
 };
 
 // Every external capability the use-case layer needs is expressed as a trait
@@ -9,9 +19,14 @@ use crate::domain::{
 
 pub trait InferenceRunner {
     /// To produce a focus label for one EEG packet without knowledge of the
-    /// underlying ONNX runtime. Mutates self because the temporal-delta
-    /// feature requires state across successive calls.
-    fn predict(&mut self, packet: &EegPacket) -> Result<FocusReading, AppError>;
+    /// underlying ONNX runtime. The temporal-delta feature needs the previous
+    /// packet of the same stream, so the caller owns that state in `history`
+    /// and one runner can serve several streams.
+    fn predict(
+        &mut self,
+        packet: &EegPacket,
+        history: &mut InferenceHistory,
+    ) -> Result<FocusReading, AppError>;
 }
 
 pub trait SessionRepository {
@@ -21,4 +36,63 @@ pub trait SessionRepository {
     /// To return all previously saved session summaries, or an empty
     /// collection when no sessions have been recorded yet.
     fn load_all(&self) -> Result<Vec<SessionSummary>, AppError>;
+
+    /// To overwrite the whole index, e.g. after a reindex merged into it.
+    fn replace_all(&mut self, summaries: Vec<SessionSummary>) -> Result<(), AppError>;
+}
+
+pub trait RecordingJournal {
+    /// To append one entry so that it survives a crash or power cut once
+    /// this returns.
+    fn append(&mut self, entry: &JournalEntry) -> Result<(), AppError>;
+}
+
+pub trait SerialConfigRepository {
+    /// To remember the line settings last used to open the named port.
+    fn save(&mut self, port_name: &str, config: SerialConfig) -> Result<(), AppError>;
+
+    /// To return the settings last used for the named port, if any.
+    fn load(&self, port_name: &str) -> Result<Option<SerialConfig>, AppError>;
+}
+
+pub trait EegSource {
+    /// To acquire the underlying device or stream before the first poll and
+    /// describe what was opened. An error here means the source never connected.
+    fn start(&mut self) -> Result<LinkInfo, AppError>;
+
+    /// To block for at most one short poll window and return what happened.
+    /// Sources return `Idle` on a quiet window so the caller can honour a
+    /// stop request; an error ends acquisition.
+    fn next_event(&mut self) -> Result<SourceEvent, AppError>;
+
+    /// To release the device or stream. Called once when acquisition ends.
+    fn stop(&mut self);
+
+    /// To hand the source the session's link counters before `start`.
+    /// Sources that see raw bytes or lines tally them; others need not.
+    fn attach_link_diagnostics(&mut self, _diagnostics: LinkDiagnostics) {}
+}
+
+pub trait EegEventSink {
+    /// To forward one complete ~1 Hz band-power packet with its gate verdict.
+    fn publish_packet(&self, packet: &GatedEegPacket);
+
+    /// To forward a batch of consecutive 512 Hz raw samples, with the
+    /// wall-clock time its last sample was read.
+    fn publish_raw_batch(&self, batch: &RawEegBatch, received_at_unix_ms: u64);
+
+    /// To forward one detected blink and its strength.
+    fn publish_blink(&self, strength: u8);
+
+    /// To forward a link state transition.
+    fn publish_status(&self, status: ConnectionStatus);
+
+    /// To forward a change in the reported signal quality tier.
+    fn publish_signal_quality(&self, update: &SignalQualityUpdate);
+
+    /// To forward the known state behind the packets that follow.
+    fn publish_ground_truth(&self, ground_truth: &GroundTruth);
+
+    /// To forward a periodic snapshot of the link and parser counters.
+    fn publish_link_stats(&self, stats: &LinkStats);
 }
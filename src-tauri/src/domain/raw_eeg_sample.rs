@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+/// Sampling rate of the TGAM raw waveform as forwarded by the ESP32 bridge.
+pub const RAW_SAMPLE_RATE_HZ: u32 = 512;
+
+// One 512 Hz sample of the unfiltered TGAM waveform, in raw ADC units.
+// Serialized as a bare number so batches stay compact on the IPC channel.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct RawEegSample {
+    pub value: i16,
+}
+
+// A run of consecutive raw samples forwarded to the frontend as one
+// `eeg-raw` event, so the webview is not flooded with 512 events per second.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RawEegBatch {
+    pub sample_rate_hz: u32,
+    pub samples: Vec<RawEegSample>,
+}
@@ -0,0 +1,210 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::errors::AppError;
+
+/// Line settings for a serial link. Every field has a default, so `{}` opens
+/// the port at the wire format's standard baud rate, 8N1, no flow control.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SerialConfig {
+    /// Falls back to the wire format's standard rate when omitted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baud_rate: Option<u32>,
+    /// 5 to 8.
+    pub data_bits: u8,
+    pub parity: SerialParity,
+    /// 1 or 2.
+    pub stop_bits: u8,
+    pub flow_control: SerialFlowControl,
+    /// At least 1; a zero timeout would spin the reader on empty reads.
+    pub read_timeout_ms: u64,
+    /// Many ESP32 dev boards wire DTR to the reset pin, so `deasserted`
+    /// opens the port without rebooting the board.
+    pub dtr: ControlLineState,
+    pub rts: ControlLineState,
+}
+
+impl Default for SerialConfig {
+    fn default() -> Self {
+        Self {
+            baud_rate: None,
+            data_bits: 8,
+            parity: SerialParity::None,
+            stop_bits: 1,
+            flow_control: SerialFlowControl::None,
+            read_timeout_ms: 500,
+            dtr: ControlLineState::Unchanged,
+            rts: ControlLineState::Unchanged,
+        }
+    }
+}
+
+impl SerialConfig {
+    /// To reject settings no serial driver accepts before anything is opened
+    /// or remembered with them, and to convert the numeric ones to the typed
+    /// values the port is opened with.
+    pub fn validate(&self) -> Result<SerialLineSettings, AppError> {
+        let data_bits = match self.data_bits {
+            5 => SerialDataBits::Five,
+            6 => SerialDataBits::Six,
+            7 => SerialDataBits::Seven,
+            8 => SerialDataBits::Eight,
+            other => {
+                return Err(AppError::AcquisitionFailure(format!(
+                    "unsupported data bits {other}; expected 5 to 8"
+                )))
+            }
+        };
+        let stop_bits = match self.stop_bits {
+            1 => SerialStopBits::One,
+            2 => SerialStopBits::Two,
+            other => {
+                return Err(AppError::AcquisitionFailure(format!(
+                    "unsupported stop bits {other}; expected 1 or 2"
+                )))
+            }
+        };
+        if self.read_timeout_ms == 0 {
+            return Err(AppError::AcquisitionFailure(
+                "read timeout must be at least 1 ms".to_string(),
+            ));
+        }
+        Ok(SerialLineSettings {
+            data_bits,
+            stop_bits,
+            read_timeout: Duration::from_millis(self.read_timeout_ms),
+        })
+    }
+}
+
+/// The numeric settings of a `SerialConfig` once `validate` has accepted them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SerialLineSettings {
+    pub data_bits: SerialDataBits,
+    pub stop_bits: SerialStopBits,
+    pub read_timeout: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SerialDataBits {
+    Five,
+    Six,
+    Seven,
+    Eight,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SerialStopBits {
+    One,
+    Two,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SerialParity {
+    #[default]
+    None,
+    Odd,
+    Even,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SerialFlowControl {
+    #[default]
+    None,
+    Software,
+    Hardware,
+}
+
+/// What to do with a modem control line when the port opens. `Unchanged`
+/// leaves it to the OS driver, which usually asserts DTR on open.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ControlLineState {
+    #[default]
+    Unchanged,
+    Asserted,
+    Deasserted,
+}
+
+impl ControlLineState {
+    /// To express the state as the level to drive, if any.
+    pub fn level(self) -> Option<bool> {
+        match self {
+            ControlLineState::Unchanged => None,
+            ControlLineState::Asserted => Some(true),
+            ControlLineState::Deasserted => Some(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_with(change: impl FnOnce(&mut SerialConfig)) -> SerialConfig {
+        let mut config = SerialConfig::default();
+        change(&mut config);
+        config
+    }
+
+    #[test]
+    fn defaults_validate_to_8n1_with_a_half_second_timeout() {
+        assert_eq!(
+            SerialConfig::default().validate().unwrap(),
+            SerialLineSettings {
+                data_bits: SerialDataBits::Eight,
+                stop_bits: SerialStopBits::One,
+                read_timeout: Duration::from_millis(500),
+            }
+        );
+    }
+
+    #[test]
+    fn every_supported_bit_count_converts() {
+        let converted: Vec<_> = (5..=8)
+            .map(|bits| {
+                config_with(|config| config.data_bits = bits)
+                    .validate()
+                    .unwrap()
+                    .data_bits
+            })
+            .collect();
+        assert_eq!(
+            converted,
+            [
+                SerialDataBits::Five,
+                SerialDataBits::Six,
+                SerialDataBits::Seven,
+                SerialDataBits::Eight
+            ]
+        );
+        let two_stop_bits = config_with(|config| config.stop_bits = 2);
+        assert_eq!(
+            two_stop_bits.validate().unwrap().stop_bits,
+            SerialStopBits::Two
+        );
+    }
+
+    #[test]
+    fn out_of_range_settings_are_rejected() {
+        let rejected = [
+            config_with(|config| config.data_bits = 4),
+            config_with(|config| config.data_bits = 9),
+            config_with(|config| config.stop_bits = 0),
+            config_with(|config| config.stop_bits = 3),
+            config_with(|config| config.read_timeout_ms = 0),
+        ];
+        for config in rejected {
+            assert!(config.validate().is_err(), "{config:?} was accepted");
+        }
+        let one_ms = config_with(|config| config.read_timeout_ms = 1);
+        assert_eq!(
+            one_ms.validate().unwrap().read_timeout,
+            Duration::from_millis(1)
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{
+    eeg_packet::{EegPacket, PacketTiming},
+    raw_eeg_sample::RawEegSample,
+};
+
+/// How rows are labelled: `recording` thresholds attention to collect
+/// training data, `live` labels each row with the loaded model's prediction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SessionMode {
+    Recording,
+    Live,
+}
+
+/// What the participant and operator chose when the recording started.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingOptions {
+    pub subject_name: String,
+    pub session_mode: SessionMode,
+    /// Attention at or above this labels a row Focused in `recording` mode.
+    pub attention_threshold: u8,
+}
+
+/// One recorder CSV row: an accepted packet, its receive timing and the
+/// focus label it was given. `focus_label` is 0 (Unfocused), 1 (Focused) or
+/// -1 when no label was available.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedRow {
+    #[serde(flatten)]
+    pub packet: EegPacket,
+    #[serde(flatten)]
+    pub timing: PacketTiming,
+    pub focus_label: i64,
+    pub focus_prediction: String,
+}
+
+/// A run of consecutive 512 Hz raw samples recorded alongside the rows.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedRawBatch {
+    /// Wall-clock time the batch was handed over, i.e. of its last sample.
+    pub received_at_unix_ms: u64,
+    /// `sequence` of the last row recorded before the batch, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after_sequence: Option<u64>,
+    pub samples: Vec<RawEegSample>,
+}
+
+/// A labelled moment in a recording, such as a task start or a stimulus
+/// onset, placed by the operator while the session runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingMarker {
+    pub label: String,
+    /// Free-form grouping, e.g. `stimulus` or `distraction`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    pub at_unix_ms: u64,
+    /// `sequence` of the last row recorded before the marker, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after_sequence: Option<u64>,
+}
+
+// One line of a recording journal. A journal opens with `started`, so a
+// journal found on disk always says whose session it holds and how its
+// rows were labelled; `finished` is written once the session was exported.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum JournalEntry {
+    #[serde(rename_all = "camelCase")]
+    Started {
+        recording_id: String,
+        device_id: String,
+        #[serde(flatten)]
+        options: RecordingOptions,
+        at_unix_ms: u64,
+    },
+    Row(RecordedRow),
+    Raw(RecordedRawBatch),
+    Marker(RecordingMarker),
+    #[serde(rename_all = "camelCase")]
+    Paused {
+        at_unix_ms: u64,
+    },
+    #[serde(rename_all = "camelCase")]
+    Resumed {
+        at_unix_ms: u64,
+    },
+    #[serde(rename_all = "camelCase")]
+    Finished {
+        at_unix_ms: u64,
+    },
+}
+
+/// A recording an earlier run left unfinished, offered for recovery at
+/// startup.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoverableSession {
+    pub recording_id: String,
+    pub device_id: String,
+    pub subject_name: String,
+    pub session_mode: SessionMode,
+    pub started_at_unix_ms: u64,
+    /// When the journal last heard from the recording, i.e. roughly when
+    /// the app stopped.
+    pub last_entry_at_unix_ms: u64,
+    pub row_count: u32,
+}
+
+/// `recording-row` payload, published for every row written to the journal
+/// so the UI can count rows and follow the focus labels without keeping
+/// the rows itself.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingProgress {
+    pub row_count: u32,
+    pub sequence: u64,
+    pub focus_label: i64,
+    pub focus_prediction: String,
+    /// Why a `live` row fell back to label -1, when inference failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inference_error: Option<String>,
+}
@@ -1,8 +1,10 @@
 use serde::{Deserialize, Serialize};
 
+use crate::domain::session_recording::RecordedRow;
+
 // Compact summary persisted to sessions.json after each export.
 // Contains pre-computed aggregates so the dashboard never re-reads CSV files.
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct SessionSummary {
     pub id: String,
@@ -18,4 +20,188 @@ pub struct SessionSummary {
     pub mean_attention: f64,
     pub mean_meditation: f64,
     pub signal_quality_pct: f64, // % of rows where poorSignalLevel === 0
+    // Sibling CSV holding the 512 Hz raw waveform, when one was recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_csv_path: Option<String>,
+    // Markers placed during the session, and the sibling CSV listing them.
+    #[serde(default)]
+    pub marker_count: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub markers_csv_path: Option<String>,
+    // Rebuilt from the journal of a recording an earlier run left unfinished.
+    #[serde(default)]
+    pub recovered: bool,
+    // Hash of the session CSV as written, so a reindex recognises copies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+}
+
+/// To fill in a summary's aggregates from the rows it covers. Identity,
+/// paths, duration and marker fields are kept from `summary`, so the caller
+/// decides those while every number derived from the rows is computed here,
+/// once, for every path that persists a session.
+///
+/// `mean_alpha` averages low and high alpha; `signal_quality_pct` is the
+/// share of rows with `poor_signal_level` 0. An empty recording yields zeros.
+pub fn summarize_recorded_rows(rows: &[RecordedRow], summary: SessionSummary) -> SessionSummary {
+    let row_total = rows.len().max(1) as f64;
+    let mean_of = |value: fn(&RecordedRow) -> f64| rows.iter().map(value).sum::<f64>() / row_total;
+    SessionSummary {
+        sample_count: rows.len() as u32,
+        focused_count: rows.iter().filter(|row| row.focus_label == 1).count() as u32,
+        unfocused_count: rows.iter().filter(|row| row.focus_label == 0).count() as u32,
+        mean_alpha: (mean_of(|row| f64::from(row.packet.low_alpha))
+            + mean_of(|row| f64::from(row.packet.high_alpha)))
+            / 2.0,
+        mean_theta: mean_of(|row| f64::from(row.packet.theta)),
+        mean_attention: mean_of(|row| f64::from(row.packet.attention)),
+        mean_meditation: mean_of(|row| f64::from(row.packet.meditation)),
+        signal_quality_pct: rows
+            .iter()
+            .filter(|row| row.packet.poor_signal_level == 0)
+            .count() as f64
+            / row_total
+            * 100.0,
+        ..summary
+    }
+}
+
+/// A recorder CSV found by a reindex: its rows, and a summary holding what
+/// the file itself tells (name, paths, sidecars, time span, content hash)
+/// with the aggregates still to be computed.
+#[derive(Debug, Clone)]
+pub struct ScannedSessionCsv {
+    pub summary: SessionSummary,
+    pub rows: Vec<RecordedRow>,
+}
+
+/// A CSV a reindex left out of the index, and why.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedSessionCsv {
+    pub csv_path: String,
+    pub reason: String,
+}
+
+/// Everything one reindex scan turned up, before it is merged into the index.
+#[derive(Debug, Clone, Default)]
+pub struct SessionCsvScan {
+    pub found: Vec<ScannedSessionCsv>,
+    pub skipped: Vec<SkippedSessionCsv>,
+}
+
+/// Outcome of a reindex, by CSV path.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionReindexReport {
+    pub added: Vec<String>,
+    pub updated: Vec<String>,
+    pub skipped: Vec<SkippedSessionCsv>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::eeg_packet::{EegPacket, PacketTiming};
+
+    fn row(
+        low_alpha: u32,
+        high_alpha: u32,
+        poor_signal_level: u8,
+        focus_label: i64,
+    ) -> RecordedRow {
+        RecordedRow {
+            packet: EegPacket {
+                delta: 0,
+                theta: 10,
+                low_alpha,
+                high_alpha,
+                low_beta: 0,
+                high_beta: 0,
+                low_gamma: 0,
+                mid_gamma: 0,
+                attention: 60,
+                meditation: 30,
+                poor_signal_level,
+                device_millis: None,
+                device_seq: None,
+                blink_strength: None,
+                extensions: Default::default(),
+            },
+            timing: PacketTiming {
+                sequence: 0,
+                monotonic_ns: 0,
+                received_at_unix_ms: 0,
+            },
+            focus_label,
+            focus_prediction: "N/A".to_string(),
+        }
+    }
+
+    fn blank_summary() -> SessionSummary {
+        SessionSummary {
+            id: "session-1".to_string(),
+            subject_name: "Ada".to_string(),
+            exported_at: "2024-03-01T10:00:00.000Z".to_string(),
+            csv_path: "Ada.csv".to_string(),
+            sample_count: 0,
+            duration_secs: 42.0,
+            focused_count: 0,
+            unfocused_count: 0,
+            mean_alpha: 0.0,
+            mean_theta: 0.0,
+            mean_attention: 0.0,
+            mean_meditation: 0.0,
+            signal_quality_pct: 0.0,
+            raw_csv_path: None,
+            marker_count: 2,
+            markers_csv_path: None,
+            recovered: false,
+            content_hash: None,
+        }
+    }
+
+    #[test]
+    fn empty_recording_yields_zeros_and_keeps_identity() {
+        let summary = summarize_recorded_rows(&[], blank_summary());
+        assert_eq!(summary, blank_summary());
+    }
+
+    #[test]
+    fn mean_alpha_averages_low_and_high_alpha() {
+        let rows = [row(100, 300, 0, 1), row(200, 600, 0, 1)];
+        let summary = summarize_recorded_rows(&rows, blank_summary());
+        // Low alpha means 150 and high alpha 450.
+        assert_eq!(summary.mean_alpha, 300.0);
+        assert_eq!(summary.mean_theta, 10.0);
+        assert_eq!(summary.mean_attention, 60.0);
+        assert_eq!(summary.mean_meditation, 30.0);
+    }
+
+    #[test]
+    fn signal_quality_counts_only_perfect_contact() {
+        let rows = [
+            row(0, 0, 0, 1),
+            row(0, 0, 1, 1),
+            row(0, 0, 0, 1),
+            row(0, 0, 49, 1),
+        ];
+        let summary = summarize_recorded_rows(&rows, blank_summary());
+        assert_eq!(summary.signal_quality_pct, 50.0);
+    }
+
+    #[test]
+    fn focus_counts_leave_unlabelled_rows_out() {
+        let rows = [
+            row(0, 0, 0, 1),
+            row(0, 0, 0, 0),
+            row(0, 0, 0, 0),
+            row(0, 0, 0, -1),
+        ];
+        let summary = summarize_recorded_rows(&rows, blank_summary());
+        assert_eq!(summary.sample_count, 4);
+        assert_eq!((summary.focused_count, summary.unfocused_count), (1, 2));
+        assert_eq!(summary.duration_secs, 42.0);
+        assert_eq!(summary.marker_count, 2);
+    }
 }
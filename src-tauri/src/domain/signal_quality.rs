@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+
+use crate::domain::eeg_packet::{EegPacket, PacketTiming};
+
+/// TGAM value meaning the electrode is not touching skin at all.
+pub const NO_CONTACT_SIGNAL_LEVEL: u8 = 200;
+
+/// Tunables for packet gating and tier reporting. Defaults reproduce the
+/// rules the frontend used to apply on its own.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SignalQualityConfig {
+    /// Packets at or above this poorSignalLevel are rejected.
+    pub rejection_threshold: u8,
+    /// How far below the threshold the level must fall before a Poor tier
+    /// recovers to Good, so a level hovering at the threshold cannot flap.
+    pub hysteresis_margin: u8,
+    /// How long a new tier must persist before it is reported.
+    pub tier_dwell_ms: u64,
+    /// Silence after which the headset is treated as gone even though the
+    /// link is still up (the ESP32 stays on USB when its Bluetooth drops).
+    pub stale_timeout_ms: u64,
+}
+
+impl Default for SignalQualityConfig {
+    fn default() -> Self {
+        Self {
+            rejection_threshold: 50,
+            hysteresis_margin: 10,
+            tier_dwell_ms: 1_500,
+            stale_timeout_ms: 2_500,
+        }
+    }
+}
+
+// Coarse signal quality as shown to the user. The link itself being down is
+// reported through `eeg-status`, not as a tier.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SignalTier {
+    Good,
+    Poor,
+    NoHeadset,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RejectionReason {
+    PoorSignal,
+    NoContact,
+}
+
+// Gate decision for one packet; flattened into the `eeg-data` payload.
+#[derive(Debug, Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct PacketVerdict {
+    pub accepted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rejection_reason: Option<RejectionReason>,
+}
+
+// `eeg-data` payload: the packet fields, its receive timing, `accepted` and,
+// for rejected packets, `rejectionReason`.
+#[derive(Debug, Serialize, Clone)]
+pub struct GatedEegPacket {
+    #[serde(flatten)]
+    pub packet: EegPacket,
+    #[serde(flatten)]
+    pub timing: PacketTiming,
+    #[serde(flatten)]
+    pub verdict: PacketVerdict,
+}
+
+// `signal-quality` payload, published whenever the reported tier changes.
+#[derive(Debug, Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct SignalQualityUpdate {
+    pub tier: SignalTier,
+    pub previous_tier: Option<SignalTier>,
+    pub poor_signal_level: u8,
+    /// True when the tier comes from the watchdog rather than a packet.
+    pub stale: bool,
+}
@@ -0,0 +1,21 @@
+use crate::domain::{
+    connection_status::ConnectionStatus, eeg_reading::EegReading, ground_truth::GroundTruth,
+};
+
+// One step of an EEG source as seen by the acquisition loop.
+#[derive(Debug, Clone)]
+pub enum SourceEvent {
+    /// A decoded packet, raw sample or blink.
+    Reading(EegReading),
+    /// A link transition the source detected itself (e.g. a reconnect).
+    Status(ConnectionStatus),
+    /// The known state behind subsequent readings (synthetic sources only).
+    GroundTruth(GroundTruth),
+    /// The poll window elapsed without data; lets the loop check its stop flag.
+    Idle,
+    /// Data is deliberately held back (e.g. a paused replay); silence while
+    /// paused is not a lost headset.
+    Paused,
+    /// The source is exhausted and will produce no further events.
+    Finished,
+}
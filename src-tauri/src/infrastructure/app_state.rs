@@ -1,22 +1,49 @@
+use std::collections::BTreeMap;
 use std::sync::{
     atomic::{AtomicBool, Ordering},
     Arc, Mutex,
 };
 
-use crate::adapters::onnx_inference_runner::OnnxInferenceRunner;
+use crate::{
+    adapters::{
+        csv_replay_source::ReplayControl, device_command_channel::DeviceCommandChannel,
+        onnx_inference_runner::OnnxInferenceRunner, synthetic_eeg_source::SyntheticEegGenerator,
+    },
+    domain::{
+        focus_reading::InferenceHistory, link_diagnostics::LinkDiagnostics,
+        session_recording::RecoverableSession,
+    },
+    use_cases::record_session::SessionRecorder,
+};
 
 /// Shared mutable state for one headset reader thread.
+/// `replay_control` is only set while the reader is replaying a recorded CSV;
+/// `serial_port` only while it reads a serial port, and `command_channel`
+/// only while that port carries the ESP32 sketch. `link_diagnostics` is
+/// kept after the reader stops so a failed session can still be inspected,
+/// and `recorder` lives as long as the headset, since pausing a scan stops
+/// the reader but not the recording.
 pub struct HeadsetConnectionState {
     pub stop_flag: Arc<AtomicBool>,
     pub thread: Option<std::thread::JoinHandle<()>>,
+    pub replay_control: Option<ReplayControl>,
+    pub serial_port: Option<String>,
+    pub command_channel: Option<DeviceCommandChannel>,
+    pub link_diagnostics: Option<LinkDiagnostics>,
+    pub recorder: SessionRecorder,
 }
 
 impl HeadsetConnectionState {
+    /// To tell whether the reader thread is alive and not asked to stop.
     pub fn is_running(&self) -> bool {
+        self.thread_alive() && !self.stop_flag.load(Ordering::Relaxed)
+    }
+
+    // A stopped thread stays alive until its current source poll returns.
+    fn thread_alive(&self) -> bool {
         self.thread
             .as_ref()
-            .map(|handle| !handle.is_finished())
-            .unwrap_or(false)
+            .is_some_and(|handle| !handle.is_finished())
     }
 
     /// To prepare the state for a new session by issuing a fresh stop flag.
@@ -25,14 +52,49 @@ impl HeadsetConnectionState {
     pub fn reset(&mut self) {
         self.stop_flag = Arc::new(AtomicBool::new(false));
         self.thread = None;
+        self.replay_control = None;
+        self.serial_port = None;
+        self.command_channel = None;
+        self.link_diagnostics = None;
     }
 
     /// To request a graceful shutdown of the active reader thread.
     pub fn stop(&mut self) {
         self.stop_flag.store(true, Ordering::Relaxed);
-        // Dropping the handle without joining lets the thread finish its current
-        // read-timeout cycle (~500 ms) rather than blocking the UI thread.
-        self.thread.take();
+        // The handle is kept rather than joined, so the UI thread never waits
+        // out the current source poll window (~500 ms for serial). The port
+        // stays held until the thread exits, and a restart joins it first.
+        self.replay_control = None;
+        self.command_channel = None;
+    }
+
+    /// To return the control handle of the running replay, if any.
+    pub fn active_replay(&self) -> Result<&ReplayControl, String> {
+        self.replay_control
+            .as_ref()
+            .filter(|_| self.is_running())
+            .ok_or_else(|| "No replay is running".to_string())
+    }
+
+    /// To return the firmware command channel of the running ESP32 reader.
+    pub fn active_command_channel(&self) -> Result<&DeviceCommandChannel, String> {
+        self.command_channel
+            .as_ref()
+            .filter(|_| self.is_running())
+            .ok_or_else(|| "No ESP32 bridge is connected".to_string())
+    }
+
+    /// To return the link counters of the running or most recent reader.
+    pub fn latest_link_diagnostics(&self) -> Result<&LinkDiagnostics, String> {
+        self.link_diagnostics
+            .as_ref()
+            .ok_or_else(|| "No EEG reader has run yet".to_string())
+    }
+
+    /// To return the serial port the reader thread holds, if any, including
+    /// one still winding down after a stop.
+    pub fn active_serial_port(&self) -> Option<&str> {
+        self.serial_port.as_deref().filter(|_| self.thread_alive())
     }
 }
 
@@ -41,12 +103,180 @@ impl Default for HeadsetConnectionState {
         Self {
             stop_flag: Arc::new(AtomicBool::new(false)),
             thread: None,
+            replay_control: None,
+            serial_port: None,
+            command_channel: None,
+            link_diagnostics: None,
+            recorder: SessionRecorder::default(),
         }
     }
 }
 
-/// Separate Tauri-managed state for the ESP32 serial reader.
-pub type Esp32ConnectionState = Mutex<HeadsetConnectionState>;
+/// Device id used by commands that do not name one, so single-headset
+/// callers never need to know about ids.
+pub const DEFAULT_DEVICE_ID: &str = "default";
+
+/// To resolve the optional device id a command was given; callers that only
+/// ever drive one headset leave it out.
+pub fn device_id_or_default(device_id: Option<String>) -> String {
+    device_id.unwrap_or_else(|| DEFAULT_DEVICE_ID.to_string())
+}
+
+/// Every headset reader the app has started, keyed by the device id the
+/// frontend chose for it. Stopped readers stay registered so their link
+/// counters can still be read.
+#[derive(Default)]
+pub struct HeadsetReaders {
+    readers: BTreeMap<String, HeadsetConnectionState>,
+}
+
+impl HeadsetReaders {
+    /// To look up the reader registered under `device_id`.
+    pub fn reader(&self, device_id: &str) -> Result<&HeadsetConnectionState, String> {
+        self.readers
+            .get(device_id)
+            .ok_or_else(|| format!("No EEG reader for device {device_id}"))
+    }
+
+    /// To look up the reader registered under `device_id` for changes,
+    /// without registering one.
+    pub fn registered_reader_mut(
+        &mut self,
+        device_id: &str,
+    ) -> Option<&mut HeadsetConnectionState> {
+        self.readers.get_mut(device_id)
+    }
+
+    /// To get the slot for `device_id`, registering an idle one if needed.
+    pub fn reader_mut(&mut self, device_id: &str) -> &mut HeadsetConnectionState {
+        self.readers.entry(device_id.to_string()).or_default()
+    }
+
+    /// To tell whether `device_id` has a reader thread still running.
+    pub fn is_running(&self, device_id: &str) -> bool {
+        self.readers
+            .get(device_id)
+            .is_some_and(HeadsetConnectionState::is_running)
+    }
+
+    /// To list every registered reader, running or not, by device id.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &HeadsetConnectionState)> {
+        self.readers
+            .iter()
+            .map(|(device_id, reader)| (device_id.as_str(), reader))
+    }
+
+    /// To find the running device, if any, that holds `port_name` open.
+    pub fn device_holding_port(&self, port_name: &str) -> Option<&str> {
+        self.iter()
+            .find(|(_, reader)| reader.active_serial_port() == Some(port_name))
+            .map(|(device_id, _)| device_id)
+    }
+
+    /// To list the serial ports held by running readers, with their devices.
+    pub fn active_serial_ports(&self) -> Vec<(String, String)> {
+        self.iter()
+            .filter_map(|(device_id, reader)| {
+                reader
+                    .active_serial_port()
+                    .map(|port| (device_id.to_string(), port.to_string()))
+            })
+            .collect()
+    }
+}
+
+/// Tauri-managed state for the EEG reader threads, one per headset, whichever
+/// source each drives.
+pub type EegReaderState = Mutex<HeadsetReaders>;
 /// Arc is required so load_model_files can replace the inner Option atomically
 /// without cloning the state handle out of the Tauri manager.
 pub type InferenceRunnerState = Arc<Mutex<Option<OnnxInferenceRunner>>>;
+/// Recordings found unfinished at startup and not yet recovered or
+/// discarded.
+pub type RecoverableSessionsState = Mutex<Vec<RecoverableSession>>;
+/// Temporal-delta state of the packets the webview sends to
+/// get_focus_prediction, kept apart from every recording's.
+pub type PredictionHistoryState = Mutex<InferenceHistory>;
+
+/// Generator behind get_mock_prediction and the temporal-delta state of the
+/// packets it drew, kept across calls so successive mock predictions follow
+/// one continuous synthetic session.
+pub struct MockPredictionStream {
+    pub generator: SyntheticEegGenerator,
+    pub history: InferenceHistory,
+}
+
+pub type MockGeneratorState = Mutex<MockPredictionStream>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::time::{Duration, Instant};
+
+    // To register `device_id` as reading `port` on a thread that, like a
+    // real reader between polls, only exits once `exit` is signalled or
+    // dropped, whatever its stop flag says.
+    fn spawn_reader(readers: &mut HeadsetReaders, device_id: &str, port: &str) -> mpsc::Sender<()> {
+        let (exit, exit_signal) = mpsc::channel::<()>();
+        let reader = readers.reader_mut(device_id);
+        reader.reset();
+        reader.serial_port = Some(port.to_string());
+        reader.thread = Some(std::thread::spawn(move || {
+            let _ = exit_signal.recv();
+        }));
+        exit
+    }
+
+    fn wait_until(condition: impl Fn() -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !condition() {
+            assert!(Instant::now() < deadline, "condition never held");
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn unknown_device_is_rejected_without_being_registered() {
+        let mut readers = HeadsetReaders::default();
+        let error = readers.reader("p2").err().unwrap();
+        assert!(error.contains("p2"), "{error}");
+        assert!(readers.registered_reader_mut("p2").is_none());
+        assert!(!readers.is_running("p2"));
+        assert_eq!(readers.iter().count(), 0);
+
+        readers.reader_mut("p2");
+        assert!(!readers.reader("p2").unwrap().is_running());
+        assert_eq!(readers.iter().count(), 1);
+    }
+
+    #[test]
+    fn omitted_device_id_falls_back_to_the_default_device() {
+        assert_eq!(device_id_or_default(None), DEFAULT_DEVICE_ID);
+        assert_eq!(device_id_or_default(Some("p2".to_string())), "p2");
+    }
+
+    #[test]
+    fn stopped_reader_holds_its_port_until_its_thread_exits() {
+        let mut readers = HeadsetReaders::default();
+        let exit_a = spawn_reader(&mut readers, "a", "COM3");
+        let _exit_b = spawn_reader(&mut readers, "b", "COM4");
+        assert_eq!(readers.device_holding_port("COM3"), Some("a"));
+        assert_eq!(
+            readers.active_serial_ports(),
+            vec![
+                ("a".to_string(), "COM3".to_string()),
+                ("b".to_string(), "COM4".to_string())
+            ]
+        );
+
+        readers.registered_reader_mut("a").unwrap().stop();
+        assert!(!readers.is_running("a"));
+        assert_eq!(readers.device_holding_port("COM3"), Some("a"));
+
+        drop(exit_a);
+        wait_until(|| readers.device_holding_port("COM3").is_none());
+        assert_eq!(readers.device_holding_port("COM4"), Some("b"));
+        assert!(readers.is_running("b"));
+    }
+}
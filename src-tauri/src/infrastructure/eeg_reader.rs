@@ -0,0 +1,347 @@
+use std::cell::Cell;
+use std::sync::{atomic::AtomicBool, Arc};
+use std::thread::JoinHandle;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::{
+    adapters::{
+        csv_replay_source::{CsvReplaySource, ReplayControl, ReplaySpeed},
+        device_command_channel::DeviceCommandChannel,
+        network_eeg_source::{NetworkEegSource, NetworkTransport},
+        reconnect_backoff::ReconnectPolicy,
+        serial_capture_replay_source::SerialCaptureReplaySource,
+        serial_eeg_source::{SerialEegSource, WireFormat},
+        synthetic_eeg_source::{SimulatorConfig, SyntheticEegSource},
+        thinkgear_connector_source::{ThinkGearConnectorConfig, ThinkGearConnectorSource},
+    },
+    domain::{
+        connection_status::ConnectionStatus,
+        field_mapping::FieldMapping,
+        ground_truth::GroundTruth,
+        link_diagnostics::{LinkDiagnostics, LinkStats},
+        ports::{EegEventSink, EegSource, InferenceRunner},
+        raw_eeg_sample::RawEegBatch,
+        serial_config::SerialConfig,
+        signal_quality::{GatedEegPacket, SignalQualityConfig, SignalQualityUpdate},
+    },
+    infrastructure::app_state::InferenceRunnerState,
+    use_cases::{record_session::SessionRecorder, run_eeg_acquisition::run_eeg_acquisition},
+};
+
+/// Frontend-selected EEG source. Tagged by `type` so the payload mirrors the
+/// frontend `EegSourceConfig` union, e.g. `{ "type": "esp32", "portName": "COM3" }`.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum EegSourceConfig {
+    #[serde(rename_all = "camelCase")]
+    Esp32 {
+        port_name: String,
+        #[serde(default)]
+        wire_format: WireFormat,
+        #[serde(default)]
+        serial: SerialConfig,
+        #[serde(default)]
+        reconnect: ReconnectPolicy,
+        /// Also logs every byte read from the port to this file.
+        #[serde(default)]
+        capture_path: Option<String>,
+        /// Rewrites an alternate sketch's JSON onto the standard schema.
+        #[serde(default)]
+        field_mapping: FieldMapping,
+    },
+    /// WiFi-mode ESP32, e.g. `{ "type": "network", "transport": "udp", "address": "0.0.0.0:5005" }`.
+    #[serde(rename_all = "camelCase")]
+    Network {
+        #[serde(default)]
+        transport: NetworkTransport,
+        address: String,
+        #[serde(default)]
+        reconnect: ReconnectPolicy,
+        #[serde(default)]
+        field_mapping: FieldMapping,
+    },
+    /// MindWave read through NeuroSky's ThinkGear Connector; options sit
+    /// alongside `type`, e.g. `{ "type": "thinkGearConnector", "enableRawOutput": false }`.
+    ThinkGearConnector {
+        #[serde(flatten)]
+        config: ThinkGearConnectorConfig,
+    },
+    #[serde(rename_all = "camelCase")]
+    Replay {
+        csv_path: String,
+        #[serde(default)]
+        speed: ReplaySpeed,
+    },
+    /// Serial byte capture fed back through the serial decoder, e.g.
+    /// `{ "type": "serialCapture", "capturePath": "...", "speed": "max" }`.
+    #[serde(rename_all = "camelCase")]
+    SerialCapture {
+        capture_path: String,
+        #[serde(default)]
+        speed: ReplaySpeed,
+        #[serde(default)]
+        field_mapping: FieldMapping,
+    },
+    /// Synthetic headset; simulator options sit alongside `type`,
+    /// e.g. `{ "type": "simulator", "seed": 7 }`. Boxed because the three
+    /// state profiles dwarf every other variant.
+    Simulator {
+        #[serde(flatten)]
+        config: Box<SimulatorConfig>,
+    },
+}
+
+/// A constructed source plus any control handle the command layer keeps so
+/// it can steer the source while the reader thread owns it.
+pub struct ConfiguredSource {
+    pub source: Box<dyn EegSource + Send>,
+    pub replay_control: Option<ReplayControl>,
+    pub command_channel: Option<DeviceCommandChannel>,
+}
+
+impl EegSourceConfig {
+    /// To name the serial port this source reads, for hotplug tracking.
+    pub fn serial_port_name(&self) -> Option<&str> {
+        match self {
+            EegSourceConfig::Esp32 { port_name, .. } => Some(port_name),
+            _ => None,
+        }
+    }
+
+    /// To construct the adapter that implements the configured source.
+    pub fn build_source(self) -> ConfiguredSource {
+        match self {
+            EegSourceConfig::Esp32 {
+                port_name,
+                wire_format,
+                serial,
+                reconnect,
+                capture_path,
+                field_mapping,
+            } => {
+                let source = SerialEegSource::new(
+                    port_name,
+                    wire_format,
+                    serial,
+                    reconnect,
+                    capture_path.map(Into::into),
+                    field_mapping,
+                );
+                ConfiguredSource {
+                    command_channel: source.command_channel(),
+                    source: Box::new(source),
+                    replay_control: None,
+                }
+            }
+            EegSourceConfig::Network {
+                transport,
+                address,
+                reconnect,
+                field_mapping,
+            } => ConfiguredSource {
+                source: Box::new(NetworkEegSource::new(
+                    transport,
+                    address,
+                    reconnect,
+                    field_mapping,
+                )),
+                replay_control: None,
+                command_channel: None,
+            },
+            EegSourceConfig::ThinkGearConnector { config } => ConfiguredSource {
+                source: Box::new(ThinkGearConnectorSource::new(config)),
+                replay_control: None,
+                command_channel: None,
+            },
+            EegSourceConfig::Replay { csv_path, speed } => {
+                let replay_control = ReplayControl::new(speed);
+                ConfiguredSource {
+                    source: Box::new(CsvReplaySource::new(
+                        csv_path.into(),
+                        replay_control.clone(),
+                    )),
+                    replay_control: Some(replay_control),
+                    command_channel: None,
+                }
+            }
+            EegSourceConfig::SerialCapture {
+                capture_path,
+                speed,
+                field_mapping,
+            } => ConfiguredSource {
+                source: Box::new(SerialCaptureReplaySource::new(
+                    capture_path.into(),
+                    speed,
+                    field_mapping,
+                )),
+                replay_control: None,
+                command_channel: None,
+            },
+            EegSourceConfig::Simulator { config } => ConfiguredSource {
+                source: Box::new(SyntheticEegSource::new(*config)),
+                replay_control: None,
+                command_channel: None,
+            },
+        }
+    }
+}
+
+/// Work to run once on the reader thread when its source first connects,
+/// e.g. remembering the serial settings that opened the port.
+pub type ConnectedHook = Box<dyn FnOnce() + Send>;
+
+/// Context passed into the EEG reader thread.
+pub struct EegReaderContext {
+    pub app: AppHandle,
+    pub device_id: String,
+    pub stop_flag: Arc<AtomicBool>,
+    pub source: Box<dyn EegSource + Send>,
+    pub signal_quality: SignalQualityConfig,
+    pub link_diagnostics: LinkDiagnostics,
+    pub recorder: SessionRecorder,
+    pub inference: InferenceRunnerState,
+    pub on_connected: Option<ConnectedHook>,
+    /// The device's previous reader thread, possibly still winding down
+    /// after a stop. Joined before the source opens, so the old session has
+    /// released its port and flushed its last samples.
+    pub previous_reader: Option<JoinHandle<()>>,
+}
+
+/// To drive the configured source on the reader thread and surface its
+/// output as `eeg-data`, `eeg-raw`, `eeg-blink`, `eeg-status`,
+/// `signal-quality`, `link-stats` and (synthetic sources only)
+/// `eeg-ground-truth` events, each tagged with the reader's device id.
+/// While the headset is recording, accepted packets are also journaled and
+/// announced as `recording-row`, and raw sample batches are journaled too.
+pub fn run_eeg_reader(mut ctx: EegReaderContext) {
+    if let Some(previous_reader) = ctx.previous_reader.take() {
+        if previous_reader.join().is_err() {
+            eprintln!("[IO] previous reader for device {} panicked", ctx.device_id);
+        }
+    }
+    let sink = TauriEventSink {
+        app: ctx.app,
+        device_id: ctx.device_id,
+        recorder: ctx.recorder,
+        inference: ctx.inference,
+        on_connected: Cell::new(ctx.on_connected),
+    };
+    run_eeg_acquisition(
+        ctx.source.as_mut(),
+        &sink,
+        &ctx.stop_flag,
+        ctx.signal_quality,
+        &ctx.link_diagnostics,
+    );
+}
+
+/// To publish `status` on `eeg-status` for `device_id` from outside its
+/// reader thread, e.g. once the hotplug watcher has stopped the reader.
+pub fn publish_device_status(app: &AppHandle, device_id: &str, status: ConnectionStatus) {
+    let _ = app.emit(
+        "eeg-status",
+        DeviceEvent {
+            device_id,
+            payload: status,
+        },
+    );
+}
+
+// Event payload tagged with the device it came from; the payload's own
+// fields sit alongside, e.g. `{ "deviceId": "a", "state": "connected", ... }`.
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct DeviceEvent<'a, T: Serialize> {
+    device_id: &'a str,
+    #[serde(flatten)]
+    payload: T,
+}
+
+// `eeg-blink` payload body; a bare number could not carry the device id.
+#[derive(Serialize, Clone)]
+struct BlinkEvent {
+    strength: u8,
+}
+
+/// Event sink that forwards one reader's acquisition output to the webview
+/// as Tauri events, and feeds the headset's recorder.
+pub struct TauriEventSink {
+    app: AppHandle,
+    device_id: String,
+    recorder: SessionRecorder,
+    inference: InferenceRunnerState,
+    on_connected: Cell<Option<ConnectedHook>>,
+}
+
+impl TauriEventSink {
+    fn emit(&self, event: &str, payload: impl Serialize + Clone) {
+        let _ = self.app.emit(
+            event,
+            DeviceEvent {
+                device_id: &self.device_id,
+                payload,
+            },
+        );
+    }
+
+    // Live-mode rows are classified here rather than by the webview, so the
+    // model sees each packet exactly once and a reload cannot skip rows.
+    fn record(&self, packet: &GatedEegPacket) {
+        if !self.recorder.is_recording() {
+            return;
+        }
+        let mut runner = self
+            .inference
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let runner = runner
+            .as_mut()
+            .map(|runner| runner as &mut dyn InferenceRunner);
+        match self.recorder.record_packet(packet, runner) {
+            Ok(Some(progress)) => self.emit("recording-row", progress),
+            Ok(None) => {}
+            Err(error) => eprintln!("[IO] recording row lost: {error}"),
+        }
+    }
+}
+
+impl EegEventSink for TauriEventSink {
+    fn publish_packet(&self, packet: &GatedEegPacket) {
+        self.emit("eeg-data", packet);
+        self.record(packet);
+    }
+
+    fn publish_raw_batch(&self, batch: &RawEegBatch, received_at_unix_ms: u64) {
+        self.emit("eeg-raw", batch);
+        if let Err(error) = self.recorder.record_raw_batch(batch, received_at_unix_ms) {
+            eprintln!("[IO] recording raw samples lost: {error}");
+        }
+    }
+
+    fn publish_blink(&self, strength: u8) {
+        self.emit("eeg-blink", BlinkEvent { strength });
+    }
+
+    fn publish_status(&self, status: ConnectionStatus) {
+        if matches!(status, ConnectionStatus::Connected(_)) {
+            if let Some(on_connected) = self.on_connected.take() {
+                on_connected();
+            }
+        }
+        self.emit("eeg-status", status);
+    }
+
+    fn publish_signal_quality(&self, update: &SignalQualityUpdate) {
+        self.emit("signal-quality", update);
+    }
+
+    fn publish_ground_truth(&self, ground_truth: &GroundTruth) {
+        self.emit("eeg-ground-truth", ground_truth);
+    }
+
+    fn publish_link_stats(&self, stats: &LinkStats) {
+        self.emit("link-stats", stats);
+    }
+}
@@ -1,3 +1,5 @@
 pub mod app_state;
-pub mod esp32_reader;
+pub mod eeg_reader;
+pub mod recording_recovery;
+pub mod serial_port_watcher;
 pub mod tauri_commands;
@@ -0,0 +1,244 @@
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Manager};
+
+use crate::{
+    adapters::file_recording_journal::{
+        list_recording_journals, read_recording_journal, FileRecordingJournal, JOURNAL_EXTENSION,
+    },
+    domain::{
+        errors::AppError,
+        ports::RecordingJournal,
+        session_recording::{JournalEntry, RecoverableSession},
+    },
+    use_cases::record_session::describe_interrupted_recording,
+};
+
+const RECORDINGS_DIR_NAME: &str = "recordings";
+
+/// To return the app-data directory that holds recording journals,
+/// creating it on first use.
+pub fn recordings_directory(app: &AppHandle) -> Result<PathBuf, String> {
+    let directory = app
+        .path()
+        .app_data_dir()
+        .map_err(|error| error.to_string())?
+        .join(RECORDINGS_DIR_NAME);
+    std::fs::create_dir_all(&directory).map_err(|error| error.to_string())?;
+    Ok(directory)
+}
+
+/// To return where the journal of `recording_id` lives.
+pub fn journal_path_for(app: &AppHandle, recording_id: &str) -> Result<PathBuf, String> {
+    Ok(recordings_directory(app)?.join(format!("{recording_id}.{JOURNAL_EXTENSION}")))
+}
+
+/// To find the recordings an earlier run left unfinished. Runs once at
+/// startup, before any recording can start, so every unfinished journal
+/// belongs to a crashed or killed run. Journals with nothing to recover are
+/// deleted; unreadable ones are left on disk so they can be salvaged by hand.
+pub fn scan_recoverable_sessions(app: &AppHandle) -> Vec<RecoverableSession> {
+    let sessions = recordings_directory(app).and_then(|directory| {
+        recoverable_sessions_in(&directory).map_err(|error| error.to_string())
+    });
+    sessions.unwrap_or_else(|error| {
+        eprintln!("[IO] cannot scan for interrupted recordings: {error}");
+        Vec::new()
+    })
+}
+
+/// To retire the journal of a recovered recording once it is exported. The
+/// session is already in sessions.json, so a journal that cannot be deleted
+/// is marked finished instead; the next startup then deletes it rather than
+/// offering the session again.
+pub fn retire_exported_journal(journal_path: PathBuf, at_unix_ms: u64) {
+    let Err(error) = std::fs::remove_file(&journal_path) else {
+        return;
+    };
+    eprintln!("[IO] failed to remove recovered recording journal: {error}");
+    if let Err(error) = mark_journal_exported(journal_path, at_unix_ms) {
+        eprintln!("[IO] failed to mark recovered recording journal: {error}");
+    }
+}
+
+fn mark_journal_exported(journal_path: PathBuf, at_unix_ms: u64) -> Result<(), AppError> {
+    FileRecordingJournal::reopen(journal_path)?.append(&JournalEntry::Finished { at_unix_ms })
+}
+
+fn recoverable_sessions_in(directory: &Path) -> Result<Vec<RecoverableSession>, AppError> {
+    let journals = list_recording_journals(directory)?;
+    let mut sessions = Vec::new();
+    for path in journals {
+        let entries = match read_recording_journal(&path) {
+            Ok(entries) => entries,
+            Err(error) => {
+                eprintln!("[IO] skipping recording journal: {error}");
+                continue;
+            }
+        };
+        match describe_interrupted_recording(&entries) {
+            Some(session) => sessions.push(session),
+            None => {
+                if let Err(error) = std::fs::remove_file(&path) {
+                    eprintln!("[IO] failed to remove spent recording journal: {error}");
+                }
+            }
+        }
+    }
+    Ok(sessions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{
+            eeg_packet::{EegPacket, PacketTiming},
+            session_recording::{RecordingOptions, SessionMode},
+            signal_quality::{GatedEegPacket, PacketVerdict},
+        },
+        use_cases::record_session::SessionRecorder,
+    };
+
+    // Directory under the system temp dir, removed when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir()
+                .join(format!("cerebro-recovery-{name}-{}", std::process::id()));
+            let _ = std::fs::remove_dir_all(&path);
+            std::fs::create_dir_all(&path).unwrap();
+            Self(path)
+        }
+
+        fn journal(&self, recording_id: &str) -> PathBuf {
+            self.0.join(format!("{recording_id}.{JOURNAL_EXTENSION}"))
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn accepted_packet(sequence: u64) -> GatedEegPacket {
+        GatedEegPacket {
+            packet: EegPacket {
+                delta: 1,
+                theta: 1,
+                low_alpha: 1,
+                high_alpha: 1,
+                low_beta: 1,
+                high_beta: 1,
+                low_gamma: 1,
+                mid_gamma: 1,
+                attention: 70,
+                meditation: 40,
+                poor_signal_level: 0,
+                device_millis: None,
+                device_seq: None,
+                blink_strength: None,
+                extensions: Default::default(),
+            },
+            timing: PacketTiming {
+                sequence,
+                monotonic_ns: 0,
+                received_at_unix_ms: 1_000 * (sequence + 2),
+            },
+            verdict: PacketVerdict {
+                accepted: true,
+                rejection_reason: None,
+            },
+        }
+    }
+
+    // To journal `rows` packets to `path` the way a live recording does,
+    // then drop the recorder unfinished, as a crash would.
+    fn record_unfinished(path: &Path, rows: u64) {
+        let recorder = SessionRecorder::default();
+        let recording_id = path.file_stem().unwrap().to_string_lossy().to_string();
+        let options = RecordingOptions {
+            subject_name: "Subject".to_string(),
+            session_mode: SessionMode::Recording,
+            attention_threshold: 50,
+        };
+        let journal = FileRecordingJournal::create(path.to_path_buf()).unwrap();
+        recorder
+            .start(recording_id, "a", options, Box::new(journal), 1_000)
+            .unwrap();
+        for sequence in 0..rows {
+            recorder
+                .record_packet(&accepted_packet(sequence), None)
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn journal_cut_short_mid_line_is_offered_up_to_its_last_whole_entry() {
+        let directory = TempDir::new("truncated");
+        let path = directory.journal("1-a");
+        record_unfinished(&path, 2);
+        let mut journal = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        std::io::Write::write_all(&mut journal, b"{\"kind\":\"row\",\"delta\":1,\"the").unwrap();
+
+        let sessions = recoverable_sessions_in(&directory.0).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].recording_id, "1-a");
+        assert_eq!(sessions[0].device_id, "a");
+        assert_eq!(sessions[0].row_count, 2);
+        assert_eq!(sessions[0].started_at_unix_ms, 1_000);
+        assert_eq!(sessions[0].last_entry_at_unix_ms, 3_000);
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn journal_without_rows_is_deleted_rather_than_offered() {
+        let directory = TempDir::new("header-only");
+        let path = directory.journal("1-a");
+        record_unfinished(&path, 0);
+
+        assert!(recoverable_sessions_in(&directory.0).unwrap().is_empty());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn exported_journal_is_deleted_or_marked_so_it_is_never_offered_again() {
+        let directory = TempDir::new("exported");
+        let deleted = directory.journal("1-a");
+        record_unfinished(&deleted, 1);
+        retire_exported_journal(deleted.clone(), 9_000);
+        assert!(!deleted.exists());
+
+        // What retire_exported_journal falls back to when deleting fails.
+        let marked = directory.journal("2-a");
+        record_unfinished(&marked, 1);
+        mark_journal_exported(marked.clone(), 9_000).unwrap();
+        assert!(
+            describe_interrupted_recording(&read_recording_journal(&marked).unwrap()).is_none()
+        );
+
+        assert!(recoverable_sessions_in(&directory.0).unwrap().is_empty());
+        assert!(!marked.exists());
+    }
+
+    #[test]
+    fn journal_corrupt_before_its_last_line_is_left_for_salvage() {
+        let directory = TempDir::new("corrupt");
+        let path = directory.journal("1-a");
+        record_unfinished(&path, 1);
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, format!("not json\n{content}")).unwrap();
+        let intact = directory.journal("2-a");
+        record_unfinished(&intact, 1);
+
+        let sessions = recoverable_sessions_in(&directory.0).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].recording_id, "2-a");
+        assert!(path.exists());
+    }
+}
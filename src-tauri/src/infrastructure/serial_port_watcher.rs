@@ -0,0 +1,269 @@
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::{
+    adapters::serial_port_probe::{list_serial_port_descriptors, SerialPortDescriptor},
+    domain::connection_status::ConnectionStatus,
+    infrastructure::{app_state::EegReaderState, eeg_reader::publish_device_status},
+};
+
+// Enumeration is cheap on every platform we ship, and one second is well
+// inside the time it takes someone to look back at the selector.
+const PORT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+// Polls a running reader's port must stay missing before its device is
+// released. A USB re-enumeration or an ESP32 brown-out drops the port for a
+// second or two, which the reader's reconnect backoff rides out.
+const UNPLUG_GRACE_POLLS: u32 = 5;
+
+/// `serial-ports-changed` payload: the full current list plus what changed
+/// since the previous poll.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SerialPortsChanged {
+    pub ports: Vec<SerialPortDescriptor>,
+    pub added: Vec<SerialPortDescriptor>,
+    pub removed: Vec<String>,
+    /// Running readers whose port is among `removed`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub active_ports_removed: Vec<ActivePortRemoved>,
+}
+
+/// A running reader's port that has just disappeared.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivePortRemoved {
+    pub device_id: String,
+    pub port: String,
+}
+
+/// To poll the OS port list for the lifetime of the app and emit
+/// `serial-ports-changed` whenever a port appears or disappears, so the
+/// selector stays current. A device whose port stays missing for
+/// `UNPLUG_GRACE_POLLS` polls has its reader stopped and its recording
+/// paused here, so the session ends well before the reader's reconnect
+/// budget runs out, and even when no window is listening.
+pub fn spawn_serial_port_watcher(app: AppHandle) {
+    std::thread::spawn(move || {
+        let mut known_ports = list_serial_port_descriptors();
+        let mut missing_polls = BTreeMap::new();
+        loop {
+            std::thread::sleep(PORT_POLL_INTERVAL);
+            let current_ports = list_serial_port_descriptors();
+            let active_ports = app
+                .state::<EegReaderState>()
+                .lock()
+                .map(|readers| readers.active_serial_ports())
+                .unwrap_or_default();
+            if let Some(change) = diff_ports(&known_ports, &current_ports, &active_ports) {
+                let _ = app.emit("serial-ports-changed", change);
+            }
+            for (device_id, port) in
+                unplugged_devices(&mut missing_polls, &current_ports, active_ports)
+            {
+                release_unplugged_device(&app, &device_id, port);
+            }
+            known_ports = current_ports;
+        }
+    });
+}
+
+// To count, per running device and port, the consecutive polls the port has
+// been missing, and return the devices, with their port, whose port has now
+// been missing for the whole grace period. A port that comes back, or a
+// reader that stopped, starts the count over.
+fn unplugged_devices(
+    missing_polls: &mut BTreeMap<(String, String), u32>,
+    current: &[SerialPortDescriptor],
+    active_ports: Vec<(String, String)>,
+) -> Vec<(String, String)> {
+    let missing: Vec<(String, String)> = active_ports
+        .into_iter()
+        .filter(|(_, port)| !current.iter().any(|known| &known.port_name == port))
+        .collect();
+    missing_polls.retain(|device_port, _| missing.contains(device_port));
+    let mut unplugged = Vec::new();
+    for device_port in missing {
+        let polls = missing_polls.entry(device_port.clone()).or_insert(0);
+        *polls += 1;
+        if *polls == UNPLUG_GRACE_POLLS {
+            unplugged.push(device_port);
+        }
+    }
+    unplugged
+}
+
+// To stop the reader of a device whose port vanished and wait for it, so the
+// trailing raw samples it flushes still reach the recording, then tell the
+// webview the port is gone. The recording is then paused: its duration ends
+// at the unplug, and it stays open for finish_recording to export or for a
+// restarted reader to resume.
+fn release_unplugged_device(app: &AppHandle, device_id: &str, port: String) {
+    let released = app
+        .state::<EegReaderState>()
+        .lock()
+        .ok()
+        .and_then(|mut readers| {
+            let reader = readers.registered_reader_mut(device_id)?;
+            let thread = reader.thread.take();
+            reader.stop();
+            Some((thread, reader.recorder.clone()))
+        });
+    let Some((thread, recorder)) = released else {
+        return;
+    };
+    if let Some(thread) = thread {
+        if thread.join().is_err() {
+            eprintln!("[IO] reader for unplugged device {device_id} panicked");
+        }
+    }
+    // A stopped reader publishes no final status of its own.
+    publish_device_status(app, device_id, ConnectionStatus::PortVanished { port });
+    if !recorder.is_recording() {
+        return;
+    }
+    let now_unix_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64);
+    if let Err(error) = recorder.pause(now_unix_ms) {
+        eprintln!("[IO] failed to pause recording of unplugged device {device_id}: {error}");
+    }
+}
+
+// To compare two snapshots by port name. Returns None when nothing changed.
+// `active_ports` pairs each running device with the port it reads.
+fn diff_ports(
+    previous: &[SerialPortDescriptor],
+    current: &[SerialPortDescriptor],
+    active_ports: &[(String, String)],
+) -> Option<SerialPortsChanged> {
+    let added: Vec<SerialPortDescriptor> = current
+        .iter()
+        .filter(|port| {
+            !previous
+                .iter()
+                .any(|known| known.port_name == port.port_name)
+        })
+        .cloned()
+        .collect();
+    let removed: Vec<String> = previous
+        .iter()
+        .filter(|known| !current.iter().any(|port| port.port_name == known.port_name))
+        .map(|known| known.port_name.clone())
+        .collect();
+    if added.is_empty() && removed.is_empty() {
+        return None;
+    }
+    Some(SerialPortsChanged {
+        ports: current.to_vec(),
+        added,
+        active_ports_removed: active_ports
+            .iter()
+            .filter(|(_, port)| removed.contains(port))
+            .map(|(device_id, port)| ActivePortRemoved {
+                device_id: device_id.clone(),
+                port: port.clone(),
+            })
+            .collect(),
+        removed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn port(port_name: &str) -> SerialPortDescriptor {
+        SerialPortDescriptor {
+            port_name: port_name.to_string(),
+            port_type: "usb".to_string(),
+            vid: None,
+            pid: None,
+            manufacturer: None,
+            product: None,
+            serial_number: None,
+        }
+    }
+
+    fn active(device_id: &str, port_name: &str) -> (String, String) {
+        (device_id.to_string(), port_name.to_string())
+    }
+
+    #[test]
+    fn unchanged_port_list_is_not_reported() {
+        let ports = [port("COM3"), port("COM4")];
+        let reordered = [port("COM4"), port("COM3")];
+        assert!(diff_ports(&ports, &reordered, &[active("a", "COM3")]).is_none());
+    }
+
+    #[test]
+    fn added_and_removed_ports_are_reported_with_the_current_list() {
+        let change = diff_ports(
+            &[port("COM3"), port("COM4")],
+            &[port("COM4"), port("COM7")],
+            &[],
+        )
+        .unwrap();
+        let names = |ports: &[SerialPortDescriptor]| -> Vec<String> {
+            ports.iter().map(|port| port.port_name.clone()).collect()
+        };
+        assert_eq!(names(&change.ports), vec!["COM4", "COM7"]);
+        assert_eq!(names(&change.added), vec!["COM7"]);
+        assert_eq!(change.removed, vec!["COM3"]);
+        assert!(change.active_ports_removed.is_empty());
+    }
+
+    #[test]
+    fn removed_ports_name_the_devices_reading_them() {
+        let change = diff_ports(
+            &[port("COM3"), port("COM4"), port("COM5")],
+            &[port("COM5")],
+            &[
+                active("a", "COM3"),
+                active("b", "COM5"),
+                active("c", "COM4"),
+            ],
+        )
+        .unwrap();
+        let removed: Vec<(&str, &str)> = change
+            .active_ports_removed
+            .iter()
+            .map(|removed| (removed.device_id.as_str(), removed.port.as_str()))
+            .collect();
+        assert_eq!(removed, vec![("a", "COM3"), ("c", "COM4")]);
+    }
+
+    #[test]
+    fn devices_are_released_only_after_the_grace_period() {
+        let mut missing_polls = BTreeMap::new();
+        let running = || vec![active("a", "COM3"), active("b", "COM4")];
+        // COM3 blips out for one poll short of the grace period, then returns.
+        for _ in 1..UNPLUG_GRACE_POLLS {
+            assert!(unplugged_devices(&mut missing_polls, &[port("COM4")], running()).is_empty());
+        }
+        let everything = [port("COM3"), port("COM4")];
+        assert!(unplugged_devices(&mut missing_polls, &everything, running()).is_empty());
+        // Once gone for the whole period, its device is released once.
+        for _ in 1..UNPLUG_GRACE_POLLS {
+            assert!(unplugged_devices(&mut missing_polls, &[port("COM4")], running()).is_empty());
+        }
+        assert_eq!(
+            unplugged_devices(&mut missing_polls, &[port("COM4")], running()),
+            vec![active("a", "COM3")]
+        );
+        assert!(unplugged_devices(&mut missing_polls, &[port("COM4")], running()).is_empty());
+    }
+
+    #[test]
+    fn a_stopped_reader_forgets_its_missing_port() {
+        let mut missing_polls = BTreeMap::new();
+        for _ in 1..UNPLUG_GRACE_POLLS {
+            unplugged_devices(&mut missing_polls, &[], vec![active("a", "COM3")]);
+        }
+        assert!(unplugged_devices(&mut missing_polls, &[], Vec::new()).is_empty());
+        assert!(missing_polls.is_empty());
+        assert!(unplugged_devices(&mut missing_polls, &[], vec![active("a", "COM3")]).is_empty());
+    }
+}
@@ -1,23 +1,64 @@
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use tauri::{AppHandle, Manager, State};
 
 use crate::{
     adapters::{
+        csv_replay_source::{ReplayControl, ReplaySpeed, ReplayStatus},
+        file_recording_journal::{read_recording_journal, FileRecordingJournal},
+        file_serial_config_repository::FileSerialConfigRepository,
         file_session_repository::FileSessionRepository,
+        iso8601::format_iso8601_millis,
         onnx_inference_runner::{ModelFilePaths, OnnxInferenceRunner},
+        reconnect_backoff::ReconnectPolicy,
+        serial_eeg_source::WireFormat,
+        serial_port_probe::{
+            list_serial_port_descriptors, probe_serial_ports, PortProbeRequest, PortProbeResult,
+            SerialPortDescriptor,
+        },
+        session_csv::{
+            markers_csv, parse_session_csv, raw_csv, session_csv, session_csv_hash,
+            sibling_csv_path_for, MARKERS_CSV_SUFFIX, RAW_CSV_SUFFIX,
+        },
+        session_csv_scan::{backfill_content_hashes, scan_session_csvs},
+    },
+    domain::{
+        device_command::DeviceCommand,
+        eeg_packet::EegPacket,
+        field_mapping::FieldMapping,
+        focus_reading::{FocusReading, InferenceHistory},
+        link_diagnostics::{LinkDiagnostics, LinkStats},
+        ports::SessionRepository,
+        serial_config::SerialConfig,
+        session_recording::{RecordingMarker, RecordingOptions, RecoverableSession},
+        session_summary::{SessionReindexReport, SessionSummary},
+        signal_quality::SignalQualityConfig,
     },
-    domain::{eeg_packet::EegPacket, focus_reading::FocusReading, session_summary::SessionSummary},
     infrastructure::{
-        app_state::{Esp32ConnectionState, InferenceRunnerState},
-        esp32_reader::{list_available_serial_ports, run_esp32_reader, Esp32ReaderContext},
+        app_state::{
+            device_id_or_default, EegReaderState, InferenceRunnerState, MockGeneratorState,
+            PredictionHistoryState, RecoverableSessionsState,
+        },
+        eeg_reader::{run_eeg_reader, ConnectedHook, EegReaderContext, EegSourceConfig},
+        recording_recovery::{journal_path_for, retire_exported_journal},
     },
     use_cases::{
         classify_eeg_packet::classify_eeg_packet,
-        manage_session_records::{load_session_summaries, persist_session_summary},
+        manage_serial_configs::{recall_serial_config, remember_serial_config},
+        manage_session_records::{
+            load_session_summaries, persist_recorded_session, persist_session_summary,
+            reindex_session_summaries,
+        },
+        record_session::{finish_recorded_session, SessionExport, SessionRecorder},
     },
 };
 
+const SESSIONS_INDEX_FILE_NAME: &str = "sessions.json";
+const SERIAL_CONFIGS_FILE_NAME: &str = "serial_configs.json";
+// Long enough for the firmware to finish a Bluetooth re-pair before acking.
+const DEVICE_COMMAND_TIMEOUT: Duration = Duration::from_secs(3);
 const MODEL_NOT_LOADED_MESSAGE: &str =
     "ML model not loaded — use the Model Setup card to load cerebro_unified.onnx and scaler_params.json";
 
@@ -28,147 +69,795 @@ const MODEL_NOT_LOADED_MESSAGE: &str =
 pub fn load_model_files(
     paths: ModelFilePaths,
     runner_state: State<InferenceRunnerState>,
+    history_state: State<PredictionHistoryState>,
 ) -> Result<(), String> {
     let runner = OnnxInferenceRunner::load(&paths)?;
     *runner_state.lock().map_err(|error| error.to_string())? = Some(runner);
+    // A new model starts its webview stream afresh.
+    *history_state.lock().map_err(|error| error.to_string())? = InferenceHistory::default();
     Ok(())
 }
 
-/// To enumerate COM ports visible to the OS for the ESP32 source selector.
+/// To enumerate COM ports visible to the OS for the ESP32 source selector,
+/// with the USB VID/PID, manufacturer and serial number where available.
 #[tauri::command]
-pub fn list_serial_ports() -> Vec<String> {
-    list_available_serial_ports()
+pub fn list_serial_ports() -> Vec<SerialPortDescriptor> {
+    list_serial_port_descriptors()
 }
 
-/// To start streaming EEG data from an ESP32 device connected over USB serial.
-/// Idempotent — a running ESP32 session is left unchanged.
+/// To listen briefly on candidate ports for ESP32 JSON or ThinkGear frames
+/// and return them ranked, most likely headset bridge first. Ports held by a
+/// running reader are skipped, so another headset can be found mid-session.
+/// The multi-second listen runs on a blocking thread, off both the main
+/// thread and the async runtime's workers.
 #[tauri::command]
-pub fn start_esp32(
-    port: String,
+pub async fn probe_ports(
+    request: Option<PortProbeRequest>,
+    reader_state: State<'_, EegReaderState>,
+) -> Result<Vec<PortProbeResult>, String> {
+    let held_ports: Vec<String> = reader_state
+        .lock()
+        .map_err(|error| error.to_string())?
+        .active_serial_ports()
+        .into_iter()
+        .map(|(_, port)| port)
+        .collect();
+    let request = request.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || probe_serial_ports(&request, &held_ports))
+        .await
+        .map_err(|error| error.to_string())
+}
+
+/// One registered reader as listed by `list_eeg_readers`.
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EegReaderInfo {
+    pub device_id: String,
+    pub running: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serial_port: Option<String>,
+}
+
+/// To list every headset reader started this run, running or stopped, so
+/// the UI can restore its device list after a reload.
+#[tauri::command]
+pub fn list_eeg_readers(reader_state: State<EegReaderState>) -> Result<Vec<EegReaderInfo>, String> {
+    let readers = reader_state.lock().map_err(|error| error.to_string())?;
+    Ok(readers
+        .iter()
+        .map(|(device_id, reader)| EegReaderInfo {
+            device_id: device_id.to_string(),
+            running: reader.is_running(),
+            serial_port: reader.active_serial_port().map(str::to_string),
+        })
+        .collect())
+}
+
+/// Bundles the frontend-supplied options for starting the serial reader.
+/// Only `port` is required: `wire_format` defaults to the ESP32 JSON sketch
+/// (pass `thinkGear` for the headset's native binary protocol through a
+/// transparent bridge or dongle), `serial` to the settings last used on this
+/// port, `reconnect` to the standard retry budget and `signal_quality` to the
+/// standard gating rules. With `capture_path` every byte read from the port
+/// is also logged there for offline replay; `field_mapping` adapts sketches
+/// that speak a different JSON schema. `device_id` names the headset when
+/// several are read at once.
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartEsp32Request {
+    pub port: String,
+    #[serde(default)]
+    pub device_id: Option<String>,
+    #[serde(default)]
+    pub wire_format: WireFormat,
+    #[serde(default)]
+    pub serial: Option<SerialConfig>,
+    #[serde(default)]
+    pub reconnect: ReconnectPolicy,
+    #[serde(default)]
+    pub signal_quality: SignalQualityConfig,
+    #[serde(default)]
+    pub capture_path: Option<String>,
+    #[serde(default)]
+    pub field_mapping: FieldMapping,
+}
+
+/// To start streaming EEG data from the configured source for `device_id`.
+/// The source is chosen by `config.type`; every source feeds the same event
+/// pipeline and the same signal quality gate, tuned by the optional
+/// `signal_quality`. Each device gets its own reader thread, and its events
+/// carry its id. Idempotent — a device whose reader is running is left
+/// unchanged. Refused when another device already reads the same port.
+#[tauri::command]
+pub fn start_eeg_source(
+    config: EegSourceConfig,
+    signal_quality: Option<SignalQualityConfig>,
+    device_id: Option<String>,
+    app: AppHandle,
+    reader_state: State<EegReaderState>,
+) -> Result<(), String> {
+    let request = StartReaderRequest {
+        config,
+        signal_quality: signal_quality.unwrap_or_default(),
+        device_id: device_id_or_default(device_id),
+        on_connected: None,
+    };
+    start_reader(request, app, reader_state)
+}
+
+// Everything start_reader needs besides the app and reader handles.
+struct StartReaderRequest {
+    config: EegSourceConfig,
+    signal_quality: SignalQualityConfig,
+    device_id: String,
+    on_connected: Option<ConnectedHook>,
+}
+
+fn start_reader(
+    request: StartReaderRequest,
     app: AppHandle,
-    esp32_state: State<Esp32ConnectionState>,
+    reader_state: State<EegReaderState>,
 ) -> Result<(), String> {
-    let mut guard = esp32_state.lock().map_err(|error| error.to_string())?;
-    if guard.is_running() {
+    let StartReaderRequest {
+        config,
+        signal_quality,
+        device_id,
+        on_connected,
+    } = request;
+    let mut readers = reader_state.lock().map_err(|error| error.to_string())?;
+    if readers.is_running(&device_id) {
         return Ok(());
     }
+    if let Some(port) = config.serial_port_name() {
+        // The device's own stopping reader is joined by the new one instead.
+        let holder = readers
+            .device_holding_port(port)
+            .filter(|holder| *holder != device_id);
+        if let Some(holder) = holder {
+            return Err(format!("{port} is already read by device {holder}"));
+        }
+    }
+    let guard = readers.reader_mut(&device_id);
+    let previous_reader = guard.thread.take();
     guard.reset();
-    let ctx = Esp32ReaderContext {
-        app,
+    guard.serial_port = config.serial_port_name().map(str::to_string);
+    let configured = config.build_source();
+    guard.replay_control = configured.replay_control;
+    guard.command_channel = configured.command_channel;
+    let link_diagnostics = LinkDiagnostics::new(Instant::now());
+    guard.link_diagnostics = Some(link_diagnostics.clone());
+    let inference = Arc::clone(&app.state::<InferenceRunnerState>());
+    let ctx = EegReaderContext {
+        device_id,
         stop_flag: Arc::clone(&guard.stop_flag),
-        port_name: port,
+        source: configured.source,
+        signal_quality,
+        link_diagnostics,
+        recorder: guard.recorder.clone(),
+        inference,
+        on_connected,
+        previous_reader,
+        app,
     };
-    guard.thread = Some(std::thread::spawn(move || run_esp32_reader(ctx)));
+    guard.thread = Some(std::thread::spawn(move || run_eeg_reader(ctx)));
     Ok(())
 }
 
-/// To stop the ESP32 serial reader and release the COM port.
+/// To stop the reader for `device_id` and release its device or stream.
+/// Returns without waiting for the thread; its serial port stays held until
+/// it exits. Other devices keep streaming. A device never started is an
+/// error rather than a new idle entry.
 #[tauri::command]
-pub fn stop_esp32(esp32_state: State<Esp32ConnectionState>) -> Result<(), String> {
-    let mut guard = esp32_state.lock().map_err(|error| error.to_string())?;
-    guard.stop();
+pub fn stop_eeg_source(
+    device_id: Option<String>,
+    reader_state: State<EegReaderState>,
+) -> Result<(), String> {
+    let device_id = device_id_or_default(device_id);
+    let mut readers = reader_state.lock().map_err(|error| error.to_string())?;
+    readers
+        .registered_reader_mut(&device_id)
+        .ok_or_else(|| format!("unknown device {device_id}"))?
+        .stop();
     Ok(())
 }
 
+/// To start streaming EEG data from an ESP32 device connected over USB serial.
+/// Without explicit `serial` settings the port reopens with the ones it was
+/// last started with. Settings are validated up front and remembered for
+/// next time only once the port has opened with them.
+/// Idempotent — a running ESP32 session for the same device is left unchanged.
+#[tauri::command]
+pub fn start_esp32(
+    request: StartEsp32Request,
+    app: AppHandle,
+    reader_state: State<EegReaderState>,
+) -> Result<(), String> {
+    let repository_path = resolve_app_data_path(&app, SERIAL_CONFIGS_FILE_NAME)?;
+    let serial = match request.serial {
+        Some(serial) => serial,
+        None => recall_serial_config(
+            &request.port,
+            &FileSerialConfigRepository::new(repository_path.clone()),
+        )?,
+    };
+    serial.validate()?;
+    let port = request.port.clone();
+    let remember_serial: ConnectedHook = Box::new(move || {
+        let mut repository = FileSerialConfigRepository::new(repository_path);
+        // Losing the remembered settings must not stop the session.
+        if let Err(error) = remember_serial_config(&port, serial, &mut repository) {
+            eprintln!("[IO] failed to remember serial settings: {error}");
+        }
+    });
+    let config = EegSourceConfig::Esp32 {
+        port_name: request.port,
+        wire_format: request.wire_format,
+        serial,
+        reconnect: request.reconnect,
+        capture_path: request.capture_path,
+        field_mapping: request.field_mapping,
+    };
+    let request = StartReaderRequest {
+        config,
+        signal_quality: request.signal_quality,
+        device_id: device_id_or_default(request.device_id),
+        on_connected: Some(remember_serial),
+    };
+    start_reader(request, app, reader_state)
+}
+
+/// To return the serial settings last used on `port`, or the defaults for a
+/// port never started, so the settings form can be prefilled.
+#[tauri::command]
+pub fn get_serial_config(port: String, app: AppHandle) -> Result<SerialConfig, String> {
+    let repository =
+        FileSerialConfigRepository::new(resolve_app_data_path(&app, SERIAL_CONFIGS_FILE_NAME)?);
+    recall_serial_config(&port, &repository).map_err(|error| error.to_string())
+}
+
+/// To read and validate a JSON field mapping profile, so the UI can show its
+/// name and pass it to `start_esp32` or `start_eeg_source`.
+#[tauri::command]
+pub fn load_field_mapping(path: String) -> Result<FieldMapping, String> {
+    let content = std::fs::read_to_string(&path).map_err(|error| error.to_string())?;
+    serde_json::from_str(&content).map_err(|error| format!("invalid field mapping: {error}"))
+}
+
+/// To send one command to the ESP32 bridge of `device_id` over its serial
+/// link and return the firmware's result, e.g. `{ "version": "1.4.0" }` for
+/// `getFirmwareVersion`. Waiting for the ack runs on a blocking thread, off
+/// both the main thread and the async runtime's workers.
+#[tauri::command]
+pub async fn send_device_command(
+    command: DeviceCommand,
+    device_id: Option<String>,
+    reader_state: State<'_, EegReaderState>,
+) -> Result<serde_json::Value, String> {
+    let device_id = device_id_or_default(device_id);
+    let channel = reader_state
+        .lock()
+        .map_err(|error| error.to_string())?
+        .reader(&device_id)?
+        .active_command_channel()?
+        .clone();
+    tauri::async_runtime::spawn_blocking(move || channel.send(&command, DEVICE_COMMAND_TIMEOUT))
+        .await
+        .map_err(|error| error.to_string())?
+        .map_err(|error| error.to_string())
+}
+
+/// To read the link and parser counters of the running reader for
+/// `device_id`, or of its last one after it stopped, e.g. to see why a
+/// session never produced data.
+#[tauri::command]
+pub fn get_link_diagnostics(
+    device_id: Option<String>,
+    reader_state: State<EegReaderState>,
+) -> Result<LinkStats, String> {
+    let device_id = device_id_or_default(device_id);
+    let readers = reader_state.lock().map_err(|error| error.to_string())?;
+    Ok(readers
+        .reader(&device_id)?
+        .latest_link_diagnostics()?
+        .snapshot(Instant::now()))
+}
+
+/// To stop the ESP32 serial reader for `device_id` and release its COM port.
+#[tauri::command]
+pub fn stop_esp32(
+    device_id: Option<String>,
+    reader_state: State<EegReaderState>,
+) -> Result<(), String> {
+    stop_eeg_source(device_id, reader_state)
+}
+
+/// Bundles the frontend-supplied options for replaying a recorded session.
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartReplayRequest {
+    pub csv_path: String,
+    #[serde(default)]
+    pub speed: ReplaySpeed,
+    #[serde(default)]
+    pub device_id: Option<String>,
+}
+
+/// To replay a recorder CSV through the live `eeg-data` pipeline so the chart,
+/// inference and recorder behave exactly as with a headset attached.
+/// Stopped with `stop_eeg_source`, like any other source.
+#[tauri::command]
+pub fn start_replay(
+    request: StartReplayRequest,
+    app: AppHandle,
+    reader_state: State<EegReaderState>,
+) -> Result<(), String> {
+    let config = EegSourceConfig::Replay {
+        csv_path: request.csv_path,
+        speed: request.speed,
+    };
+    start_eeg_source(config, None, request.device_id, app, reader_state)
+}
+
+// To run `action` on the control handle of the replay `device_id` is running.
+fn with_active_replay<T>(
+    device_id: Option<String>,
+    reader_state: &State<EegReaderState>,
+    action: impl FnOnce(&ReplayControl) -> T,
+) -> Result<T, String> {
+    let device_id = device_id_or_default(device_id);
+    let readers = reader_state.lock().map_err(|error| error.to_string())?;
+    Ok(action(readers.reader(&device_id)?.active_replay()?))
+}
+
+/// To pause the running replay without losing its position.
+#[tauri::command]
+pub fn pause_replay(
+    device_id: Option<String>,
+    reader_state: State<EegReaderState>,
+) -> Result<(), String> {
+    with_active_replay(device_id, &reader_state, |replay| replay.set_paused(true))
+}
+
+/// To resume a paused replay from the row it stopped at.
+#[tauri::command]
+pub fn resume_replay(
+    device_id: Option<String>,
+    reader_state: State<EegReaderState>,
+) -> Result<(), String> {
+    with_active_replay(device_id, &reader_state, |replay| replay.set_paused(false))
+}
+
+/// To jump the running replay to `position_secs` from the start of the recording.
+#[tauri::command]
+pub fn seek_replay(
+    position_secs: f64,
+    device_id: Option<String>,
+    reader_state: State<EegReaderState>,
+) -> Result<(), String> {
+    with_active_replay(device_id, &reader_state, |replay| {
+        replay.seek(position_secs)
+    })
+}
+
+/// To switch the running replay between 1x, 4x and max speed.
+#[tauri::command]
+pub fn set_replay_speed(
+    speed: ReplaySpeed,
+    device_id: Option<String>,
+    reader_state: State<EegReaderState>,
+) -> Result<(), String> {
+    with_active_replay(device_id, &reader_state, |replay| replay.set_speed(speed))
+}
+
+/// To report the running replay's position and duration for a seek bar.
+#[tauri::command]
+pub fn get_replay_status(
+    device_id: Option<String>,
+    reader_state: State<EegReaderState>,
+) -> Result<ReplayStatus, String> {
+    with_active_replay(device_id, &reader_state, ReplayControl::status)
+}
+
 /// To run one EEG packet through the loaded inference model and return a focus label.
 #[tauri::command]
 pub fn get_focus_prediction(
     payload: EegPacket,
     runner_state: State<InferenceRunnerState>,
+    history_state: State<PredictionHistoryState>,
 ) -> Result<FocusReading, String> {
     let mut guard = runner_state.lock().map_err(|error| error.to_string())?;
     let runner = guard.as_mut().ok_or(MODEL_NOT_LOADED_MESSAGE)?;
-    classify_eeg_packet(&payload, runner).map_err(|error| error.to_string())
+    let mut history = history_state.lock().map_err(|error| error.to_string())?;
+    classify_eeg_packet(&payload, runner, &mut history).map_err(|error| error.to_string())
 }
 
 /// To exercise the full inference pipeline without a physical headset.
-/// Alternates between a beta-dominant (focused) and alpha-dominant (unfocused)
-/// synthetic profile every 5 s so both prediction paths are reachable.
+/// Draws the next packet from the synthetic generator, which wanders between
+/// focused, unfocused and drowsy profiles so every prediction path is reachable.
 #[tauri::command]
 pub fn get_mock_prediction(
     runner_state: State<InferenceRunnerState>,
+    generator_state: State<MockGeneratorState>,
 ) -> Result<FocusReading, String> {
-    let mock_packet = build_mock_eeg_packet();
+    let mut stream = generator_state.lock().map_err(|error| error.to_string())?;
+    let (_, mock_packet) = stream.generator.next_packet();
     let mut guard = runner_state.lock().map_err(|error| error.to_string())?;
     let runner = guard.as_mut().ok_or(MODEL_NOT_LOADED_MESSAGE)?;
-    classify_eeg_packet(&mock_packet, runner).map_err(|error| error.to_string())
+    classify_eeg_packet(&mock_packet, runner, &mut stream.history)
+        .map_err(|error| error.to_string())
 }
 
-// Alternates between beta-dominant (focused) and alpha-dominant (unfocused)
-// every 5 s using wall-clock time so both labels are reachable without a
-// physical headset or an external rand dependency.
-fn build_mock_eeg_packet() -> EegPacket {
-    let elapsed_seconds = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_secs();
-
-    if (elapsed_seconds / 5) % 2 == 0 {
-        EegPacket {
-            delta: 150_000,
-            theta: 80_000,
-            low_alpha: 60_000,
-            high_alpha: 50_000,
-            low_beta: 300_000,
-            high_beta: 250_000,
-            low_gamma: 40_000,
-            mid_gamma: 30_000,
-            attention: 75,
-            meditation: 40,
-            poor_signal_level: 0,
-        }
-    } else {
-        EegPacket {
-            delta: 200_000,
-            theta: 90_000,
-            low_alpha: 350_000,
-            high_alpha: 300_000,
-            low_beta: 80_000,
-            high_beta: 70_000,
-            low_gamma: 30_000,
-            mid_gamma: 20_000,
-            attention: 35,
-            meditation: 65,
-            poor_signal_level: 0,
-        }
-    }
-}
-
-/// Bundles the three frontend-supplied fields for a session save operation.
+/// Bundles the frontend-supplied fields for a session save operation.
 /// Wrapping them in a struct satisfies the rule against 3+ bare positional arguments.
+/// `raw_csv_content` is optional: sessions recorded without the 512 Hz stream omit it.
+/// Only the identity, paths, duration and marker fields of `summary` are kept;
+/// its aggregates are recomputed from `csv_content`.
 #[derive(Debug, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SaveSessionRequest {
     pub csv_path: String,
     pub csv_content: String,
+    #[serde(default)]
+    pub raw_csv_content: Option<String>,
     pub summary: SessionSummary,
 }
 
-fn resolve_sessions_index_path(app: &AppHandle) -> Result<std::path::PathBuf, String> {
+fn resolve_app_data_path(app: &AppHandle, file_name: &str) -> Result<PathBuf, String> {
     let directory = app
         .path()
         .app_data_dir()
         .map_err(|error| error.to_string())?;
     std::fs::create_dir_all(&directory).map_err(|error| error.to_string())?;
-    Ok(directory.join("sessions.json"))
+    Ok(directory.join(file_name))
 }
 
-/// To persist a completed session: writes the CSV to disk first, then appends
-/// a summary entry to sessions.json. The index is only updated after the CSV
-/// write succeeds, keeping the two stores consistent under failure.
+/// To persist a completed session: writes the CSV (and the raw-waveform CSV,
+/// when supplied) to disk first, then appends a summary entry to sessions.json.
+/// The index is only updated after every CSV write succeeds, keeping the
+/// stores consistent under failure. The summary's aggregates are recomputed
+/// from `csv_content`, and the summary as stored is returned.
 #[tauri::command]
-pub fn save_session(app: AppHandle, request: SaveSessionRequest) -> Result<(), String> {
+pub fn save_session(app: AppHandle, request: SaveSessionRequest) -> Result<SessionSummary, String> {
+    let mut summary = request.summary;
+    let rows = parse_session_csv(&request.csv_content).map_err(|error| error.to_string())?;
+    summary.content_hash = Some(session_csv_hash(&request.csv_content));
     std::fs::write(&request.csv_path, request.csv_content).map_err(|error| error.to_string())?;
-    let index_path = resolve_sessions_index_path(&app)?;
+    if let Some(raw_csv_content) = request.raw_csv_content {
+        let raw_csv_path = sibling_csv_path_for(&request.csv_path, RAW_CSV_SUFFIX);
+        std::fs::write(&raw_csv_path, raw_csv_content).map_err(|error| error.to_string())?;
+        summary.raw_csv_path = Some(raw_csv_path);
+    }
+    let index_path = resolve_app_data_path(&app, SESSIONS_INDEX_FILE_NAME)?;
     let mut repository = FileSessionRepository::new(index_path);
-    persist_session_summary(request.summary, &mut repository).map_err(|error| error.to_string())
+    persist_recorded_session(summary, &rows, &mut repository).map_err(|error| error.to_string())
 }
 
 /// To return all saved session summaries from the local index, or an empty
 /// collection on first launch when no sessions have been recorded yet.
 #[tauri::command]
 pub fn load_sessions(app: AppHandle) -> Result<Vec<SessionSummary>, String> {
-    let index_path = resolve_sessions_index_path(&app)?;
+    let index_path = resolve_app_data_path(&app, SESSIONS_INDEX_FILE_NAME)?;
     let repository = FileSessionRepository::new(index_path);
     load_session_summaries(&repository).map_err(|error| error.to_string())
 }
+
+/// To rebuild the index from the recorder CSVs directly inside `directories`,
+/// e.g. after sessions.json was lost or the aggregate formulas changed.
+/// Every summary is recomputed from its CSV; returns which CSVs were added,
+/// updated or skipped, and why.
+#[tauri::command]
+pub fn reindex_sessions(
+    app: AppHandle,
+    directories: Vec<String>,
+) -> Result<SessionReindexReport, String> {
+    let directories: Vec<PathBuf> = directories.into_iter().map(PathBuf::from).collect();
+    let scan = scan_session_csvs(&directories);
+    let index_path = resolve_app_data_path(&app, SESSIONS_INDEX_FILE_NAME)?;
+    let mut repository = FileSessionRepository::new(index_path);
+    let mut summaries = repository.load_all()?;
+    if backfill_content_hashes(&mut summaries) {
+        repository.replace_all(summaries)?;
+    }
+    reindex_session_summaries(scan, &mut repository).map_err(|error| error.to_string())
+}
+
+/// Bundles the frontend-supplied options for starting a recording: who is
+/// recorded, how rows are labelled, and on which headset.
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartRecordingRequest {
+    #[serde(flatten)]
+    pub options: RecordingOptions,
+    #[serde(default)]
+    pub device_id: Option<String>,
+}
+
+/// Bundles the frontend-supplied fields for finishing a recording.
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FinishRecordingRequest {
+    pub csv_path: String,
+    #[serde(default)]
+    pub device_id: Option<String>,
+}
+
+fn unix_now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+// The recorder is cloned out so the reader map is not locked while the
+// journal is read or the CSV written.
+fn recorder_for(
+    device_id: Option<String>,
+    reader_state: &State<EegReaderState>,
+) -> Result<SessionRecorder, String> {
+    let device_id = device_id_or_default(device_id);
+    let readers = reader_state.lock().map_err(|error| error.to_string())?;
+    Ok(readers.reader(&device_id)?.recorder.clone())
+}
+
+// To turn a journal into the session CSV, its markers CSV when markers were
+// placed, and its index entry. The CSVs are written before the index, as in
+// save_session, so the index never points at a missing file.
+fn export_recording(
+    app: &AppHandle,
+    journal_path: &Path,
+    csv_path: String,
+    recovered: bool,
+) -> Result<SessionSummary, String> {
+    let entries = read_recording_journal(journal_path).map_err(|error| error.to_string())?;
+    let export = SessionExport {
+        csv_path,
+        exported_at: format_iso8601_millis(unix_now_ms() as i64),
+        recovered,
+    };
+    let mut finished =
+        finish_recorded_session(entries, export).map_err(|error| error.to_string())?;
+    let csv_content = session_csv(&finished.rows);
+    std::fs::write(&finished.summary.csv_path, &csv_content).map_err(|error| error.to_string())?;
+    finished.summary.content_hash = Some(session_csv_hash(&csv_content));
+    if !finished.raw_batches.is_empty() {
+        let raw_csv_path = sibling_csv_path_for(&finished.summary.csv_path, RAW_CSV_SUFFIX);
+        std::fs::write(&raw_csv_path, raw_csv(&finished.raw_batches))
+            .map_err(|error| error.to_string())?;
+        finished.summary.raw_csv_path = Some(raw_csv_path);
+    }
+    if !finished.markers.is_empty() {
+        let markers_csv_path = sibling_csv_path_for(&finished.summary.csv_path, MARKERS_CSV_SUFFIX);
+        std::fs::write(&markers_csv_path, markers_csv(&finished.markers))
+            .map_err(|error| error.to_string())?;
+        finished.summary.markers_csv_path = Some(markers_csv_path);
+    }
+    let index_path = resolve_app_data_path(app, SESSIONS_INDEX_FILE_NAME)?;
+    let mut repository = FileSessionRepository::new(index_path);
+    persist_session_summary(finished.summary.clone(), &mut repository)
+        .map_err(|error| error.to_string())?;
+    Ok(finished.summary)
+}
+
+/// To start recording the accepted packets of one headset. Rows are labelled
+/// and journaled by the reader thread as they arrive, so the webview only
+/// sees `recording-row` progress events. The recording survives reader
+/// restarts; returns its id. The device must have been started first.
+#[tauri::command]
+pub fn start_recording(
+    request: StartRecordingRequest,
+    app: AppHandle,
+    reader_state: State<EegReaderState>,
+) -> Result<String, String> {
+    let device_id = device_id_or_default(request.device_id);
+    let recorder = reader_state
+        .lock()
+        .map_err(|error| error.to_string())?
+        .registered_reader_mut(&device_id)
+        .ok_or_else(|| format!("unknown device {device_id}"))?
+        .recorder
+        .clone();
+    if recorder.is_recording() {
+        return Err(format!(
+            "a recording is already active on device {device_id}"
+        ));
+    }
+    let started_at = unix_now_ms();
+    let safe_device_id: String = device_id
+        .chars()
+        .map(|character| {
+            if character.is_ascii_alphanumeric() || character == '-' || character == '_' {
+                character
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let recording_id = format!("{started_at}-{safe_device_id}");
+    let journal_path = journal_path_for(&app, &recording_id)?;
+    let journal =
+        FileRecordingJournal::create(journal_path.clone()).map_err(|error| error.to_string())?;
+    if let Err(error) = recorder.start(
+        recording_id.clone(),
+        &device_id,
+        request.options,
+        Box::new(journal),
+        started_at,
+    ) {
+        if let Err(remove_error) = std::fs::remove_file(&journal_path) {
+            eprintln!("[IO] failed to remove unused recording journal: {remove_error}");
+        }
+        return Err(error.to_string());
+    }
+    Ok(recording_id)
+}
+
+/// Bundles the frontend-supplied fields for a marker. `at_unix_ms` lets the
+/// UI stamp the moment the operator clicked; it defaults to now.
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddMarkerRequest {
+    pub label: String,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+    #[serde(default)]
+    pub at_unix_ms: Option<u64>,
+    #[serde(default)]
+    pub device_id: Option<String>,
+}
+
+/// To store a labelled, timestamped marker against the headset's active
+/// recording, paused or not. Exported beside the session CSV as
+/// `<name>_markers.csv`. Returns the marker as stored.
+#[tauri::command]
+pub fn add_marker(
+    request: AddMarkerRequest,
+    reader_state: State<EegReaderState>,
+) -> Result<RecordingMarker, String> {
+    let blank_to_none = |text: Option<String>| text.filter(|text| !text.trim().is_empty());
+    let marker = RecordingMarker {
+        label: request.label,
+        category: blank_to_none(request.category),
+        note: blank_to_none(request.note),
+        at_unix_ms: request.at_unix_ms.unwrap_or_else(unix_now_ms),
+        after_sequence: None,
+    };
+    recorder_for(request.device_id, &reader_state)?
+        .add_marker(marker)
+        .map_err(|error| error.to_string())
+}
+
+/// To stop taking rows into the headset's recording; paused time is left
+/// out of the session duration.
+#[tauri::command]
+pub fn pause_recording(
+    device_id: Option<String>,
+    reader_state: State<EegReaderState>,
+) -> Result<(), String> {
+    recorder_for(device_id, &reader_state)?
+        .pause(unix_now_ms())
+        .map(|_| ())
+        .map_err(|error| error.to_string())
+}
+
+/// To take rows into the headset's recording again after a pause.
+#[tauri::command]
+pub fn resume_recording(
+    device_id: Option<String>,
+    reader_state: State<EegReaderState>,
+) -> Result<(), String> {
+    recorder_for(device_id, &reader_state)?
+        .resume(unix_now_ms())
+        .map(|_| ())
+        .map_err(|error| error.to_string())
+}
+
+/// To export the headset's recording to `csv_path`, add it to sessions.json
+/// and return its summary. On failure the recording stays active, unpaused
+/// if it was running, so the export can be retried.
+#[tauri::command]
+pub fn finish_recording(
+    request: FinishRecordingRequest,
+    app: AppHandle,
+    reader_state: State<EegReaderState>,
+) -> Result<SessionSummary, String> {
+    let recorder = recorder_for(request.device_id, &reader_state)?;
+    let recording_id = recorder
+        .active_recording_id()
+        .ok_or("no recording is active on this headset")?;
+    // Closing the recording first ends the active interval, so the journal
+    // read below holds the final duration, and no marker can land after it.
+    let paused_now = recorder
+        .begin_finish(unix_now_ms())
+        .map_err(|error| error.to_string())?;
+    let journal_path = journal_path_for(&app, &recording_id)?;
+    match export_recording(&app, &journal_path, request.csv_path, false) {
+        Ok(summary) => {
+            if let Err(error) = recorder.finish(unix_now_ms()) {
+                eprintln!("[IO] failed to mark recording as exported: {error}");
+            }
+            if let Err(error) = std::fs::remove_file(&journal_path) {
+                eprintln!("[IO] failed to remove exported recording journal: {error}");
+            }
+            Ok(summary)
+        }
+        Err(error) => {
+            if let Err(reopen_error) = recorder.cancel_finish(paused_now, unix_now_ms()) {
+                eprintln!("[IO] failed to reopen recording: {reopen_error}");
+            }
+            Err(error)
+        }
+    }
+}
+
+/// To drop the headset's recording without exporting it, deleting its
+/// journal.
+#[tauri::command]
+pub fn discard_recording(
+    device_id: Option<String>,
+    app: AppHandle,
+    reader_state: State<EegReaderState>,
+) -> Result<(), String> {
+    let Some(recording_id) = recorder_for(device_id, &reader_state)?.abandon() else {
+        return Ok(());
+    };
+    std::fs::remove_file(journal_path_for(&app, &recording_id)?).map_err(|error| error.to_string())
+}
+
+/// To list the recordings an earlier run left unfinished, found when the
+/// app started.
+#[tauri::command]
+pub fn list_recoverable_sessions(
+    recoverable_state: State<RecoverableSessionsState>,
+) -> Result<Vec<RecoverableSession>, String> {
+    Ok(recoverable_state
+        .lock()
+        .map_err(|error| error.to_string())?
+        .clone())
+}
+
+/// What to do with an interrupted recording: export it like a finished one,
+/// or delete it.
+#[derive(Debug, serde::Deserialize)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum RecoveryAction {
+    #[serde(rename_all = "camelCase")]
+    Finalize {
+        csv_path: String,
+    },
+    Discard,
+}
+
+/// To finalize an interrupted recording into a CSV and a summary marked as
+/// recovered, returning the summary, or to discard it. Its duration runs up
+/// to the last entry the journal holds.
+#[tauri::command]
+pub fn recover_session(
+    recording_id: String,
+    action: RecoveryAction,
+    app: AppHandle,
+    recoverable_state: State<RecoverableSessionsState>,
+) -> Result<Option<SessionSummary>, String> {
+    let mut sessions = recoverable_state
+        .lock()
+        .map_err(|error| error.to_string())?;
+    let index = sessions
+        .iter()
+        .position(|session| session.recording_id == recording_id)
+        .ok_or_else(|| format!("no interrupted recording {recording_id}"))?;
+    let journal_path = journal_path_for(&app, &recording_id)?;
+    let summary = match action {
+        RecoveryAction::Finalize { csv_path } => {
+            let summary = export_recording(&app, &journal_path, csv_path, true)?;
+            retire_exported_journal(journal_path, unix_now_ms());
+            Some(summary)
+        }
+        RecoveryAction::Discard => {
+            std::fs::remove_file(&journal_path).map_err(|error| error.to_string())?;
+            None
+        }
+    };
+    sessions.remove(index);
+    Ok(summary)
+}
@@ -5,11 +5,23 @@ pub mod use_cases;
 
 use std::sync::{Arc, Mutex};
 
+use adapters::synthetic_eeg_source::{SimulatorConfig, SyntheticEegGenerator};
+use domain::focus_reading::InferenceHistory;
 use infrastructure::{
-    app_state::{Esp32ConnectionState, HeadsetConnectionState, InferenceRunnerState},
+    app_state::{
+        EegReaderState, HeadsetReaders, InferenceRunnerState, MockGeneratorState,
+        MockPredictionStream, PredictionHistoryState, RecoverableSessionsState,
+    },
+    recording_recovery::scan_recoverable_sessions,
+    serial_port_watcher::spawn_serial_port_watcher,
     tauri_commands::{
-        get_focus_prediction, get_mock_prediction, list_serial_ports, load_model_files,
-        load_sessions, save_session, start_esp32, stop_esp32,
+        add_marker, discard_recording, finish_recording, get_focus_prediction,
+        get_link_diagnostics, get_mock_prediction, get_replay_status, get_serial_config,
+        list_eeg_readers, list_recoverable_sessions, list_serial_ports, load_field_mapping,
+        load_model_files, load_sessions, pause_recording, pause_replay, probe_ports,
+        recover_session, reindex_sessions, resume_recording, resume_replay, save_session,
+        seek_replay, send_device_command, set_replay_speed, start_eeg_source, start_esp32,
+        start_recording, start_replay, stop_eeg_source, stop_esp32,
     },
 };
 use tauri::Manager;
@@ -27,18 +39,59 @@ pub fn run() {
             // The inference runner starts as None — the user loads files via
             // the Model Setup card, which calls load_model_files at runtime.
             app.manage(Arc::new(Mutex::new(None)) as InferenceRunnerState);
-            app.manage(Mutex::new(HeadsetConnectionState::default()) as Esp32ConnectionState);
+            app.manage(Mutex::new(InferenceHistory::default()) as PredictionHistoryState);
+            app.manage(Mutex::new(HeadsetReaders::default()) as EegReaderState);
+            // Seeded from the clock so each launch explores a different mock session.
+            let mock_config = SimulatorConfig {
+                seed: std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_nanos() as u64,
+                ..SimulatorConfig::default()
+            };
+            app.manage(Mutex::new(MockPredictionStream {
+                generator: SyntheticEegGenerator::new(mock_config),
+                history: InferenceHistory::default(),
+            }) as MockGeneratorState);
+            // Scanned before the webview can start a recording, so every
+            // unfinished journal found here was left by an earlier run.
+            let recoverable_sessions = scan_recoverable_sessions(app.handle());
+            app.manage(Mutex::new(recoverable_sessions) as RecoverableSessionsState);
+            spawn_serial_port_watcher(app.handle().clone());
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             load_model_files,
             list_serial_ports,
+            probe_ports,
+            list_eeg_readers,
+            start_eeg_source,
+            stop_eeg_source,
             start_esp32,
+            get_serial_config,
+            load_field_mapping,
+            send_device_command,
+            get_link_diagnostics,
             stop_esp32,
+            start_replay,
+            pause_replay,
+            resume_replay,
+            seek_replay,
+            set_replay_speed,
+            get_replay_status,
             get_focus_prediction,
             get_mock_prediction,
+            start_recording,
+            pause_recording,
+            resume_recording,
+            finish_recording,
+            discard_recording,
+            add_marker,
+            list_recoverable_sessions,
+            recover_session,
             save_session,
             load_sessions,
+            reindex_sessions,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
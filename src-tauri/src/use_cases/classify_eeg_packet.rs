@@ -1,16 +1,18 @@
 use crate::domain::{
     eeg_packet::EegPacket,
     errors::AppError,
-    focus_reading::FocusReading,
+    focus_reading::{FocusReading, InferenceHistory},
     ports::InferenceRunner,
 };
 
 /// To produce a focus label for one EEG packet without knowledge of the
 /// concrete inference backend. The caller supplies a runner so this function
-/// is testable independently of the ONNX runtime.
+/// is testable independently of the ONNX runtime, and the `history` of the
+/// stream the packet belongs to.
 pub fn classify_eeg_packet(
     packet: &EegPacket,
     runner: &mut impl InferenceRunner,
+    history: &mut InferenceHistory,
 ) -> Result<FocusReading, AppError> {
-    runner.predict(packet)
+    runner.predict(packet, history)
 }
@@ -0,0 +1,300 @@
+use std::time::{Duration, Instant};
+
+use crate::domain::{
+    eeg_packet::EegPacket,
+    signal_quality::{
+        PacketVerdict, RejectionReason, SignalQualityConfig, SignalQualityUpdate, SignalTier,
+        NO_CONTACT_SIGNAL_LEVEL,
+    },
+};
+
+// A tier seen in recent packets that has not persisted long enough to report.
+struct PendingTier {
+    tier: SignalTier,
+    since: Instant,
+}
+
+/// Single source of truth for signal quality during acquisition: rejects
+/// packets above the configured threshold, derives the reported tier with
+/// hysteresis and a dwell time, and flags a silent headset via a watchdog.
+/// Time is passed in rather than read, so the rules run identically under
+/// replay at any speed.
+pub struct SignalQualityGate {
+    config: SignalQualityConfig,
+    reported_tier: Option<SignalTier>,
+    pending: Option<PendingTier>,
+    last_signal_level: u8,
+    last_packet_at: Instant,
+    stale: bool,
+}
+
+impl SignalQualityGate {
+    /// To start gating with the watchdog armed from `now`, so a headset that
+    /// never sends a packet is still reported.
+    pub fn new(config: SignalQualityConfig, now: Instant) -> Self {
+        Self {
+            config,
+            reported_tier: None,
+            pending: None,
+            last_signal_level: NO_CONTACT_SIGNAL_LEVEL,
+            last_packet_at: now,
+            stale: false,
+        }
+    }
+
+    /// To judge one packet and fold its signal level into tier tracking.
+    /// Returns the verdict to tag the packet with and, when the reported tier
+    /// changes, the update to publish.
+    pub fn assess_packet(
+        &mut self,
+        packet: &EegPacket,
+        now: Instant,
+    ) -> (PacketVerdict, Option<SignalQualityUpdate>) {
+        let level = packet.poor_signal_level;
+        self.last_packet_at = now;
+        self.last_signal_level = level;
+        self.stale = false;
+
+        let rejection_reason = if level >= NO_CONTACT_SIGNAL_LEVEL {
+            Some(RejectionReason::NoContact)
+        } else if level >= self.config.rejection_threshold {
+            Some(RejectionReason::PoorSignal)
+        } else {
+            None
+        };
+        let verdict = PacketVerdict {
+            accepted: rejection_reason.is_none(),
+            rejection_reason,
+        };
+
+        let tier = self.tier_for_level(level);
+        (verdict, self.propose_tier(tier, now))
+    }
+
+    /// To run the stale-data watchdog and commit any pending tier whose dwell
+    /// time has elapsed. Called between packets so both fire on time even
+    /// when nothing arrives.
+    pub fn tick(&mut self, now: Instant) -> Option<SignalQualityUpdate> {
+        let silence = now.saturating_duration_since(self.last_packet_at);
+        if !self.stale && silence >= Duration::from_millis(self.config.stale_timeout_ms) {
+            // The timeout is itself a dwell, so report immediately.
+            self.stale = true;
+            self.last_signal_level = NO_CONTACT_SIGNAL_LEVEL;
+            return self.commit(SignalTier::NoHeadset);
+        }
+        let pending = self.pending.as_ref()?;
+        if self.dwell_elapsed(pending, now) {
+            let tier = pending.tier;
+            return self.commit(tier);
+        }
+        None
+    }
+
+    /// To hold the stale-data watchdog while the source is paused on
+    /// purpose. Silence is counted again from `now`.
+    pub fn hold_watchdog(&mut self, now: Instant) {
+        self.last_packet_at = now;
+    }
+
+    // Entering Poor happens at the threshold; leaving it requires dropping
+    // `hysteresis_margin` below, so a level hovering at the threshold stays put.
+    fn tier_for_level(&self, level: u8) -> SignalTier {
+        let threshold = self.config.rejection_threshold;
+        if level >= NO_CONTACT_SIGNAL_LEVEL {
+            SignalTier::NoHeadset
+        } else if level >= threshold {
+            SignalTier::Poor
+        } else if self.reported_tier == Some(SignalTier::Good)
+            || level < threshold.saturating_sub(self.config.hysteresis_margin)
+        {
+            SignalTier::Good
+        } else {
+            SignalTier::Poor
+        }
+    }
+
+    fn propose_tier(&mut self, tier: SignalTier, now: Instant) -> Option<SignalQualityUpdate> {
+        // The first tier after connecting is reported straight away.
+        let Some(reported_tier) = self.reported_tier else {
+            return self.commit(tier);
+        };
+        if tier == reported_tier {
+            self.pending = None;
+            return None;
+        }
+        if self.pending.as_ref().map(|pending| pending.tier) != Some(tier) {
+            self.pending = Some(PendingTier { tier, since: now });
+        }
+        self.tick(now)
+    }
+
+    fn dwell_elapsed(&self, pending: &PendingTier, now: Instant) -> bool {
+        now.saturating_duration_since(pending.since)
+            >= Duration::from_millis(self.config.tier_dwell_ms)
+    }
+
+    fn commit(&mut self, tier: SignalTier) -> Option<SignalQualityUpdate> {
+        self.pending = None;
+        if self.reported_tier == Some(tier) {
+            return None;
+        }
+        let previous_tier = self.reported_tier.replace(tier);
+        Some(SignalQualityUpdate {
+            tier,
+            previous_tier,
+            poor_signal_level: self.last_signal_level,
+            stale: self.stale,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(start: Instant, ms: u64) -> Instant {
+        start + Duration::from_millis(ms)
+    }
+
+    fn packet(poor_signal_level: u8) -> EegPacket {
+        EegPacket {
+            delta: 0,
+            theta: 0,
+            low_alpha: 0,
+            high_alpha: 0,
+            low_beta: 0,
+            high_beta: 0,
+            low_gamma: 0,
+            mid_gamma: 0,
+            attention: 0,
+            meditation: 0,
+            poor_signal_level,
+            device_millis: None,
+            device_seq: None,
+            blink_strength: None,
+            extensions: Default::default(),
+        }
+    }
+
+    // Gate with the default rules (threshold 50, hysteresis 10, dwell
+    // 1500 ms, stale 2500 ms) and a helper that feeds it one packet.
+    struct Harness {
+        start: Instant,
+        gate: SignalQualityGate,
+    }
+
+    impl Harness {
+        fn new() -> Self {
+            let start = Instant::now();
+            Self {
+                start,
+                gate: SignalQualityGate::new(SignalQualityConfig::default(), start),
+            }
+        }
+
+        fn feed(&mut self, level: u8, ms: u64) -> (PacketVerdict, Option<SignalTier>) {
+            let (verdict, update) = self.gate.assess_packet(&packet(level), at(self.start, ms));
+            (verdict, update.map(|update| update.tier))
+        }
+
+        fn tick(&mut self, ms: u64) -> Option<SignalTier> {
+            self.gate.tick(at(self.start, ms)).map(|update| update.tier)
+        }
+    }
+
+    #[test]
+    fn packets_are_rejected_from_the_threshold_up() {
+        let mut harness = Harness::new();
+        let (verdict, _) = harness.feed(49, 0);
+        assert!(verdict.accepted);
+        let (verdict, _) = harness.feed(50, 100);
+        assert_eq!(verdict.rejection_reason, Some(RejectionReason::PoorSignal));
+        let (verdict, _) = harness.feed(NO_CONTACT_SIGNAL_LEVEL, 200);
+        assert_eq!(verdict.rejection_reason, Some(RejectionReason::NoContact));
+    }
+
+    #[test]
+    fn first_tier_is_reported_at_once_and_changes_wait_out_the_dwell() {
+        let mut harness = Harness::new();
+        assert_eq!(harness.feed(0, 0).1, Some(SignalTier::Good));
+
+        assert_eq!(harness.feed(50, 1_000).1, None);
+        assert_eq!(harness.tick(2_499), None);
+        assert_eq!(harness.feed(60, 2_000).1, None);
+        assert_eq!(harness.tick(2_500), Some(SignalTier::Poor));
+    }
+
+    #[test]
+    fn a_tier_that_does_not_persist_is_never_reported() {
+        let mut harness = Harness::new();
+        harness.feed(0, 0);
+        harness.feed(50, 1_000);
+        // Back to Good before the dwell ends, so the pending Poor is dropped...
+        assert_eq!(harness.feed(10, 2_000).1, None);
+        // ...and a new excursion starts its dwell from scratch.
+        assert_eq!(harness.feed(50, 2_200).1, None);
+        assert_eq!(harness.tick(2_600), None);
+        assert_eq!(harness.tick(3_699), None);
+        assert_eq!(harness.tick(3_700), Some(SignalTier::Poor));
+    }
+
+    #[test]
+    fn poor_only_recovers_below_the_hysteresis_band() {
+        let mut harness = Harness::new();
+        assert_eq!(harness.feed(70, 0).1, Some(SignalTier::Poor));
+        // 41..=49 are accepted packets but sit inside the band.
+        for (index, level) in [49, 45, 41, 45].into_iter().enumerate() {
+            let ms = 500 * (index as u64 + 1);
+            let (verdict, update) = harness.feed(level, ms);
+            assert!(verdict.accepted);
+            assert_eq!(update, None);
+            assert_eq!(harness.tick(ms + 100), None);
+        }
+        assert_eq!(harness.feed(39, 2_500).1, None);
+        assert_eq!(harness.tick(4_000), Some(SignalTier::Good));
+        // Once Good, anything under the threshold stays Good.
+        assert_eq!(harness.feed(49, 4_500).1, None);
+        assert_eq!(harness.tick(6_500), None);
+    }
+
+    #[test]
+    fn stale_watchdog_trips_after_silence_and_clears_on_the_next_packet() {
+        let mut harness = Harness::new();
+        harness.feed(0, 0);
+        assert_eq!(harness.tick(2_499), None);
+        let update = harness.gate.tick(at(harness.start, 2_500)).unwrap();
+        assert_eq!(update.tier, SignalTier::NoHeadset);
+        assert_eq!(update.poor_signal_level, NO_CONTACT_SIGNAL_LEVEL);
+        assert!(update.stale);
+        // Reported once, not on every tick.
+        assert_eq!(harness.tick(9_000), None);
+
+        // A packet clears the stale flag; the good tier still needs its dwell.
+        assert_eq!(harness.feed(0, 9_000).1, None);
+        let update = harness.gate.tick(at(harness.start, 10_500)).unwrap();
+        assert_eq!(update.tier, SignalTier::Good);
+        assert!(!update.stale);
+    }
+
+    #[test]
+    fn headset_that_never_sends_a_packet_is_reported() {
+        let mut harness = Harness::new();
+        assert_eq!(harness.tick(2_499), None);
+        assert_eq!(harness.tick(2_500), Some(SignalTier::NoHeadset));
+    }
+
+    #[test]
+    fn held_watchdog_counts_silence_from_the_hold() {
+        let start = Instant::now();
+        let mut gate = SignalQualityGate::new(SignalQualityConfig::default(), start);
+        gate.hold_watchdog(at(start, 2_000));
+        gate.hold_watchdog(at(start, 4_000));
+        assert!(gate.tick(at(start, 6_000)).is_none());
+
+        let update = gate
+            .tick(at(start, 6_500))
+            .expect("stale after 2.5 s unheld");
+        assert_eq!(update.tier, SignalTier::NoHeadset);
+        assert!(update.stale);
+    }
+}
@@ -0,0 +1,20 @@
+use crate::domain::{errors::AppError, ports::SerialConfigRepository, serial_config::SerialConfig};
+
+/// To remember the settings a port was opened with, so the next session on
+/// the same port starts from them.
+pub fn remember_serial_config(
+    port_name: &str,
+    config: SerialConfig,
+    repository: &mut impl SerialConfigRepository,
+) -> Result<(), AppError> {
+    repository.save(port_name, config)
+}
+
+/// To return the settings last used for a port, or the defaults for a port
+/// that has never been opened.
+pub fn recall_serial_config(
+    port_name: &str,
+    repository: &impl SerialConfigRepository,
+) -> Result<SerialConfig, AppError> {
+    Ok(repository.load(port_name)?.unwrap_or_default())
+}
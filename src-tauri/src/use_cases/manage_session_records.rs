@@ -1,7 +1,11 @@
 use crate::domain::{
     errors::AppError,
     ports::SessionRepository,
-    session_summary::SessionSummary,
+    session_recording::RecordedRow,
+    session_summary::{
+        summarize_recorded_rows, SessionCsvScan, SessionReindexReport, SessionSummary,
+        SkippedSessionCsv,
+    },
 };
 
 /// To append a completed session summary to the persistent store.
@@ -12,9 +16,262 @@ pub fn persist_session_summary(
     repository.save(summary)
 }
 
+/// To append a session summary whose aggregates are recomputed from the
+/// session's rows, discarding whatever the caller computed. Returns the
+/// summary as stored.
+pub fn persist_recorded_session(
+    summary: SessionSummary,
+    rows: &[RecordedRow],
+    repository: &mut impl SessionRepository,
+) -> Result<SessionSummary, AppError> {
+    let summary = summarize_recorded_rows(rows, summary);
+    repository.save(summary.clone())?;
+    Ok(summary)
+}
+
 /// To retrieve all previously saved session summaries from the persistent store.
 pub fn load_session_summaries(
     repository: &impl SessionRepository,
 ) -> Result<Vec<SessionSummary>, AppError> {
     repository.load_all()
 }
+
+/// To merge the CSVs found by a reindex into the persistent store, computing
+/// every summary from its rows. A CSV already indexed under its path is
+/// refreshed in place, keeping the id, subject, export time, duration and
+/// recovery flag the CSV cannot tell; a CSV whose content is already indexed
+/// under another path is skipped as a copy. The store is only rewritten when
+/// something was added or updated.
+pub fn reindex_session_summaries(
+    scan: SessionCsvScan,
+    repository: &mut impl SessionRepository,
+) -> Result<SessionReindexReport, AppError> {
+    let mut summaries = repository.load_all()?;
+    let mut report = SessionReindexReport {
+        skipped: scan.skipped,
+        ..SessionReindexReport::default()
+    };
+    for scanned in scan.found {
+        let summary = summarize_recorded_rows(&scanned.rows, scanned.summary);
+        let csv_path = summary.csv_path.clone();
+        let skip = |reason: String| SkippedSessionCsv {
+            csv_path: csv_path.clone(),
+            reason,
+        };
+        if let Some(indexed) = summaries
+            .iter_mut()
+            .find(|indexed| indexed.csv_path == csv_path)
+        {
+            let refreshed = SessionSummary {
+                id: indexed.id.clone(),
+                subject_name: indexed.subject_name.clone(),
+                exported_at: indexed.exported_at.clone(),
+                duration_secs: indexed.duration_secs,
+                recovered: indexed.recovered,
+                ..summary
+            };
+            if refreshed == *indexed {
+                report.skipped.push(skip("already up to date".to_string()));
+            } else {
+                *indexed = refreshed;
+                report.updated.push(csv_path);
+            }
+        } else if let Some(copy_of) = summaries
+            .iter()
+            .find(|indexed| indexed.content_hash == summary.content_hash)
+        {
+            report
+                .skipped
+                .push(skip(format!("same content as {}", copy_of.csv_path)));
+        } else {
+            summaries.push(summary);
+            report.added.push(csv_path);
+        }
+    }
+    if !report.added.is_empty() || !report.updated.is_empty() {
+        repository.replace_all(summaries)?;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        eeg_packet::{EegPacket, PacketTiming},
+        session_summary::ScannedSessionCsv,
+    };
+
+    // Index kept in memory, counting how often it was rewritten.
+    #[derive(Default)]
+    struct MemoryRepository {
+        summaries: Vec<SessionSummary>,
+        rewrites: u32,
+    }
+
+    impl SessionRepository for MemoryRepository {
+        fn save(&mut self, summary: SessionSummary) -> Result<(), AppError> {
+            self.summaries.push(summary);
+            Ok(())
+        }
+
+        fn load_all(&self) -> Result<Vec<SessionSummary>, AppError> {
+            Ok(self.summaries.clone())
+        }
+
+        fn replace_all(&mut self, summaries: Vec<SessionSummary>) -> Result<(), AppError> {
+            self.summaries = summaries;
+            self.rewrites += 1;
+            Ok(())
+        }
+    }
+
+    fn row(attention: u8, focus_label: i64) -> RecordedRow {
+        RecordedRow {
+            packet: EegPacket {
+                delta: 1,
+                theta: 2,
+                low_alpha: 3,
+                high_alpha: 5,
+                low_beta: 1,
+                high_beta: 1,
+                low_gamma: 1,
+                mid_gamma: 1,
+                attention,
+                meditation: 40,
+                poor_signal_level: 0,
+                device_millis: None,
+                device_seq: None,
+                blink_strength: None,
+                extensions: Default::default(),
+            },
+            timing: PacketTiming {
+                sequence: 0,
+                monotonic_ns: 0,
+                received_at_unix_ms: 0,
+            },
+            focus_label,
+            focus_prediction: "N/A".to_string(),
+        }
+    }
+
+    fn summary(csv_path: &str, content_hash: &str) -> SessionSummary {
+        SessionSummary {
+            id: format!("reindexed-{content_hash}"),
+            subject_name: "From file name".to_string(),
+            exported_at: "2024-03-01T10:00:00.000Z".to_string(),
+            csv_path: csv_path.to_string(),
+            sample_count: 0,
+            duration_secs: 60.0,
+            focused_count: 0,
+            unfocused_count: 0,
+            mean_alpha: 0.0,
+            mean_theta: 0.0,
+            mean_attention: 0.0,
+            mean_meditation: 0.0,
+            signal_quality_pct: 0.0,
+            raw_csv_path: None,
+            marker_count: 0,
+            markers_csv_path: None,
+            recovered: false,
+            content_hash: Some(content_hash.to_string()),
+        }
+    }
+
+    fn scanned(csv_path: &str, content_hash: &str, rows: Vec<RecordedRow>) -> ScannedSessionCsv {
+        ScannedSessionCsv {
+            summary: summary(csv_path, content_hash),
+            rows,
+        }
+    }
+
+    fn scan(found: Vec<ScannedSessionCsv>) -> SessionCsvScan {
+        SessionCsvScan {
+            found,
+            skipped: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn new_csvs_are_added_with_aggregates_from_their_rows() {
+        let mut repository = MemoryRepository::default();
+        let report = reindex_session_summaries(
+            scan(vec![scanned("a.csv", "aaaa", vec![row(80, 1), row(20, 0)])]),
+            &mut repository,
+        )
+        .unwrap();
+        assert_eq!(report.added, vec!["a.csv"]);
+        assert_eq!(repository.rewrites, 1);
+        let indexed = &repository.summaries[0];
+        assert_eq!(indexed.sample_count, 2);
+        assert_eq!((indexed.focused_count, indexed.unfocused_count), (1, 1));
+        assert_eq!(indexed.mean_attention, 50.0);
+    }
+
+    #[test]
+    fn indexed_csvs_are_refreshed_keeping_what_the_csv_cannot_tell() {
+        let mut repository = MemoryRepository::default();
+        repository.summaries.push(SessionSummary {
+            id: "1700000000-a".to_string(),
+            subject_name: "Ada Lovelace".to_string(),
+            duration_secs: 45.5,
+            recovered: true,
+            marker_count: 0,
+            content_hash: None,
+            ..summary("a.csv", "stale")
+        });
+        let rows = || vec![row(70, 1)];
+        let report = reindex_session_summaries(
+            scan(vec![scanned("a.csv", "aaaa", rows())]),
+            &mut repository,
+        )
+        .unwrap();
+        assert_eq!(report.updated, vec!["a.csv"]);
+        let indexed = &repository.summaries[0];
+        assert_eq!(indexed.id, "1700000000-a");
+        assert_eq!(indexed.subject_name, "Ada Lovelace");
+        assert_eq!(indexed.duration_secs, 45.5);
+        assert!(indexed.recovered);
+        assert_eq!(indexed.content_hash.as_deref(), Some("aaaa"));
+        assert_eq!(indexed.sample_count, 1);
+
+        // Nothing changed since, so the index is left alone.
+        let report = reindex_session_summaries(
+            scan(vec![scanned("a.csv", "aaaa", rows())]),
+            &mut repository,
+        )
+        .unwrap();
+        assert!(report.updated.is_empty());
+        assert_eq!(report.skipped[0].reason, "already up to date");
+        assert_eq!(repository.rewrites, 1);
+    }
+
+    #[test]
+    fn copies_of_indexed_content_are_skipped() {
+        let mut repository = MemoryRepository::default();
+        repository.summaries.push(summary("original.csv", "aaaa"));
+        let report = reindex_session_summaries(
+            scan(vec![
+                scanned("backup/original.csv", "aaaa", vec![row(70, 1)]),
+                scanned("other.csv", "bbbb", vec![row(70, 1)]),
+                scanned("backup/other.csv", "bbbb", vec![row(70, 1)]),
+            ]),
+            &mut repository,
+        )
+        .unwrap();
+        assert_eq!(report.added, vec!["other.csv"]);
+        let skipped: Vec<(&str, &str)> = report
+            .skipped
+            .iter()
+            .map(|skipped| (skipped.csv_path.as_str(), skipped.reason.as_str()))
+            .collect();
+        assert_eq!(
+            skipped,
+            vec![
+                ("backup/original.csv", "same content as original.csv"),
+                ("backup/other.csv", "same content as other.csv"),
+            ]
+        );
+        assert_eq!(repository.summaries.len(), 2);
+    }
+}
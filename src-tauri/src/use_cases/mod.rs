@@ -1,2 +1,6 @@
 pub mod classify_eeg_packet;
+pub mod gate_signal_quality;
+pub mod manage_serial_configs;
 pub mod manage_session_records;
+pub mod record_session;
+pub mod run_eeg_acquisition;
@@ -0,0 +1,768 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::domain::{
+    eeg_packet::PacketTiming,
+    errors::AppError,
+    focus_reading::InferenceHistory,
+    ports::{InferenceRunner, RecordingJournal},
+    raw_eeg_sample::RawEegBatch,
+    session_recording::{
+        JournalEntry, RecordedRawBatch, RecordedRow, RecordingMarker, RecordingOptions,
+        RecordingProgress, RecoverableSession, SessionMode,
+    },
+    session_summary::{summarize_recorded_rows, SessionSummary},
+    signal_quality::GatedEegPacket,
+};
+
+const UNAVAILABLE_LABEL: i64 = -1;
+const UNAVAILABLE_PREDICTION: &str = "N/A";
+
+struct ActiveRecording {
+    recording_id: String,
+    options: RecordingOptions,
+    journal: Box<dyn RecordingJournal + Send>,
+    paused: bool,
+    // Set while the journal is being exported; nothing more is journaled.
+    finishing: bool,
+    row_count: u32,
+    // Recording sequence of the last row journaled.
+    last_sequence: Option<u64>,
+    // Each reader counts its packets from 0, while the recording outlives
+    // reader restarts; a reader's sequences are shifted by this offset so
+    // the recording's keep rising.
+    sequence_offset: u64,
+    last_reader_sequence: Option<u64>,
+    // Kept per recording so concurrent headsets sharing one model never
+    // feed each other's packets into the temporal-delta feature.
+    inference_history: InferenceHistory,
+}
+
+/// Shared handle to one headset's recording. The reader thread hands it
+/// every gated packet while commands start, pause and finish it; it
+/// outlives reader restarts, so pausing a scan keeps the same recording.
+/// Cloning yields another handle to the same recording.
+#[derive(Clone, Default)]
+pub struct SessionRecorder {
+    state: Arc<Mutex<Option<ActiveRecording>>>,
+}
+
+impl SessionRecorder {
+    /// To begin a recording that journals to `journal`. Refused while
+    /// another recording is active on this headset.
+    pub fn start(
+        &self,
+        recording_id: String,
+        device_id: &str,
+        options: RecordingOptions,
+        mut journal: Box<dyn RecordingJournal + Send>,
+        at_unix_ms: u64,
+    ) -> Result<(), AppError> {
+        let mut state = self.lock();
+        if state.is_some() {
+            return Err(AppError::RecordingFailure(
+                "a recording is already active on this headset".to_string(),
+            ));
+        }
+        journal.append(&JournalEntry::Started {
+            recording_id: recording_id.clone(),
+            device_id: device_id.to_string(),
+            options: options.clone(),
+            at_unix_ms,
+        })?;
+        *state = Some(ActiveRecording {
+            recording_id,
+            options,
+            journal,
+            paused: false,
+            finishing: false,
+            row_count: 0,
+            last_sequence: None,
+            sequence_offset: 0,
+            last_reader_sequence: None,
+            inference_history: InferenceHistory::default(),
+        });
+        Ok(())
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.lock().is_some()
+    }
+
+    /// To return the id of the active recording. Its journal already holds
+    /// every row recorded so far.
+    pub fn active_recording_id(&self) -> Option<String> {
+        self.lock()
+            .as_ref()
+            .map(|recording| recording.recording_id.clone())
+    }
+
+    /// To stop taking rows until `resume`; paused time does not count
+    /// towards the session duration. Returns false if it was already paused.
+    pub fn pause(&self, at_unix_ms: u64) -> Result<bool, AppError> {
+        self.set_paused(true, at_unix_ms)
+    }
+
+    /// To take rows again after `pause`. Returns false if it was not paused.
+    pub fn resume(&self, at_unix_ms: u64) -> Result<bool, AppError> {
+        self.set_paused(false, at_unix_ms)
+    }
+
+    /// To journal one packet as a row, labelled for the session mode. `live`
+    /// rows are classified with `runner` when a model is loaded. The row's
+    /// sequence continues the recording's even when the reader restarted.
+    /// Returns None when nothing was recorded: no recording, paused, or a
+    /// rejected packet.
+    pub fn record_packet(
+        &self,
+        packet: &GatedEegPacket,
+        runner: Option<&mut dyn InferenceRunner>,
+    ) -> Result<Option<RecordingProgress>, AppError> {
+        let mut state = self.lock();
+        let Some(recording) = state.as_mut().filter(|recording| !recording.paused) else {
+            return Ok(None);
+        };
+        if !packet.verdict.accepted {
+            return Ok(None);
+        }
+        let (focus_label, focus_prediction, inference_error) =
+            label_packet(packet, recording, runner);
+        let row = RecordedRow {
+            packet: packet.packet.clone(),
+            timing: PacketTiming {
+                sequence: recording.recording_sequence(packet.timing.sequence),
+                ..packet.timing
+            },
+            focus_label,
+            focus_prediction,
+        };
+        recording.journal.append(&JournalEntry::Row(row.clone()))?;
+        recording.row_count += 1;
+        recording.last_sequence = Some(row.timing.sequence);
+        Ok(Some(RecordingProgress {
+            row_count: recording.row_count,
+            sequence: row.timing.sequence,
+            focus_label: row.focus_label,
+            focus_prediction: row.focus_prediction,
+            inference_error,
+        }))
+    }
+
+    /// To journal a batch of raw samples received at `received_at_unix_ms`.
+    /// Returns false when nothing was recorded: no recording, or paused.
+    pub fn record_raw_batch(
+        &self,
+        batch: &RawEegBatch,
+        received_at_unix_ms: u64,
+    ) -> Result<bool, AppError> {
+        let mut state = self.lock();
+        let Some(recording) = state.as_mut().filter(|recording| !recording.paused) else {
+            return Ok(false);
+        };
+        if batch.samples.is_empty() {
+            return Ok(false);
+        }
+        recording
+            .journal
+            .append(&JournalEntry::Raw(RecordedRawBatch {
+                received_at_unix_ms,
+                after_sequence: recording.last_sequence,
+                samples: batch.samples.clone(),
+            }))?;
+        Ok(true)
+    }
+
+    /// To journal a marker against the active recording, paused or not,
+    /// anchored after the last row recorded so far. Returns it as stored.
+    pub fn add_marker(&self, mut marker: RecordingMarker) -> Result<RecordingMarker, AppError> {
+        let mut state = self.lock();
+        let recording = state.as_mut().ok_or_else(no_active_recording)?;
+        if recording.finishing {
+            return Err(being_finished());
+        }
+        marker.label = marker.label.trim().to_string();
+        if marker.label.is_empty() {
+            return Err(AppError::RecordingFailure(
+                "a marker needs a label".to_string(),
+            ));
+        }
+        marker.after_sequence = recording.last_sequence;
+        recording
+            .journal
+            .append(&JournalEntry::Marker(marker.clone()))?;
+        Ok(marker)
+    }
+
+    /// To close the active recording to rows, raw samples and markers before
+    /// its journal is read for export, so nothing journaled during the
+    /// export can be left out of it. The recording is paused, and resuming
+    /// or adding markers is refused until `finish` or `cancel_finish`.
+    /// Returns whether this paused it.
+    pub fn begin_finish(&self, at_unix_ms: u64) -> Result<bool, AppError> {
+        let mut state = self.lock();
+        let recording = state.as_mut().ok_or_else(no_active_recording)?;
+        if recording.finishing {
+            return Err(being_finished());
+        }
+        let paused_now = !recording.paused;
+        if paused_now {
+            recording
+                .journal
+                .append(&JournalEntry::Paused { at_unix_ms })?;
+            recording.paused = true;
+        }
+        recording.finishing = true;
+        Ok(paused_now)
+    }
+
+    /// To reopen a recording whose export failed after `begin_finish`,
+    /// resuming it when `resume` (i.e. `begin_finish` paused it).
+    pub fn cancel_finish(&self, resume: bool, at_unix_ms: u64) -> Result<(), AppError> {
+        let mut state = self.lock();
+        let recording = state.as_mut().ok_or_else(no_active_recording)?;
+        recording.finishing = false;
+        if resume && recording.paused {
+            recording
+                .journal
+                .append(&JournalEntry::Resumed { at_unix_ms })?;
+            recording.paused = false;
+        }
+        Ok(())
+    }
+
+    /// To mark the active recording as exported and release it. The
+    /// recording is released even when the marker cannot be written.
+    pub fn finish(&self, at_unix_ms: u64) -> Result<(), AppError> {
+        let mut recording = self.lock().take().ok_or_else(no_active_recording)?;
+        recording
+            .journal
+            .append(&JournalEntry::Finished { at_unix_ms })
+    }
+
+    /// To drop the active recording without exporting it. Returns its id so
+    /// the caller can delete the journal.
+    pub fn abandon(&self) -> Option<String> {
+        self.lock().take().map(|recording| recording.recording_id)
+    }
+
+    fn set_paused(&self, paused: bool, at_unix_ms: u64) -> Result<bool, AppError> {
+        let mut state = self.lock();
+        let recording = state.as_mut().ok_or_else(no_active_recording)?;
+        if recording.paused == paused {
+            return Ok(false);
+        }
+        if recording.finishing {
+            return Err(being_finished());
+        }
+        let entry = if paused {
+            JournalEntry::Paused { at_unix_ms }
+        } else {
+            JournalEntry::Resumed { at_unix_ms }
+        };
+        recording.journal.append(&entry)?;
+        recording.paused = paused;
+        Ok(true)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Option<ActiveRecording>> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl ActiveRecording {
+    // A reader sequence that does not rise past the last one recorded came
+    // from a restarted reader, which then continues after the last row.
+    fn recording_sequence(&mut self, reader_sequence: u64) -> u64 {
+        if self
+            .last_reader_sequence
+            .is_some_and(|last| reader_sequence <= last)
+        {
+            self.sequence_offset = self.last_sequence.map_or(0, |last| last + 1);
+        }
+        self.last_reader_sequence = Some(reader_sequence);
+        self.sequence_offset + reader_sequence
+    }
+}
+
+fn no_active_recording() -> AppError {
+    AppError::RecordingFailure("no recording is active on this headset".to_string())
+}
+
+fn being_finished() -> AppError {
+    AppError::RecordingFailure("the recording on this headset is being finished".to_string())
+}
+
+// Recording mode labels from attention alone; live mode asks the model and
+// falls back to -1 so no packet is silently dropped when inference fails.
+fn label_packet(
+    packet: &GatedEegPacket,
+    recording: &mut ActiveRecording,
+    runner: Option<&mut dyn InferenceRunner>,
+) -> (i64, String, Option<String>) {
+    let unavailable =
+        |error: Option<String>| (UNAVAILABLE_LABEL, UNAVAILABLE_PREDICTION.to_string(), error);
+    let options = &recording.options;
+    match options.session_mode {
+        SessionMode::Recording => (
+            i64::from(packet.packet.attention >= options.attention_threshold),
+            UNAVAILABLE_PREDICTION.to_string(),
+            None,
+        ),
+        SessionMode::Live => match runner
+            .map(|runner| runner.predict(&packet.packet, &mut recording.inference_history))
+        {
+            None => unavailable(None),
+            Some(Ok(reading)) if reading.label == 0 || reading.label == 1 => {
+                (reading.label, reading.label_name, None)
+            }
+            Some(Ok(reading)) => {
+                unavailable(Some(format!("unexpected model label {}", reading.label)))
+            }
+            Some(Err(error)) => unavailable(Some(error.to_string())),
+        },
+    }
+}
+
+/// Where and when a journal is being exported.
+pub struct SessionExport {
+    pub csv_path: String,
+    /// ISO 8601.
+    pub exported_at: String,
+    /// Whether the journal is being salvaged after a crash.
+    pub recovered: bool,
+}
+
+/// A journal turned back into CSV rows, the raw samples and markers recorded
+/// among them and the summary that indexes them.
+pub struct FinishedRecording {
+    pub rows: Vec<RecordedRow>,
+    pub raw_batches: Vec<RecordedRawBatch>,
+    pub markers: Vec<RecordingMarker>,
+    pub summary: SessionSummary,
+}
+
+/// To rebuild a session from its journal entries: the rows, raw samples and
+/// markers in recorded order and a summary whose duration counts only unpaused time.
+pub fn finish_recorded_session(
+    entries: Vec<JournalEntry>,
+    export: SessionExport,
+) -> Result<FinishedRecording, AppError> {
+    let mut identity: Option<(String, RecordingOptions)> = None;
+    let mut rows = Vec::new();
+    let mut raw_batches = Vec::new();
+    let mut markers = Vec::new();
+    let mut active_ms: u64 = 0;
+    let mut active_since: Option<u64> = None;
+    let mut last_seen_ms: Option<u64> = None;
+    for entry in entries {
+        match entry {
+            JournalEntry::Started {
+                recording_id,
+                options,
+                at_unix_ms,
+                ..
+            } => {
+                identity = Some((recording_id, options));
+                active_since = Some(at_unix_ms);
+                last_seen_ms = Some(at_unix_ms);
+            }
+            JournalEntry::Row(row) => {
+                last_seen_ms = Some(row.timing.received_at_unix_ms);
+                rows.push(row);
+            }
+            JournalEntry::Raw(batch) => {
+                last_seen_ms = Some(batch.received_at_unix_ms);
+                raw_batches.push(batch);
+            }
+            JournalEntry::Marker(marker) => {
+                last_seen_ms = Some(marker.at_unix_ms);
+                markers.push(marker);
+            }
+            JournalEntry::Resumed { at_unix_ms } => {
+                active_since = active_since.or(Some(at_unix_ms));
+                last_seen_ms = Some(at_unix_ms);
+            }
+            JournalEntry::Paused { at_unix_ms } | JournalEntry::Finished { at_unix_ms } => {
+                if let Some(since) = active_since.take() {
+                    active_ms += at_unix_ms.saturating_sub(since);
+                }
+                last_seen_ms = Some(at_unix_ms);
+            }
+        }
+    }
+    // A journal cut short never saw its end, so count up to its last entry.
+    if let (Some(since), Some(last_seen_ms)) = (active_since, last_seen_ms) {
+        active_ms += last_seen_ms.saturating_sub(since);
+    }
+    let (recording_id, options) = identity.ok_or_else(|| {
+        AppError::RecordingFailure("recording journal has no start entry".to_string())
+    })?;
+    let summary = summarize_recorded_rows(
+        &rows,
+        SessionSummary {
+            id: recording_id,
+            subject_name: options.subject_name,
+            exported_at: export.exported_at,
+            csv_path: export.csv_path,
+            sample_count: 0,
+            duration_secs: active_ms as f64 / 1_000.0,
+            focused_count: 0,
+            unfocused_count: 0,
+            mean_alpha: 0.0,
+            mean_theta: 0.0,
+            mean_attention: 0.0,
+            mean_meditation: 0.0,
+            signal_quality_pct: 0.0,
+            raw_csv_path: None,
+            marker_count: markers.len() as u32,
+            markers_csv_path: None,
+            recovered: export.recovered,
+            content_hash: None,
+        },
+    );
+    Ok(FinishedRecording {
+        rows,
+        raw_batches,
+        markers,
+        summary,
+    })
+}
+
+/// To describe a journal found on disk at startup. Returns None when there
+/// is nothing to recover: the session was exported, or no row was recorded.
+pub fn describe_interrupted_recording(entries: &[JournalEntry]) -> Option<RecoverableSession> {
+    let Some(JournalEntry::Started {
+        recording_id,
+        device_id,
+        options,
+        at_unix_ms,
+    }) = entries.first()
+    else {
+        return None;
+    };
+    let mut row_count = 0;
+    let mut last_entry_at_unix_ms = *at_unix_ms;
+    for entry in entries {
+        match entry {
+            JournalEntry::Finished { .. } => return None,
+            JournalEntry::Row(row) => {
+                row_count += 1;
+                last_entry_at_unix_ms = row.timing.received_at_unix_ms;
+            }
+            JournalEntry::Raw(batch) => {
+                last_entry_at_unix_ms = batch.received_at_unix_ms;
+            }
+            JournalEntry::Marker(RecordingMarker { at_unix_ms, .. })
+            | JournalEntry::Paused { at_unix_ms }
+            | JournalEntry::Resumed { at_unix_ms } => {
+                last_entry_at_unix_ms = *at_unix_ms;
+            }
+            JournalEntry::Started { .. } => {}
+        }
+    }
+    (row_count > 0).then(|| RecoverableSession {
+        recording_id: recording_id.clone(),
+        device_id: device_id.clone(),
+        subject_name: options.subject_name.clone(),
+        session_mode: options.session_mode,
+        started_at_unix_ms: *at_unix_ms,
+        last_entry_at_unix_ms,
+        row_count,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        eeg_packet::EegPacket,
+        focus_reading::{focus_label_name, FocusReading},
+        raw_eeg_sample::{RawEegSample, RAW_SAMPLE_RATE_HZ},
+        signal_quality::PacketVerdict,
+    };
+
+    // Journal that keeps entries in memory, shared with the test.
+    #[derive(Clone, Default)]
+    struct MemoryJournal(Arc<Mutex<Vec<JournalEntry>>>);
+
+    impl RecordingJournal for MemoryJournal {
+        fn append(&mut self, entry: &JournalEntry) -> Result<(), AppError> {
+            self.0.lock().unwrap().push(entry.clone());
+            Ok(())
+        }
+    }
+
+    fn accepted_packet(sequence: u64, received_at_unix_ms: u64) -> GatedEegPacket {
+        GatedEegPacket {
+            packet: EegPacket {
+                delta: 1,
+                theta: 1,
+                low_alpha: 1,
+                high_alpha: 1,
+                low_beta: 1,
+                high_beta: 1,
+                low_gamma: 1,
+                mid_gamma: 1,
+                attention: 70,
+                meditation: 40,
+                poor_signal_level: 0,
+                device_millis: None,
+                device_seq: None,
+                blink_strength: None,
+                extensions: Default::default(),
+            },
+            timing: PacketTiming {
+                sequence,
+                monotonic_ns: 0,
+                received_at_unix_ms,
+            },
+            verdict: PacketVerdict {
+                accepted: true,
+                rejection_reason: None,
+            },
+        }
+    }
+
+    fn raw_batch(values: &[i16]) -> RawEegBatch {
+        RawEegBatch {
+            sample_rate_hz: RAW_SAMPLE_RATE_HZ,
+            samples: values.iter().map(|&value| RawEegSample { value }).collect(),
+        }
+    }
+
+    fn marker(label: &str, at_unix_ms: u64) -> RecordingMarker {
+        RecordingMarker {
+            label: label.to_string(),
+            category: None,
+            note: None,
+            at_unix_ms,
+            after_sequence: None,
+        }
+    }
+
+    // Labels a packet Focused when its delta rose since the previous packet
+    // of its stream, like the model's temporal-delta feature would see it.
+    struct DeltaTrendRunner;
+
+    impl InferenceRunner for DeltaTrendRunner {
+        fn predict(
+            &mut self,
+            packet: &EegPacket,
+            history: &mut InferenceHistory,
+        ) -> Result<FocusReading, AppError> {
+            let delta = packet.delta as f32;
+            let label = i64::from(delta > history.prev_delta_relative);
+            history.prev_delta_relative = delta;
+            Ok(FocusReading {
+                label,
+                label_name: focus_label_name(label),
+            })
+        }
+    }
+
+    fn packet_with_delta(sequence: u64, delta: u32) -> GatedEegPacket {
+        let mut packet = accepted_packet(sequence, sequence * 1_000);
+        packet.packet.delta = delta;
+        packet
+    }
+
+    fn started_recorder(journal: &MemoryJournal) -> SessionRecorder {
+        started_recorder_in(journal, SessionMode::Recording)
+    }
+
+    fn started_recorder_in(journal: &MemoryJournal, session_mode: SessionMode) -> SessionRecorder {
+        let recorder = SessionRecorder::default();
+        let options = RecordingOptions {
+            subject_name: "Subject".to_string(),
+            session_mode,
+            attention_threshold: 50,
+        };
+        recorder
+            .start(
+                "1-a".to_string(),
+                "a",
+                options,
+                Box::new(journal.clone()),
+                0,
+            )
+            .unwrap();
+        recorder
+    }
+
+    // To label `deltas` on a fresh live recording with its own runner.
+    fn labels_alone(deltas: &[u32]) -> Vec<i64> {
+        let recorder = started_recorder_in(&MemoryJournal::default(), SessionMode::Live);
+        deltas
+            .iter()
+            .zip(0..)
+            .map(|(&delta, sequence)| {
+                recorder
+                    .record_packet(
+                        &packet_with_delta(sequence, delta),
+                        Some(&mut DeltaTrendRunner),
+                    )
+                    .unwrap()
+                    .unwrap()
+                    .focus_label
+            })
+            .collect()
+    }
+
+    #[test]
+    fn concurrent_live_recordings_keep_their_own_inference_history() {
+        let first_deltas = [10, 20, 30];
+        let second_deltas = [50, 40, 35];
+        let first = started_recorder_in(&MemoryJournal::default(), SessionMode::Live);
+        let second = started_recorder_in(&MemoryJournal::default(), SessionMode::Live);
+        let mut shared_runner = DeltaTrendRunner;
+        let mut first_labels = Vec::new();
+        let mut second_labels = Vec::new();
+        for (sequence, (&first_delta, &second_delta)) in
+            (0..).zip(first_deltas.iter().zip(&second_deltas))
+        {
+            let progress = first
+                .record_packet(
+                    &packet_with_delta(sequence, first_delta),
+                    Some(&mut shared_runner),
+                )
+                .unwrap()
+                .unwrap();
+            first_labels.push(progress.focus_label);
+            let progress = second
+                .record_packet(
+                    &packet_with_delta(sequence, second_delta),
+                    Some(&mut shared_runner),
+                )
+                .unwrap()
+                .unwrap();
+            second_labels.push(progress.focus_label);
+        }
+        assert_eq!(first_labels, labels_alone(&first_deltas));
+        assert_eq!(second_labels, labels_alone(&second_deltas));
+        assert_eq!(second_labels, vec![1, 0, 0]);
+    }
+
+    #[test]
+    fn raw_batches_are_journaled_and_replayed_on_finish() {
+        let journal = MemoryJournal::default();
+        let recorder = started_recorder(&journal);
+        assert!(recorder.record_raw_batch(&raw_batch(&[1, 2]), 500).unwrap());
+        recorder
+            .record_packet(&accepted_packet(3, 1_000), None)
+            .unwrap();
+        recorder.pause(1_100).unwrap();
+        assert!(!recorder.record_raw_batch(&raw_batch(&[9]), 1_200).unwrap());
+        recorder.resume(1_300).unwrap();
+        assert!(recorder.record_raw_batch(&raw_batch(&[3]), 1_400).unwrap());
+        assert!(!recorder.record_raw_batch(&raw_batch(&[]), 1_500).unwrap());
+
+        let entries = journal.0.lock().unwrap().clone();
+        let export = SessionExport {
+            csv_path: "session.csv".to_string(),
+            exported_at: "2024-01-01T00:00:00.000Z".to_string(),
+            recovered: false,
+        };
+        let finished = finish_recorded_session(entries, export).unwrap();
+        let replayed: Vec<(Option<u64>, Vec<i16>)> = finished
+            .raw_batches
+            .iter()
+            .map(|batch| {
+                let values = batch.samples.iter().map(|sample| sample.value).collect();
+                (batch.after_sequence, values)
+            })
+            .collect();
+        assert_eq!(replayed, vec![(None, vec![1, 2]), (Some(3), vec![3])]);
+        assert_eq!(finished.rows.len(), 1);
+    }
+
+    #[test]
+    fn sequences_keep_rising_across_a_reader_restart() {
+        let journal = MemoryJournal::default();
+        let recorder = started_recorder(&journal);
+        for sequence in 0..3 {
+            recorder
+                .record_packet(&accepted_packet(sequence, 1_000 + sequence), None)
+                .unwrap();
+        }
+        // Pausing the scan stops the reader; the next one counts from 0.
+        recorder.pause(2_000).unwrap();
+        recorder.resume(3_000).unwrap();
+        let progress = recorder
+            .record_packet(&accepted_packet(0, 3_100), None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(progress.sequence, 3);
+        recorder.record_raw_batch(&raw_batch(&[7]), 3_150).unwrap();
+        recorder.add_marker(marker("task", 3_160)).unwrap();
+        recorder
+            .record_packet(&accepted_packet(2, 3_200), None)
+            .unwrap();
+
+        let entries = journal.0.lock().unwrap().clone();
+        let export = SessionExport {
+            csv_path: "session.csv".to_string(),
+            exported_at: "2024-01-01T00:00:00.000Z".to_string(),
+            recovered: false,
+        };
+        let finished = finish_recorded_session(entries, export).unwrap();
+        let sequences: Vec<u64> = finished
+            .rows
+            .iter()
+            .map(|row| row.timing.sequence)
+            .collect();
+        assert_eq!(sequences, vec![0, 1, 2, 3, 5]);
+        assert_eq!(finished.raw_batches[0].after_sequence, Some(3));
+        assert_eq!(finished.markers[0].after_sequence, Some(3));
+    }
+
+    #[test]
+    fn finishing_refuses_markers_until_cancelled() {
+        let journal = MemoryJournal::default();
+        let recorder = started_recorder(&journal);
+        recorder
+            .record_packet(&accepted_packet(0, 1_000), None)
+            .unwrap();
+        assert!(recorder.begin_finish(1_100).unwrap());
+        assert!(recorder.add_marker(marker("late", 1_200)).is_err());
+        assert!(recorder.resume(1_200).is_err());
+        assert!(recorder
+            .record_packet(&accepted_packet(1, 1_200), None)
+            .unwrap()
+            .is_none());
+
+        // A failed export reopens the recording as it was.
+        recorder.cancel_finish(true, 1_300).unwrap();
+        recorder.add_marker(marker("retry", 1_400)).unwrap();
+        assert!(recorder
+            .record_packet(&accepted_packet(2, 1_500), None)
+            .unwrap()
+            .is_some());
+        let markers: Vec<String> = journal
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|entry| match entry {
+                JournalEntry::Marker(marker) => Some(marker.label.clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(markers, vec!["retry".to_string()]);
+    }
+
+    #[test]
+    fn interrupted_recording_counts_raw_batches_as_activity() {
+        let journal = MemoryJournal::default();
+        let recorder = started_recorder(&journal);
+        recorder
+            .record_packet(&accepted_packet(0, 1_000), None)
+            .unwrap();
+        recorder.record_raw_batch(&raw_batch(&[5]), 1_800).unwrap();
+
+        let entries = journal.0.lock().unwrap().clone();
+        let recoverable = describe_interrupted_recording(&entries).unwrap();
+        assert_eq!(recoverable.row_count, 1);
+        assert_eq!(recoverable.last_entry_at_unix_ms, 1_800);
+    }
+}
@@ -0,0 +1,633 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::{
+    domain::{
+        connection_status::ConnectionStatus,
+        eeg_packet::{EegPacket, PacketTiming},
+        eeg_reading::EegReading,
+        link_diagnostics::LinkDiagnostics,
+        ports::{EegEventSink, EegSource},
+        raw_eeg_sample::{RawEegBatch, RawEegSample, RAW_SAMPLE_RATE_HZ},
+        signal_quality::{GatedEegPacket, SignalQualityConfig},
+        source_event::SourceEvent,
+    },
+    use_cases::gate_signal_quality::SignalQualityGate,
+};
+
+/// Number of 512 Hz raw samples bundled into one `eeg-raw` event (~8 events/s).
+const RAW_SAMPLES_PER_EVENT: usize = 64;
+/// How often a `link-stats` snapshot goes out while the reader runs.
+const LINK_STATS_INTERVAL: Duration = Duration::from_secs(1);
+
+// The moment a reading came off the source, on both clocks: the monotonic
+// one spaces packets, the wall clock dates them in recordings. Both are read
+// together so a slow sink cannot push the wall-clock time past the read.
+#[derive(Clone, Copy)]
+struct ReadTime {
+    instant: Instant,
+    unix_ms: u64,
+}
+
+impl ReadTime {
+    fn now() -> Self {
+        Self {
+            instant: Instant::now(),
+            // A clock set before 1970 is not worth failing a packet over.
+            unix_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_millis() as u64),
+        }
+    }
+}
+
+// Raw samples waiting to fill a batch, and when the latest one was read.
+struct RawBatchBuffer {
+    samples: Vec<RawEegSample>,
+    last_read_at_unix_ms: u64,
+}
+
+impl RawBatchBuffer {
+    fn push(&mut self, sample: RawEegSample, read_at: ReadTime) {
+        self.samples.push(sample);
+        self.last_read_at_unix_ms = read_at.unix_ms;
+    }
+
+    fn flush(&mut self, sink: &impl EegEventSink) {
+        if self.samples.is_empty() {
+            return;
+        }
+        let batch = RawEegBatch {
+            sample_rate_hz: RAW_SAMPLE_RATE_HZ,
+            samples: std::mem::take(&mut self.samples),
+        };
+        sink.publish_raw_batch(&batch, self.last_read_at_unix_ms);
+    }
+}
+
+// Stamps packets in arrival order against the moment acquisition started.
+// Blinks usually arrive as separate readings, so the strongest one since the
+// previous packet is carried onto the next packet for the recorder.
+struct PacketClock {
+    started: Instant,
+    next_sequence: u64,
+    strongest_blink: Option<u8>,
+}
+
+impl PacketClock {
+    fn note_blink(&mut self, strength: u8) {
+        self.strongest_blink = self.strongest_blink.max(Some(strength));
+    }
+
+    fn stamp(&mut self, packet: &mut EegPacket, received: ReadTime) -> PacketTiming {
+        let strongest_blink = self.strongest_blink.take();
+        if packet.blink_strength.is_none() {
+            packet.blink_strength = strongest_blink;
+        }
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        PacketTiming {
+            sequence,
+            monotonic_ns: received
+                .instant
+                .saturating_duration_since(self.started)
+                .as_nanos() as u64,
+            received_at_unix_ms: received.unix_ms,
+        }
+    }
+}
+
+/// To drive one EEG source until it finishes, fails or a stop is requested,
+/// forwarding everything it produces to the sink. Every packet passes through
+/// the signal quality gate, so all consumers see the same accept/reject
+/// decisions. Knows nothing about serial ports or Tauri, so the whole loop
+/// runs the same against simulated, replay and network sources. Link and
+/// parser counters accumulate in `diagnostics`, which outlives the loop so a
+/// failed session can still be inspected.
+pub fn run_eeg_acquisition(
+    source: &mut dyn EegSource,
+    sink: &impl EegEventSink,
+    stop_flag: &AtomicBool,
+    signal_quality: SignalQualityConfig,
+    diagnostics: &LinkDiagnostics,
+) {
+    source.attach_link_diagnostics(diagnostics.clone());
+    sink.publish_status(ConnectionStatus::Connecting);
+    let link = match source.start() {
+        Ok(link) => link,
+        Err(error) => {
+            eprintln!("[IO] EEG source failed to start: {error}");
+            sink.publish_status(ConnectionStatus::from(&error));
+            return;
+        }
+    };
+    sink.publish_status(ConnectionStatus::Connected(link.clone()));
+
+    let started = Instant::now();
+    let mut quality_gate = SignalQualityGate::new(signal_quality, started);
+    let mut packet_clock = PacketClock {
+        started,
+        next_sequence: 0,
+        strongest_blink: None,
+    };
+    let mut raw_batch = RawBatchBuffer {
+        samples: Vec::with_capacity(RAW_SAMPLES_PER_EVENT),
+        last_read_at_unix_ms: 0,
+    };
+    let mut next_link_stats_at = started + LINK_STATS_INTERVAL;
+    // Set by a watchdog trip until packets flow again.
+    let mut headset_silent = false;
+    while !stop_flag.load(Ordering::Relaxed) {
+        let event = source.next_event();
+        // Sources return as soon as a line or chunk is decoded, so this is
+        // the read time to within the parse.
+        let received = ReadTime::now();
+        match event {
+            Ok(SourceEvent::Reading(reading)) => {
+                // The headset is back, so the link is connected again.
+                if headset_silent && matches!(reading, EegReading::Packet(_)) {
+                    headset_silent = false;
+                    sink.publish_status(ConnectionStatus::Connected(link.clone()));
+                }
+                forward_reading(
+                    reading,
+                    received,
+                    &mut packet_clock,
+                    &mut quality_gate,
+                    &mut raw_batch,
+                    diagnostics,
+                    sink,
+                )
+            }
+            Ok(SourceEvent::Status(status)) => sink.publish_status(status),
+            Ok(SourceEvent::GroundTruth(ground_truth)) => sink.publish_ground_truth(&ground_truth),
+            Ok(SourceEvent::Idle) => {}
+            Ok(SourceEvent::Paused) => quality_gate.hold_watchdog(received.instant),
+            Ok(SourceEvent::Finished) => {
+                sink.publish_status(ConnectionStatus::Disconnected);
+                break;
+            }
+            Err(error) => {
+                eprintln!("[IO] EEG source stopped: {error}");
+                sink.publish_status(ConnectionStatus::from(&error));
+                break;
+            }
+        }
+        if let Some(update) = quality_gate.tick(Instant::now()) {
+            // A watchdog trip means the link is up but nothing is arriving.
+            if update.stale {
+                headset_silent = true;
+                sink.publish_status(ConnectionStatus::HeadsetNotDetected);
+            }
+            sink.publish_signal_quality(&update);
+        }
+        let now = Instant::now();
+        if now >= next_link_stats_at {
+            sink.publish_link_stats(&diagnostics.snapshot(now));
+            next_link_stats_at = now + LINK_STATS_INTERVAL;
+        }
+    }
+    // Forward any trailing partial batch so a stop or dropout never loses the
+    // last fraction of a second of raw signal.
+    raw_batch.flush(sink);
+    sink.publish_link_stats(&diagnostics.snapshot(Instant::now()));
+    source.stop();
+}
+
+// To forward one decoded reading: complete 1 Hz packets are stamped, gated
+// and go out with their timing and verdict, blinks go out immediately, raw
+// samples are buffered until a full batch is available. A blink the source
+// reported inside a packet also goes out as a blink.
+fn forward_reading(
+    reading: EegReading,
+    received: ReadTime,
+    packet_clock: &mut PacketClock,
+    quality_gate: &mut SignalQualityGate,
+    raw_batch: &mut RawBatchBuffer,
+    diagnostics: &LinkDiagnostics,
+    sink: &impl EegEventSink,
+) {
+    match reading {
+        EegReading::Packet(mut packet) => {
+            diagnostics.record_packet(received.instant);
+            if let Some(strength) = packet.blink_strength {
+                sink.publish_blink(strength);
+            }
+            let timing = packet_clock.stamp(&mut packet, received);
+            let (verdict, update) = quality_gate.assess_packet(&packet, received.instant);
+            sink.publish_packet(&GatedEegPacket {
+                packet,
+                timing,
+                verdict,
+            });
+            if let Some(update) = update {
+                sink.publish_signal_quality(&update);
+            }
+        }
+        EegReading::Raw(sample) => {
+            raw_batch.push(sample, received);
+            if raw_batch.samples.len() >= RAW_SAMPLES_PER_EVENT {
+                raw_batch.flush(sink);
+            }
+        }
+        EegReading::Blink(strength) => {
+            packet_clock.note_blink(strength);
+            sink.publish_blink(strength);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+
+    use super::*;
+    use crate::domain::{
+        connection_status::LinkInfo, errors::AppError, ground_truth::GroundTruth,
+        link_diagnostics::LinkStats, signal_quality::SignalQualityUpdate,
+    };
+
+    // Source that plays back a script, then either finishes or raises the
+    // stop flag the way a stop command would.
+    struct ScriptedSource<'a> {
+        script: VecDeque<Result<SourceEvent, AppError>>,
+        stop_when_exhausted: Option<&'a AtomicBool>,
+        stopped: bool,
+    }
+
+    impl<'a> ScriptedSource<'a> {
+        fn new(script: Vec<Result<SourceEvent, AppError>>) -> Self {
+            Self {
+                script: script.into(),
+                stop_when_exhausted: None,
+                stopped: false,
+            }
+        }
+    }
+
+    impl EegSource for ScriptedSource<'_> {
+        fn start(&mut self) -> Result<LinkInfo, AppError> {
+            Ok(link())
+        }
+
+        fn next_event(&mut self) -> Result<SourceEvent, AppError> {
+            match (self.script.pop_front(), self.stop_when_exhausted) {
+                (Some(event), _) => event,
+                (None, Some(stop_flag)) => {
+                    stop_flag.store(true, Ordering::Relaxed);
+                    Ok(SourceEvent::Idle)
+                }
+                (None, None) => Ok(SourceEvent::Finished),
+            }
+        }
+
+        fn stop(&mut self) {
+            self.stopped = true;
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Published {
+        Packet {
+            sequence: u64,
+            accepted: bool,
+            blink: Option<u8>,
+        },
+        RawBatch(Vec<i16>),
+        Blink(u8),
+        Status(ConnectionStatus),
+    }
+
+    // Sink that keeps what the loop published, and apart from it the
+    // wall-clock times on packets and raw batches; quality and link-stats
+    // snapshots are left out so assertions stay on the event stream.
+    #[derive(Default)]
+    struct RecordingSink(RefCell<Vec<Published>>, RefCell<Vec<u64>>);
+
+    impl RecordingSink {
+        fn statuses(&self) -> Vec<ConnectionStatus> {
+            self.0
+                .borrow()
+                .iter()
+                .filter_map(|event| match event {
+                    Published::Status(status) => Some(status.clone()),
+                    _ => None,
+                })
+                .collect()
+        }
+    }
+
+    impl EegEventSink for RecordingSink {
+        fn publish_packet(&self, packet: &GatedEegPacket) {
+            self.0.borrow_mut().push(Published::Packet {
+                sequence: packet.timing.sequence,
+                accepted: packet.verdict.accepted,
+                blink: packet.packet.blink_strength,
+            });
+            self.1.borrow_mut().push(packet.timing.received_at_unix_ms);
+        }
+
+        fn publish_raw_batch(&self, batch: &RawEegBatch, received_at_unix_ms: u64) {
+            let values = batch.samples.iter().map(|sample| sample.value).collect();
+            self.0.borrow_mut().push(Published::RawBatch(values));
+            self.1.borrow_mut().push(received_at_unix_ms);
+        }
+
+        fn publish_blink(&self, strength: u8) {
+            self.0.borrow_mut().push(Published::Blink(strength));
+        }
+
+        fn publish_status(&self, status: ConnectionStatus) {
+            self.0.borrow_mut().push(Published::Status(status));
+        }
+
+        fn publish_signal_quality(&self, _update: &SignalQualityUpdate) {}
+
+        fn publish_ground_truth(&self, _ground_truth: &GroundTruth) {}
+
+        fn publish_link_stats(&self, _stats: &LinkStats) {}
+    }
+
+    fn link() -> LinkInfo {
+        LinkInfo {
+            port: "COM3".to_string(),
+            baud: Some(115_200),
+        }
+    }
+
+    fn packet(poor_signal_level: u8) -> Result<SourceEvent, AppError> {
+        Ok(SourceEvent::Reading(EegReading::Packet(EegPacket {
+            delta: 1,
+            theta: 1,
+            low_alpha: 1,
+            high_alpha: 1,
+            low_beta: 1,
+            high_beta: 1,
+            low_gamma: 1,
+            mid_gamma: 1,
+            attention: 50,
+            meditation: 50,
+            poor_signal_level,
+            device_millis: None,
+            device_seq: None,
+            blink_strength: None,
+            extensions: Default::default(),
+        })))
+    }
+
+    fn raw(value: i16) -> Result<SourceEvent, AppError> {
+        Ok(SourceEvent::Reading(EegReading::Raw(RawEegSample {
+            value,
+        })))
+    }
+
+    fn run(source: &mut ScriptedSource, stop_flag: &AtomicBool) -> RecordingSink {
+        let sink = RecordingSink::default();
+        run_eeg_acquisition(
+            source,
+            &sink,
+            stop_flag,
+            SignalQualityConfig::default(),
+            &LinkDiagnostics::new(Instant::now()),
+        );
+        sink
+    }
+
+    #[test]
+    fn packets_are_sequenced_gated_and_bracketed_by_status() {
+        let mut source = ScriptedSource::new(vec![
+            packet(0),
+            Ok(SourceEvent::Idle),
+            Ok(SourceEvent::Reading(EegReading::Blink(40))),
+            Ok(SourceEvent::Reading(EegReading::Blink(90))),
+            packet(120),
+            Ok(SourceEvent::Status(ConnectionStatus::Reconnected)),
+            packet(0),
+        ]);
+        let sink = run(&mut source, &AtomicBool::new(false));
+
+        assert_eq!(
+            sink.0.into_inner(),
+            vec![
+                Published::Status(ConnectionStatus::Connecting),
+                Published::Status(ConnectionStatus::Connected(link())),
+                Published::Packet {
+                    sequence: 0,
+                    accepted: true,
+                    blink: None
+                },
+                Published::Blink(40),
+                Published::Blink(90),
+                // The strongest blink since the last packet rides on the next one.
+                Published::Packet {
+                    sequence: 1,
+                    accepted: false,
+                    blink: Some(90)
+                },
+                Published::Status(ConnectionStatus::Reconnected),
+                Published::Packet {
+                    sequence: 2,
+                    accepted: true,
+                    blink: None
+                },
+                Published::Status(ConnectionStatus::Disconnected),
+            ]
+        );
+        assert!(source.stopped);
+    }
+
+    #[test]
+    fn raw_samples_go_out_in_full_batches_and_the_remainder_at_the_end() {
+        let mut script: Vec<_> = (0..RAW_SAMPLES_PER_EVENT as i16).map(raw).collect();
+        script.extend([raw(-1), raw(-2)]);
+        let sink = run(&mut ScriptedSource::new(script), &AtomicBool::new(false));
+
+        let batches: Vec<Vec<i16>> = sink
+            .0
+            .into_inner()
+            .into_iter()
+            .filter_map(|event| match event {
+                Published::RawBatch(values) => Some(values),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].len(), RAW_SAMPLES_PER_EVENT);
+        assert_eq!(batches[1], vec![-1, -2]);
+    }
+
+    #[test]
+    fn source_error_ends_acquisition_with_its_status() {
+        let mut source = ScriptedSource::new(vec![
+            packet(0),
+            Err(AppError::PortVanished("COM3".to_string())),
+            packet(0),
+        ]);
+        let sink = run(&mut source, &AtomicBool::new(false));
+
+        assert_eq!(
+            sink.statuses(),
+            vec![
+                ConnectionStatus::Connecting,
+                ConnectionStatus::Connected(link()),
+                ConnectionStatus::PortVanished {
+                    port: "COM3".to_string()
+                },
+            ]
+        );
+        let packets = sink
+            .0
+            .borrow()
+            .iter()
+            .filter(|event| matches!(event, Published::Packet { .. }))
+            .count();
+        assert_eq!(packets, 1);
+        assert!(source.stopped);
+    }
+
+    #[test]
+    fn stop_request_ends_acquisition_and_flushes_raw_samples() {
+        let stop_flag = AtomicBool::new(false);
+        let mut source = ScriptedSource::new(vec![packet(0), raw(7), Ok(SourceEvent::Idle)]);
+        source.stop_when_exhausted = Some(&stop_flag);
+        let sink = run(&mut source, &stop_flag);
+
+        let events = sink.0.into_inner();
+        assert_eq!(events.last(), Some(&Published::RawBatch(vec![7])));
+        assert!(!events.contains(&Published::Status(ConnectionStatus::Disconnected)));
+        assert!(source.stopped);
+    }
+
+    #[test]
+    fn headset_returning_after_a_watchdog_trip_is_reported_connected() {
+        // Sends one packet, stays silent past the stale timeout, then
+        // streams again.
+        struct DroppingHeadset {
+            started: Instant,
+            packets_sent: u32,
+        }
+        impl EegSource for DroppingHeadset {
+            fn start(&mut self) -> Result<LinkInfo, AppError> {
+                Ok(link())
+            }
+            fn next_event(&mut self) -> Result<SourceEvent, AppError> {
+                let silence_over = self.started.elapsed() >= Duration::from_millis(60);
+                match self.packets_sent {
+                    0 => {}
+                    1 | 2 if silence_over => {}
+                    1 => {
+                        std::thread::sleep(Duration::from_millis(5));
+                        return Ok(SourceEvent::Idle);
+                    }
+                    _ => return Ok(SourceEvent::Finished),
+                }
+                self.packets_sent += 1;
+                packet(0)
+            }
+            fn stop(&mut self) {}
+        }
+        let sink = RecordingSink::default();
+        run_eeg_acquisition(
+            &mut DroppingHeadset {
+                started: Instant::now(),
+                packets_sent: 0,
+            },
+            &sink,
+            &AtomicBool::new(false),
+            SignalQualityConfig {
+                stale_timeout_ms: 20,
+                ..SignalQualityConfig::default()
+            },
+            &LinkDiagnostics::new(Instant::now()),
+        );
+        let events = sink.0.into_inner();
+        let stamped = |sequence| Published::Packet {
+            sequence,
+            accepted: true,
+            blink: None,
+        };
+        assert_eq!(
+            events[2..],
+            [
+                stamped(0),
+                Published::Status(ConnectionStatus::HeadsetNotDetected),
+                Published::Status(ConnectionStatus::Connected(link())),
+                stamped(1),
+                stamped(2),
+                Published::Status(ConnectionStatus::Disconnected),
+            ]
+        );
+    }
+
+    #[test]
+    fn start_failure_reports_the_error_without_polling() {
+        struct BusyPort;
+        impl EegSource for BusyPort {
+            fn start(&mut self) -> Result<LinkInfo, AppError> {
+                Err(AppError::PortBusy("COM3".to_string()))
+            }
+            fn next_event(&mut self) -> Result<SourceEvent, AppError> {
+                panic!("polled a source that never started")
+            }
+            fn stop(&mut self) {}
+        }
+        let sink = RecordingSink::default();
+        run_eeg_acquisition(
+            &mut BusyPort,
+            &sink,
+            &AtomicBool::new(false),
+            SignalQualityConfig::default(),
+            &LinkDiagnostics::new(Instant::now()),
+        );
+        assert_eq!(
+            sink.statuses(),
+            vec![
+                ConnectionStatus::Connecting,
+                ConnectionStatus::PortBusy {
+                    port: "COM3".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn packets_and_raw_batches_carry_the_wall_clock_time_they_were_read() {
+        // Plays its script, then lingers before finishing, so the trailing
+        // raw batch is only flushed well after its sample was read.
+        struct LingeringSource(VecDeque<Result<SourceEvent, AppError>>);
+        impl EegSource for LingeringSource {
+            fn start(&mut self) -> Result<LinkInfo, AppError> {
+                Ok(link())
+            }
+            fn next_event(&mut self) -> Result<SourceEvent, AppError> {
+                self.0.pop_front().unwrap_or_else(|| {
+                    std::thread::sleep(Duration::from_millis(200));
+                    Ok(SourceEvent::Finished)
+                })
+            }
+            fn stop(&mut self) {}
+        }
+        let unix_now_ms = || ReadTime::now().unix_ms;
+        let sink = RecordingSink::default();
+        let before_reads = unix_now_ms();
+        run_eeg_acquisition(
+            &mut LingeringSource(VecDeque::from([raw(7), packet(0)])),
+            &sink,
+            &AtomicBool::new(false),
+            SignalQualityConfig::default(),
+            &LinkDiagnostics::new(Instant::now()),
+        );
+        let after_flush = unix_now_ms();
+
+        let read_times = sink.1.into_inner();
+        assert_eq!(read_times.len(), 2);
+        assert!(after_flush - before_reads >= 200);
+        for read_at in read_times {
+            assert!(
+                (before_reads..before_reads + 100).contains(&read_at),
+                "stamped {read_at}, reads began at {before_reads}"
+            );
+        }
+    }
+}
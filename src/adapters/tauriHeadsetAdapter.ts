@@ -1,6 +1,17 @@
 import { invoke } from "@tauri-apps/api/core";
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
-import type { EegBandPowers, HeadsetConnectionStatus } from "@/domain";
+import type {
+  FieldMapping,
+  GatedEegPacket,
+  HeadsetConnectionStatus,
+  MarkerInput,
+  RecordingMarker,
+  RecordingOptions,
+  RecordingProgress,
+  SerialConfig,
+  SessionSummary,
+  SignalQualityUpdate,
+} from "@/domain";
 import type { EegSourceConfig } from "@/use_cases/useEegListener";
 import { logger } from "@/lib/logger";
 
@@ -8,30 +19,226 @@ import { logger } from "@/lib/logger";
 // These are the only files in the frontend that may import from @tauri-apps.
 // Use cases call these functions; they never call Tauri directly.
 
+// Mirrors the Rust DEFAULT_DEVICE_ID, used when a call names no headset.
+export const DEFAULT_DEVICE_ID = "default";
+
+// Every reader event carries the id of the headset it came from.
+type DeviceEvent<Payload> = Payload & { deviceId: string };
+
+// To subscribe to one headset's share of a reader event. The tag is
+// stripped so packets reach the recorder exactly as before.
+const listenForDevice = <Payload>(
+  eventName: string,
+  deviceId: string,
+  onPayload: (payload: Payload) => void,
+): Promise<UnlistenFn> =>
+  listen<DeviceEvent<Payload>>(eventName, (event) => {
+    const { deviceId: sourceDeviceId, ...payload } = event.payload;
+    if (sourceDeviceId === deviceId) onPayload(payload as Payload);
+  });
+
 export const startEegReader = async (
   source: EegSourceConfig,
 ): Promise<void> => {
-  await invoke("start_esp32", { port: source.portName });
+  await invoke("start_esp32", {
+    request: {
+      port: source.portName,
+      deviceId: source.deviceId,
+      serial: source.serial,
+      capturePath: source.capturePath,
+      fieldMapping: source.fieldMapping,
+    },
+  });
 };
 
+// Feeds a serial byte capture back through the live pipeline; stopped with
+// stopEegReader like any other source.
+export const startSerialCaptureReplay = async (
+  capturePath: string,
+  speed: "1x" | "4x" | "max" = "1x",
+  deviceId: string = DEFAULT_DEVICE_ID,
+): Promise<void> => {
+  await invoke("start_eeg_source", {
+    config: { type: "serialCapture", capturePath, speed },
+    deviceId,
+  });
+};
+
+// Stops only this source's headset; other devices keep streaming.
 export const stopEegReader = async (source: EegSourceConfig): Promise<void> => {
-  void source;
-  await invoke("stop_esp32").catch((error) =>
+  await invoke("stop_esp32", { deviceId: source.deviceId }).catch((error) =>
     logger.ioError("stop_esp32 failed", error),
   );
 };
 
 export const subscribeToEegPackets = (
-  onPacket: (packet: EegBandPowers) => void,
-): Promise<UnlistenFn> =>
-  listen<EegBandPowers>("eeg-data", (event) => onPacket(event.payload));
+  onPacket: (packet: GatedEegPacket) => void,
+  deviceId: string = DEFAULT_DEVICE_ID,
+): Promise<UnlistenFn> => listenForDevice("eeg-data", deviceId, onPacket);
 
 export const subscribeToHeadsetStatus = (
   onStatus: (status: HeadsetConnectionStatus) => void,
+  deviceId: string = DEFAULT_DEVICE_ID,
+): Promise<UnlistenFn> => listenForDevice("eeg-status", deviceId, onStatus);
+
+export const subscribeToSignalQuality = (
+  onUpdate: (update: SignalQualityUpdate) => void,
+  deviceId: string = DEFAULT_DEVICE_ID,
 ): Promise<UnlistenFn> =>
-  listen<HeadsetConnectionStatus>("eeg-status", (event) =>
-    onStatus(event.payload),
+  listenForDevice("signal-quality", deviceId, onUpdate);
+
+// Mirrors the Rust SerialPortDescriptor returned by list_serial_ports.
+export type SerialPortDescriptor = {
+  portName: string;
+  portType: "usb" | "bluetooth" | "pci" | "unknown";
+  vid: number | null;
+  pid: number | null;
+  manufacturer: string | null;
+  product: string | null;
+  serialNumber: string | null;
+};
+
+// Mirrors the Rust PortProbeResult returned by probe_ports, best match first.
+export type PortProbeResult = {
+  port: SerialPortDescriptor;
+  detectedFormat: "esp32Json" | "thinkGear" | null;
+  readingsDecoded: number;
+  knownBridge: string | null;
+  score: number;
+  error: string | null;
+};
+
+// `serial-ports-changed` payload from the backend hotplug watcher.
+export type SerialPortsChanged = {
+  ports: SerialPortDescriptor[];
+  added: SerialPortDescriptor[];
+  removed: string[];
+  // Running readers whose port was just unplugged.
+  activePortsRemoved?: { deviceId: string; port: string }[];
+};
+
+export const subscribeToSerialPortChanges = (
+  onChange: (change: SerialPortsChanged) => void,
+): Promise<UnlistenFn> =>
+  listen<SerialPortsChanged>("serial-ports-changed", (event) =>
+    onChange(event.payload),
+  );
+
+// Mirrors the Rust DeviceCommand understood by the ESP32 bridge firmware.
+export type DeviceCommand =
+  | { cmd: "getFirmwareVersion" }
+  | { cmd: "resetPairing" }
+  | { cmd: "setRawOutput"; enabled: boolean }
+  | { cmd: "setOutputRate"; hz: number };
+
+// Resolves with the firmware's result once it acknowledges the command;
+// rejects when it refuses, times out, or the device has no ESP32 reader running.
+export const sendDeviceCommand = <Result = unknown>(
+  command: DeviceCommand,
+  deviceId: string = DEFAULT_DEVICE_ID,
+): Promise<Result> =>
+  invoke<Result>("send_device_command", { command, deviceId });
+
+export const getFirmwareVersion = async (
+  deviceId: string = DEFAULT_DEVICE_ID,
+): Promise<string> => {
+  const result = await sendDeviceCommand<{ version: string }>(
+    { cmd: "getFirmwareVersion" },
+    deviceId,
   );
+  return result.version;
+};
+
+// Mirrors the Rust LinkStats carried by `link-stats` and returned by
+// get_link_diagnostics; counts cover the whole reader session.
+export type LinkStats = {
+  elapsedSecs: number;
+  linesRead: number;
+  bytesRead: number;
+  lossyUtf8Lines: number;
+  jsonParseFailures: number;
+  rawOnlyLines: number;
+  completePackets: number;
+  packetsPerSecond: number;
+  meanInterPacketMs: number;
+  interPacketJitterMs: number;
+};
+
+export const subscribeToLinkStats = (
+  onStats: (stats: LinkStats) => void,
+  deviceId: string = DEFAULT_DEVICE_ID,
+): Promise<UnlistenFn> => listenForDevice("link-stats", deviceId, onStats);
+
+// Also answers after the reader stopped, so a failed session can be inspected.
+export const getLinkDiagnostics = (
+  deviceId: string = DEFAULT_DEVICE_ID,
+): Promise<LinkStats> =>
+  invoke<LinkStats>("get_link_diagnostics", { deviceId });
+
+// Mirrors the Rust EegReaderInfo returned by list_eeg_readers.
+export type EegReaderInfo = {
+  deviceId: string;
+  running: boolean;
+  serialPort?: string;
+};
+
+export const listEegReaders = (): Promise<EegReaderInfo[]> =>
+  invoke<EegReaderInfo[]>("list_eeg_readers");
+
+// The backend records every accepted packet itself; these calls only steer
+// the recording, and `recording-row` reports its progress.
+export const startRecording = (
+  options: RecordingOptions,
+  deviceId: string = DEFAULT_DEVICE_ID,
+): Promise<string> =>
+  invoke<string>("start_recording", { request: { ...options, deviceId } });
+
+export const pauseRecording = (
+  deviceId: string = DEFAULT_DEVICE_ID,
+): Promise<void> => invoke("pause_recording", { deviceId });
+
+export const resumeRecording = (
+  deviceId: string = DEFAULT_DEVICE_ID,
+): Promise<void> => invoke("resume_recording", { deviceId });
+
+// Writes the CSV and the sessions.json entry, resolving with the summary.
+export const finishRecording = (
+  csvPath: string,
+  deviceId: string = DEFAULT_DEVICE_ID,
+): Promise<SessionSummary> =>
+  invoke<SessionSummary>("finish_recording", {
+    request: { csvPath, deviceId },
+  });
+
+// Works while the recording is paused too; resolves with the stored marker.
+export const addMarker = (
+  marker: MarkerInput,
+  deviceId: string = DEFAULT_DEVICE_ID,
+): Promise<RecordingMarker> =>
+  invoke<RecordingMarker>("add_marker", { request: { ...marker, deviceId } });
+
+export const discardRecording = (
+  deviceId: string = DEFAULT_DEVICE_ID,
+): Promise<void> => invoke("discard_recording", { deviceId });
+
+export const subscribeToRecordingProgress = (
+  onProgress: (progress: RecordingProgress) => void,
+  deviceId: string = DEFAULT_DEVICE_ID,
+): Promise<UnlistenFn> =>
+  listenForDevice("recording-row", deviceId, onProgress);
+
+// Validates the profile in Rust, so a bad file fails here rather than at start.
+export const loadFieldMapping = (path: string): Promise<FieldMapping> =>
+  invoke<FieldMapping>("load_field_mapping", { path });
+
+export const loadSerialConfig = (port: string): Promise<SerialConfig> =>
+  invoke<SerialConfig>("get_serial_config", { port });
+
+export const listSerialPortDescriptors = (): Promise<SerialPortDescriptor[]> =>
+  invoke<SerialPortDescriptor[]>("list_serial_ports");
+
+export const listAvailableSerialPorts = async (): Promise<string[]> =>
+  (await listSerialPortDescriptors()).map((port) => port.portName);
 
-export const listAvailableSerialPorts = (): Promise<string[]> =>
-  invoke<string[]>("list_serial_ports");
+export const probeSerialPorts = (): Promise<PortProbeResult[]> =>
+  invoke<PortProbeResult[]>("probe_ports");
@@ -1,5 +1,7 @@
 import { invoke } from "@tauri-apps/api/core";
 import type {
+  RecoverableSession,
+  SessionReindexReport,
   SessionSummary,
   SessionPersistencePort,
   SaveSessionRequest,
@@ -7,9 +9,35 @@ import type {
 
 // Concrete implementation of SessionPersistencePort backed by Tauri commands.
 export const createTauriSessionRepository = (): SessionPersistencePort => ({
-  saveSession: (request: SaveSessionRequest): Promise<void> =>
-    invoke("save_session", { request }),
+  saveSession: (request: SaveSessionRequest): Promise<SessionSummary> =>
+    invoke<SessionSummary>("save_session", { request }),
 
   loadSessions: (): Promise<SessionSummary[]> =>
     invoke<SessionSummary[]>("load_sessions"),
+
+  reindexSessions: (directories: string[]): Promise<SessionReindexReport> =>
+    invoke<SessionReindexReport>("reindex_sessions", { directories }),
 });
+
+// Recordings an earlier run left unfinished, as found when the app started.
+export const listRecoverableSessions = (): Promise<RecoverableSession[]> =>
+  invoke<RecoverableSession[]>("list_recoverable_sessions");
+
+// Writes the CSV and a summary marked as recovered, resolving with the summary.
+export const recoverSession = async (
+  recordingId: string,
+  csvPath: string,
+): Promise<SessionSummary> =>
+  (await invoke<SessionSummary | null>("recover_session", {
+    recordingId,
+    action: { action: "finalize", csvPath },
+  })) as SessionSummary;
+
+export const discardRecoverableSession = async (
+  recordingId: string,
+): Promise<void> => {
+  await invoke("recover_session", {
+    recordingId,
+    action: { action: "discard" },
+  });
+};
@@ -1,5 +1,5 @@
 import { create } from "zustand";
-import type { SessionSummary } from "@/domain";
+import type { SessionReindexReport, SessionSummary } from "@/domain";
 import { createTauriSessionRepository } from "./tauriSessionAdapter";
 
 interface SessionHistoryStore {
@@ -8,6 +8,8 @@ interface SessionHistoryStore {
   loadSessions: () => Promise<void>;
   /** To push a new summary into the store immediately after a successful export. */
   addSession: (summary: SessionSummary) => void;
+  /** To rebuild sessions.json from the CSVs in the given directories, then reload. */
+  reindexSessions: (directories: string[]) => Promise<SessionReindexReport>;
 }
 
 const sessionRepository = createTauriSessionRepository();
@@ -22,4 +24,10 @@ export const useSessionStore = create<SessionHistoryStore>((set) => ({
 
   addSession: (summary) =>
     set((store) => ({ sessions: [...store.sessions, summary] })),
+
+  reindexSessions: async (directories) => {
+    const report = await sessionRepository.reindexSessions(directories);
+    set({ sessions: await sessionRepository.loadSessions() });
+    return report;
+  },
 }));
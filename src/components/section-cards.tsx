@@ -92,7 +92,7 @@ export function SectionCards({ sessions }: Props) {
           </div>
           <div className="text-muted-foreground text-xs">
             {last
-              ? `Last session · ${last.subjectName}`
+              ? `Last session · ${last.subjectName}${last.recovered ? " (recovered)" : ""}`
               : "Export a session to populate"}
           </div>
         </CardFooter>
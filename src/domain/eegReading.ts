@@ -24,4 +24,89 @@ export type FocusReading = {
   labelName: string; // "Focused" | "Unfocused" | "N/A"
 };
 
-export type HeadsetConnectionStatus = "connected" | "disconnected";
+// `eeg-status` payload, tagged by `state`. Mirrors the Rust ConnectionStatus:
+// failure states carry the port or reason so setup problems can be shown to
+// the operator. "reconnecting" is emitted while the backend retries a dropped
+// port; "reconnected" once the same port is streaming again.
+export type HeadsetConnectionStatus =
+  | { state: "connecting" }
+  | { state: "connected"; port: string; baud?: number }
+  | { state: "headsetNotDetected" }
+  | { state: "portBusy"; port: string }
+  | { state: "portVanished"; port: string }
+  | {
+      state: "reconnecting";
+      attempt: number;
+      maxAttempts: number;
+      lastError: string;
+    }
+  | { state: "reconnected" }
+  | { state: "error"; reason: string }
+  | { state: "disconnected" };
+
+// `eeg-data` payload: the packet plus the backend signal-quality verdict.
+// Rejected packets still carry their poorSignalLevel for display.
+// Receive stamp the backend reader puts on every packet, plus the firmware's
+// own clock and counter when the sketch sends them. Gaps in `deviceSeq` are
+// dropped packets; `receivedAtUnixMs - deviceMillis` drift is link latency.
+export type PacketTiming = {
+  sequence: number;
+  monotonicNs: number;
+  receivedAtUnixMs: number;
+  deviceMillis?: number;
+  deviceSeq?: number;
+};
+
+// Optional packet fields: the strongest blink since the previous packet, and
+// numeric fields some firmware adds beyond the standard set.
+export type EegPacketExtras = {
+  blinkStrength?: number;
+  extensions?: Record<string, number>;
+};
+
+export type TimedEegPacket = EegBandPowers & PacketTiming & EegPacketExtras;
+
+export type GatedEegPacket = TimedEegPacket & {
+  accepted: boolean;
+  rejectionReason?: "poorSignal" | "noContact";
+};
+
+// Tier reported by the backend gate, already debounced with hysteresis.
+export type SignalTier = "good" | "poor" | "noHeadset";
+
+// `signal-quality` payload, emitted only when the tier changes. `stale` is
+// set when the tier comes from the backend watchdog rather than a packet.
+export type SignalQualityUpdate = {
+  tier: SignalTier;
+  previousTier: SignalTier | null;
+  poorSignalLevel: number;
+  stale: boolean;
+};
+
+// Mirrors the Rust SerialConfig. Omitted fields take the backend defaults:
+// the wire format's standard baud rate, 8N1, no flow control, 500 ms timeout.
+// `dtr: "deasserted"` opens an ESP32 dev board without resetting it.
+export type ControlLineState = "unchanged" | "asserted" | "deasserted";
+export type SerialConfig = {
+  baudRate?: number;
+  dataBits?: 5 | 6 | 7 | 8;
+  parity?: "none" | "odd" | "even";
+  stopBits?: 1 | 2;
+  flowControl?: "none" | "software" | "hardware";
+  readTimeoutMs?: number;
+  dtr?: ControlLineState;
+  rts?: ControlLineState;
+};
+
+// Mirrors the Rust FieldMapping. Keys of `fields` are standard ESP32 JSON
+// fields; `path` is dot-separated, e.g. "eegPower.low_alpha".
+export type FieldRule = {
+  path: string;
+  scale?: number;
+  offset?: number;
+  default?: number;
+};
+export type FieldMapping = {
+  name?: string;
+  fields: Record<string, FieldRule>;
+};
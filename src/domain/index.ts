@@ -1,10 +1,30 @@
 export type {
   EegBandPowers,
   FocusReading,
+  GatedEegPacket,
   HeadsetConnectionStatus,
+  PacketTiming,
+  SerialConfig,
+  SignalQualityUpdate,
+  SignalTier,
+  TimedEegPacket,
+  EegPacketExtras,
+  FieldMapping,
+  FieldRule,
 } from "./eegReading";
 export type { SessionMode } from "./sessionMode";
-export type { SessionSummary } from "./sessionSummary";
+export type {
+  MarkerInput,
+  RecordingMarker,
+  RecordingOptions,
+  RecordingProgress,
+  RecoverableSession,
+} from "./sessionRecording";
+export type {
+  SessionReindexReport,
+  SessionSummary,
+  SkippedSessionCsv,
+} from "./sessionSummary";
 export type { Screen, AppFile } from "./screenTypes";
 export type {
   FocusClassifierPort,
@@ -1,5 +1,5 @@
 import type { EegBandPowers, FocusReading } from "./eegReading";
-import type { SessionSummary } from "./sessionSummary";
+import type { SessionReindexReport, SessionSummary } from "./sessionSummary";
 
 // Every external capability that the use-case layer depends on is described
 // as an interface here. Concrete implementations live in adapters/ and are
@@ -10,8 +10,11 @@ export interface FocusClassifierPort {
 }
 
 export interface SessionPersistencePort {
-  saveSession(request: SaveSessionRequest): Promise<void>;
+  // Resolves with the summary as stored, aggregates recomputed by the backend.
+  saveSession(request: SaveSessionRequest): Promise<SessionSummary>;
   loadSessions(): Promise<SessionSummary[]>;
+  // Recomputes the index from the recorder CSVs found in the directories.
+  reindexSessions(directories: string[]): Promise<SessionReindexReport>;
 }
 
 // Bundles the three fields required to persist a session.
@@ -19,5 +22,8 @@ export interface SessionPersistencePort {
 export interface SaveSessionRequest {
   csvPath: string;
   csvContent: string;
+  // Optional 512 Hz raw-waveform CSV, written next to the band-power CSV.
+  rawCsvContent?: string;
+  // Aggregates are recomputed from csvContent; the rest is stored as given.
   summary: SessionSummary;
 }
@@ -0,0 +1,44 @@
+import type { SessionMode } from "./sessionMode";
+
+// Mirrors the Rust RecordingOptions: what the backend recorder needs to
+// label rows without asking the webview.
+export type RecordingOptions = {
+  subjectName: string;
+  sessionMode: SessionMode;
+  attentionThreshold: number; // attention >= this is Focused in recording mode
+};
+
+// Mirrors the Rust RecordingMarker: a labelled moment in a recording.
+export type RecordingMarker = {
+  label: string;
+  category?: string; // e.g. "stimulus" or "distraction"
+  note?: string;
+  atUnixMs: number;
+  afterSequence?: number; // last row recorded before the marker
+};
+
+// What the operator enters for a marker; `atUnixMs` defaults to now.
+export type MarkerInput = Omit<RecordingMarker, "afterSequence" | "atUnixMs"> & {
+  atUnixMs?: number;
+};
+
+// A recording an earlier run left unfinished, found at startup.
+export type RecoverableSession = {
+  recordingId: string;
+  deviceId: string;
+  subjectName: string;
+  sessionMode: SessionMode;
+  startedAtUnixMs: number;
+  lastEntryAtUnixMs: number; // roughly when the app stopped
+  rowCount: number;
+};
+
+// `recording-row` payload, sent for every row the backend journaled.
+export type RecordingProgress = {
+  rowCount: number;
+  sequence: number;
+  focusLabel: number; // 0 = Unfocused, 1 = Focused, −1 = unavailable
+  focusPrediction: string;
+  // Why a live-mode row fell back to −1, when inference failed.
+  inferenceError?: string;
+};
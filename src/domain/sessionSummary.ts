@@ -14,4 +14,22 @@ export type SessionSummary = {
   meanAttention: number;
   meanMeditation: number;
   signalQualityPct: number; // % of rows where poorSignalLevel === 0
+  rawCsvPath?: string; // Sibling raw-waveform CSV, when one was recorded
+  markerCount?: number; // Markers placed during the session
+  markersCsvPath?: string; // Sibling CSV listing them, when there were any
+  recovered?: boolean; // Rebuilt from an interrupted recording's journal
+  contentHash?: string; // Hash of the CSV, so a reindex recognises copies
+};
+
+// A CSV a reindex left out of the index, and why.
+export type SkippedSessionCsv = {
+  csvPath: string;
+  reason: string;
+};
+
+// Outcome of reindex_sessions, by CSV path.
+export type SessionReindexReport = {
+  added: string[];
+  updated: string[];
+  skipped: SkippedSessionCsv[];
 };
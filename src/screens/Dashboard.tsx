@@ -1,11 +1,41 @@
+import { useState } from "react";
 import { motion } from "motion/react";
+import { open } from "@tauri-apps/plugin-dialog";
+import { sileo } from "sileo";
+import { IconRefresh } from "@tabler/icons-react";
+import { Button } from "@/components/ui/button";
 import { ChartAreaInteractive } from "@/components/chart-area-interactive";
 import { SectionCards } from "@/components/section-cards";
 import { ease } from "@/lib/constants";
 import { useSessionStore } from "@/adapters/useSessionStore";
+import { logger } from "@/lib/logger";
 
 const DashboardScreen = () => {
   const sessions = useSessionStore((store) => store.sessions);
+  const reindexSessions = useSessionStore((store) => store.reindexSessions);
+  const [isReindexing, setIsReindexing] = useState(false);
+
+  // Rebuilds the summaries from the CSVs in the chosen folders, e.g. after
+  // sessions.json was lost.
+  const handleReindex = async () => {
+    const selected = await open({ directory: true, multiple: true });
+    if (!selected || selected.length === 0) return;
+    setIsReindexing(true);
+    try {
+      const report = await reindexSessions(selected);
+      const counts = `${report.added.length} added, ${report.updated.length} updated, ${report.skipped.length} skipped`;
+      logger.io(`Reindexed sessions: ${counts}`);
+      sileo.success({ title: "Sessions reindexed", description: `${counts}.` });
+    } catch (error) {
+      logger.ioError("Session reindex failed", error);
+      sileo.error({
+        title: "Reindex failed",
+        description: "The session index could not be rebuilt.",
+      });
+    } finally {
+      setIsReindexing(false);
+    }
+  };
 
   return (
     <motion.div
@@ -25,14 +55,26 @@ const DashboardScreen = () => {
                 Brain activity overview &amp; signal metrics
               </p>
             </div>
-            <div className="flex items-center gap-2 rounded-full border border-border/60 bg-background/20 backdrop-blur-sm px-3 py-1">
-              <span className="relative flex h-1.5 w-1.5">
-                <span className="absolute inline-flex h-full w-full animate-ping rounded-full bg-foreground/50 opacity-75" />
-                <span className="relative inline-flex h-1.5 w-1.5 rounded-full bg-foreground/70" />
-              </span>
-              <span className="text-[10px] font-medium tracking-[0.2em] uppercase text-muted-foreground">
-                Live
-              </span>
+            <div className="flex items-center gap-2">
+              <Button
+                variant="outline"
+                size="sm"
+                disabled={isReindexing}
+                onClick={handleReindex}>
+                <IconRefresh
+                  className={isReindexing ? "animate-spin" : undefined}
+                />
+                Reindex
+              </Button>
+              <div className="flex items-center gap-2 rounded-full border border-border/60 bg-background/20 backdrop-blur-sm px-3 py-1">
+                <span className="relative flex h-1.5 w-1.5">
+                  <span className="absolute inline-flex h-full w-full animate-ping rounded-full bg-foreground/50 opacity-75" />
+                  <span className="relative inline-flex h-1.5 w-1.5 rounded-full bg-foreground/70" />
+                </span>
+                <span className="text-[10px] font-medium tracking-[0.2em] uppercase text-muted-foreground">
+                  Live
+                </span>
+              </div>
             </div>
           </div>
           <SectionCards sessions={sessions} />
@@ -1,11 +1,13 @@
 import React from "react";
+import { save } from "@tauri-apps/plugin-dialog";
+import { sileo } from "sileo";
 import { motion, AnimatePresence } from "motion/react";
 import { useTheme } from "next-themes";
 import { ease } from "@/lib/constants";
 import AppSidebar from "@/components/AppSidebar";
 import CodeView from "@/components/CodeView";
 import { fileLabels, fileSource, liveScreens } from "@/lib/file-contents";
-import { type AppFile } from "@/domain";
+import { type AppFile, type RecoverableSession } from "@/domain";
 import SessionScreen from "./Session";
 import DashboardScreen from "./Dashboard";
 import { SiteHeader } from "@/components/site-header";
@@ -17,6 +19,9 @@ import {
 } from "@/components/animate-ui/components/radix/sidebar";
 import { useSessionStore } from "@/adapters/useSessionStore";
 import { logger } from "@/lib/logger";
+import { useSessionRecovery } from "@/use_cases/useSessionRecovery";
+import { RecoveryDialog } from "./session/components/RecoveryDialog";
+import { buildExportFilename } from "./session/utils";
 
 const screenComponents: Record<AppFile, React.ComponentType> = {
   session: SessionScreen,
@@ -34,6 +39,47 @@ const Layout = () => {
     );
   }, [loadSessions]);
 
+  // Interrupted recordings are offered before anything else, whichever
+  // screen is open, so a participant's data is not forgotten.
+  const recovery = useSessionRecovery();
+
+  const handleRecover = async (session: RecoverableSession) => {
+    const selectedPath = await save({
+      filters: [{ name: "CSV Data", extensions: ["csv"] }],
+      defaultPath: buildExportFilename(session.subjectName),
+    });
+    if (!selectedPath) return;
+    try {
+      const summary = await recovery.recover(session, selectedPath);
+      sileo.success({
+        title: "Session recovered",
+        description: `Saved to ${summary.csvPath.split(/[\\/]/).pop()}.`,
+      });
+    } catch (error) {
+      logger.ioError("Session recovery failed", error);
+      sileo.error({
+        title: "Recovery failed",
+        description: "The session could not be saved. Please try again.",
+      });
+    }
+  };
+
+  const handleDiscard = async (session: RecoverableSession) => {
+    try {
+      await recovery.discard(session);
+      sileo.info({
+        title: "Session discarded",
+        description: `The interrupted session for ${session.subjectName} was deleted.`,
+      });
+    } catch (error) {
+      logger.ioError("Interrupted session discard failed", error);
+      sileo.error({
+        title: "Discard failed",
+        description: "The interrupted session could not be deleted.",
+      });
+    }
+  };
+
   const isLiveScreen = liveScreens.includes(activeScreen);
   const ActiveScreen = screenComponents[activeScreen];
 
@@ -79,6 +125,13 @@ const Layout = () => {
           )}
         </SidebarInset>
       </SidebarProvider>
+
+      <RecoveryDialog
+        session={recovery.pendingSessions[0]}
+        isBusy={recovery.isBusy}
+        onRecover={handleRecover}
+        onDiscard={handleDiscard}
+      />
     </motion.div>
   );
 };
@@ -1,7 +1,7 @@
 import * as React from "react";
 import { save } from "@tauri-apps/plugin-dialog";
 import { sileo } from "sileo";
-import { type FocusReading, type EegBandPowers } from "@/domain";
+import { type MarkerInput } from "@/domain";
 import { IconClockHour3, IconBrain, IconDatabase } from "@tabler/icons-react";
 import { motion } from "motion/react";
 import { ease } from "@/lib/constants";
@@ -19,12 +19,15 @@ import {
 import { useSessionStore } from "@/adapters/useSessionStore";
 import { useHeadsetStore } from "@/adapters/useHeadsetStore";
 import { useModelStore } from "@/adapters/useModelStore";
-import { createTauriClassifier } from "@/adapters/tauriClassifierAdapter";
-import { createTauriSessionRepository } from "@/adapters/tauriSessionAdapter";
-import { listAvailableSerialPorts } from "@/adapters/tauriHeadsetAdapter";
+import {
+  listAvailableSerialPorts,
+  probeSerialPorts,
+  subscribeToSerialPortChanges,
+} from "@/adapters/tauriHeadsetAdapter";
 import { useSessionTimer } from "@/use_cases/useSessionTimer";
 import { useCalibration } from "@/use_cases/useCalibration";
 import {
+  describeConnectionStatus,
   useEegListener,
   type EegSourceConfig,
 } from "@/use_cases/useEegListener";
@@ -33,13 +36,11 @@ import { useSignalMonitor } from "@/use_cases/useSignalMonitor";
 import { StatCard } from "./session/components/StatCard";
 import { ModelManagementCard } from "./session/components/ModelManagementCard";
 import { SessionControlsCard } from "./session/components/SessionControlsCard";
+import { MarkerCard } from "./session/components/MarkerCard";
 import { SubjectNameDialog } from "./session/components/SubjectNameDialog";
 import { CalibrationDialog } from "./session/components/CalibrationDialog";
 import { DisconnectDialog } from "./session/components/DisconnectDialog";
 
-const focusClassifier = createTauriClassifier();
-const sessionRepository = createTauriSessionRepository();
-
 const SessionScreen = () => {
   // Persistent across screen navigation — backed by Zustand so navigating away
   // and back does not lose an in-progress session or reset the model state.
@@ -70,15 +71,33 @@ const SessionScreen = () => {
 
   const eegSource: EegSourceConfig = { type: "esp32", portName: esp32Port };
 
+  // Port chosen automatically (first listed, then best probe match); the
+  // probe result only replaces a port the participant has not picked by hand.
+  const autoSelectedPortRef = React.useRef("");
+
   const loadPorts = React.useCallback(() => {
     listAvailableSerialPorts()
       .then((serialPorts) => {
         setAvailablePorts(serialPorts);
-        setEsp32Port((previous) =>
-          previous === "" && serialPorts.length > 0 ? serialPorts[0] : previous,
+        setEsp32Port((previous) => {
+          if (previous !== "" || serialPorts.length === 0) return previous;
+          autoSelectedPortRef.current = serialPorts[0];
+          return serialPorts[0];
+        });
+        return probeSerialPorts();
+      })
+      .then((probeResults) => {
+        const bestMatch = probeResults.find(
+          (result) => result.detectedFormat !== null,
         );
+        if (!bestMatch) return;
+        setEsp32Port((previous) => {
+          if (previous !== autoSelectedPortRef.current) return previous;
+          autoSelectedPortRef.current = bestMatch.port.portName;
+          return bestMatch.port.portName;
+        });
       })
-      .catch((error) => logger.ioError("list_serial_ports failed", error));
+      .catch((error) => logger.ioError("Serial port discovery failed", error));
   }, []);
 
   // Populate port list once on mount so it’s ready when the user picks ESP32.
@@ -86,20 +105,56 @@ const SessionScreen = () => {
     loadPorts();
   }, [loadPorts]);
 
+  // Keep the list current as ports are plugged in or removed. A newly added
+  // port is adopted only when nothing usable was selected yet.
+  React.useEffect(() => {
+    let isCleaned = false;
+    let unlisten: (() => void) | undefined;
+    subscribeToSerialPortChanges((change) => {
+      const portNames = change.ports.map((port) => port.portName);
+      setAvailablePorts(portNames);
+      if (change.added.length > 0) {
+        setEsp32Port((previous) => {
+          if (portNames.includes(previous)) return previous;
+          autoSelectedPortRef.current = change.added[0].portName;
+          return change.added[0].portName;
+        });
+      }
+    })
+      .then((stopListening) => {
+        if (isCleaned) {
+          stopListening();
+          return;
+        }
+        unlisten = stopListening;
+      })
+      .catch((error) =>
+        logger.ioError("Serial port change subscription failed", error),
+      );
+    return () => {
+      isCleaned = true;
+      unlisten?.();
+    };
+  }, []);
+
   const stagedModelMap = useModelStore((s) => s.stagedModelMap);
   const modelReady = useModelStore((s) => s.modelReady);
   const isModelRequired = useModelStore((s) => s.isModelRequired);
   const isSessionReady = useModelStore((s) => s.isSessionReady);
   const handleLoadModel = useModelStore((s) => s.handleLoadModel);
-  const { displayBandPowers, rawBandPowers, isConnected, poorSignalLevel } =
-    useEegListener(isScanning || showCalibrationDialog, eegSource);
+  const {
+    displayBandPowers,
+    isConnected,
+    poorSignalLevel,
+    signalTier,
+    connectionStatus,
+  } = useEegListener(isScanning || showCalibrationDialog, eegSource);
   // Timer runs for the full scanning duration so the elapsed clock does not
   // freeze during brief signal dropouts. Packet acceptance and recording are
-  // still gated on poorSignalLevel < 50 — only the display is decoupled.
-  const hasGoodSignal = isConnected && poorSignalLevel < 50;
+  // still gated by the backend signal quality gate — only the display is decoupled.
+  const hasGoodSignal = isConnected && signalTier === "good";
   const { elapsedSeconds, reset: resetTimer } = useSessionTimer(isScanning);
-  useSignalMonitor({ active: isScanning, isConnected, poorSignalLevel });
-  const recorder = useSessionRecorder();
+  useSignalMonitor({ active: isScanning, isConnected, signalTier });
   const modelRequired = isModelRequired(sessionMode);
   const modelGateSatisfied = isSessionReady(sessionMode);
   const canEditSessionSetup = !isScanning && !hasSessionStarted;
@@ -133,11 +188,24 @@ const SessionScreen = () => {
     },
     [],
   );
+  // The backend labels and journals every accepted packet; live-mode rows
+  // that fell back to −1 are reported here.
+  const recorder = useSessionRecorder(reportInferenceIssue);
+
+  // A paused recording leaves the stopped scan out of the session duration.
+  const pauseRecording = () => {
+    recorder
+      .pauseRecording()
+      .catch((error) => logger.ioError("Recording pause failed", error));
+  };
 
   const handleStartScanning = () => {
     // New scan cycle: clear stale connection history from prior runs.
     wasConnectedRef.current = false;
     if (hasSessionStarted) {
+      recorder
+        .resumeRecording()
+        .catch((error) => logger.ioError("Recording resume failed", error));
       setIsScanning(true);
       sileo.success({
         title: "Scanning resumed",
@@ -176,7 +244,21 @@ const SessionScreen = () => {
     resetCalibration();
   };
 
-  const handleCalibrationComplete = () => {
+  const handleCalibrationComplete = async () => {
+    try {
+      await recorder.startRecording({
+        subjectName,
+        sessionMode,
+        attentionThreshold,
+      });
+    } catch (error) {
+      logger.ioError("Recording start failed", error);
+      sileo.error({
+        title: "Recording failed",
+        description: "The recording could not be started. Please try again.",
+      });
+      return;
+    }
     setShowCalibrationDialog(false);
     wasConnectedRef.current = false;
     recorder.resetFocusWindow();
@@ -202,38 +284,12 @@ const SessionScreen = () => {
 
   React.useEffect(() => {
     if (isScanning && !isConnected && wasConnectedRef.current) {
+      pauseRecording();
       setIsScanning(false);
       setShowDisconnectDialog(true);
     }
   }, [isScanning, isConnected]);
 
-  // Recording mode skips inference entirely and labels rows from attention.
-  // Live mode keeps model inference and stores fallback labels on failures.
-  const recordEegPacket = (bandPowers: EegBandPowers) => {
-    if (sessionMode === "live" && modelReady) {
-      focusClassifier
-        .classify(bandPowers)
-        .then((focusReading: FocusReading) => {
-          if (focusReading.label === 0 || focusReading.label === 1) {
-            recorder.appendEegRecord(bandPowers, focusReading);
-            return;
-          }
-
-          reportInferenceIssue(
-            `Unexpected model label received: ${focusReading.label}`,
-          );
-          recorder.appendEegRecord(bandPowers, undefined);
-        })
-        .catch((error) => {
-          reportInferenceIssue("Focus inference request failed", error);
-          recorder.appendEegRecord(bandPowers, undefined);
-        });
-      return;
-    }
-
-    recorder.appendEegRecord(bandPowers, undefined);
-  };
-
   const handleThresholdChange = (
     event: React.ChangeEvent<HTMLInputElement>,
   ) => {
@@ -242,16 +298,10 @@ const SessionScreen = () => {
     setAttentionThreshold(numericValue);
   };
 
-  React.useEffect(() => {
-    if (!isScanning || !rawBandPowers) return;
-    recordEegPacket(rawBandPowers);
-    // rawBandPowers reference changes on every new EEG packet — that is the
-    // intended trigger; suppressing the exhaustive-deps warning is deliberate.
-    // eslint-disable-next-line react-hooks/exhaustive-deps
-  }, [rawBandPowers]);
-
   const signalMessage = !isConnected
-    ? "Could not open the serial port. Make sure the ESP32 is connected via USB and the correct COM port is selected."
+    ? connectionStatus && connectionStatus.state !== "disconnected"
+      ? describeConnectionStatus(connectionStatus)
+      : "Could not open the serial port. Make sure the ESP32 is connected via USB and the correct COM port is selected."
     : poorSignalLevel >= 200
       ? "Connected but no headset was detected. Turn on the headset and make sure it is properly worn."
       : "Headset connected but signal quality is poor. Adjust the headband and try again.";
@@ -267,8 +317,8 @@ const SessionScreen = () => {
   };
 
   /**
-   * To write the accumulated CSV to disk and append the session summary to
-   * the local index. Returns true on success so callers can perform
+   * To have the backend export the recording to a CSV and append its summary
+   * to the local index. Returns true on success so callers can perform
    * source-specific teardown (e.g. stopping the scanner) only on success.
    */
   const persistAndResetSession = async (
@@ -281,17 +331,7 @@ const SessionScreen = () => {
     if (!selectedPath) return false;
 
     const csvPath = selectedPath as string;
-    const summary = recorder.buildSessionSummary({
-      subjectName,
-      durationSecs: elapsedSeconds,
-      csvPath,
-    });
-
-    await sessionRepository.saveSession({
-      csvPath,
-      csvContent: recorder.buildCsvString(),
-      summary,
-    });
+    const summary = await recorder.finishRecording(csvPath);
     addSession(summary);
     resetAfterSession();
 
@@ -307,6 +347,7 @@ const SessionScreen = () => {
     // setIsScanning(false) and a late setIsConnected(false) from the
     // useEegListener teardown cannot trigger the disconnect dialog.
     wasConnectedRef.current = false;
+    pauseRecording();
     setIsScanning(false);
     sileo.info({
       title: "Scanning paused",
@@ -314,10 +355,27 @@ const SessionScreen = () => {
     });
   };
 
+  const handleAddMarker = async (marker: MarkerInput): Promise<boolean> => {
+    try {
+      await recorder.addMarker(marker);
+      return true;
+    } catch (error) {
+      logger.ioError("Marker add failed", error);
+      sileo.error({
+        title: "Marker not saved",
+        description: "The marker could not be added to the recording.",
+      });
+      return false;
+    }
+  };
+
   const handleDisconnectSaveAndEnd = async () => {
     setShowDisconnectDialog(false);
     wasConnectedRef.current = false;
     if (recorder.rowCount === 0) {
+      recorder
+        .discardRecording()
+        .catch((error) => logger.ioError("Recording discard failed", error));
       resetAfterSession();
       return;
     }
@@ -368,21 +426,21 @@ const SessionScreen = () => {
                     className={cn(
                       "relative inline-flex h-1.5 w-1.5 rounded-full transition-colors",
                       isConnected
-                        ? poorSignalLevel < 50
+                        ? signalTier === "good"
                           ? "bg-emerald-500"
-                          : poorSignalLevel >= 200
-                            ? "bg-foreground/30"
-                            : "bg-amber-500"
+                          : signalTier === "poor"
+                            ? "bg-amber-500"
+                            : "bg-foreground/30"
                         : "bg-foreground/30",
                     )}
                   />
                   <span className="text-[10px] font-medium tracking-[0.2em] uppercase text-muted-foreground">
                     {isConnected
-                      ? poorSignalLevel < 50
+                      ? signalTier === "good"
                         ? "Signal OK"
-                        : poorSignalLevel >= 200
-                          ? "No Headset"
-                          : "Poor Signal"
+                        : signalTier === "poor"
+                          ? "Poor Signal"
+                          : "No Headset"
                       : "No ESP32"}
                   </span>
                 </div>
@@ -548,6 +606,12 @@ const SessionScreen = () => {
                 availablePorts={availablePorts}
                 onRefreshPorts={loadPorts}
               />
+
+              <MarkerCard
+                enabled={hasSessionStarted}
+                markerCount={recorder.markerCount}
+                onAddMarker={handleAddMarker}
+              />
             </div>
           </div>
         </div>
@@ -0,0 +1,117 @@
+import * as React from "react";
+import { Card, CardContent, CardHeader, CardTitle } from "@/components/ui/card";
+import { Input } from "@/components/ui/input";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+import { cn } from "@/lib/utils";
+import type { MarkerInput } from "@/domain";
+
+const MARKER_CATEGORIES = [
+  "task",
+  "stimulus",
+  "distraction",
+  "artifact",
+  "note",
+] as const;
+
+interface MarkerCardProps {
+  /** Markers need an active recording, paused or not. */
+  enabled: boolean;
+  markerCount: number;
+  onAddMarker: (marker: MarkerInput) => Promise<boolean>;
+}
+
+export const MarkerCard = ({
+  enabled,
+  markerCount,
+  onAddMarker,
+}: MarkerCardProps) => {
+  const [label, setLabel] = React.useState("");
+  const [category, setCategory] = React.useState<string>("task");
+  const [note, setNote] = React.useState("");
+  const canAdd = enabled && label.trim() !== "";
+
+  const handleAdd = async () => {
+    if (!canAdd) return;
+    // Stamped on click so typing a note does not shift the marker.
+    const added = await onAddMarker({
+      label,
+      category,
+      note,
+      atUnixMs: Date.now(),
+    });
+    if (!added) return;
+    setLabel("");
+    setNote("");
+  };
+
+  return (
+    <Card className="border border-border/50 bg-background/10 backdrop-blur-md gap-2 py-3.5">
+      <CardHeader className="px-4 pb-0">
+        <div className="flex items-center justify-between">
+          <div>
+            <CardTitle className="text-sm font-semibold leading-tight">
+              Markers
+            </CardTitle>
+            <p className="text-[10px] text-muted-foreground/60 leading-tight mt-0.5">
+              Timestamped events saved with the session
+            </p>
+          </div>
+          <span className="text-[10px] font-mono tracking-wider text-muted-foreground/60">
+            {markerCount}
+          </span>
+        </div>
+      </CardHeader>
+      <CardContent className="flex flex-col gap-1.5 px-4 pb-0">
+        <div className="flex gap-1">
+          <Input
+            value={label}
+            onChange={(event) => setLabel(event.target.value)}
+            onKeyDown={(event) => {
+              if (event.key === "Enter") handleAdd();
+            }}
+            placeholder="Label, e.g. stimulus onset"
+            disabled={!enabled}
+            className="h-7 text-[11px] flex-1"
+          />
+          <Select
+            value={category}
+            onValueChange={setCategory}
+            disabled={!enabled}>
+            <SelectTrigger className="h-7 w-28 text-[11px] border-border/40 bg-background/20">
+              <SelectValue />
+            </SelectTrigger>
+            <SelectContent>
+              {MARKER_CATEGORIES.map((option) => (
+                <SelectItem key={option} value={option} className="text-xs">
+                  {option}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+        </div>
+        <Input
+          value={note}
+          onChange={(event) => setNote(event.target.value)}
+          placeholder="Note (optional)"
+          disabled={!enabled}
+          className="h-7 text-[11px]"
+        />
+        <button
+          onClick={handleAdd}
+          disabled={!canAdd}
+          className={cn(
+            "h-7 rounded-md border border-border/40 bg-background/20 text-[11px] font-medium transition-all hover:bg-foreground/10",
+            !canAdd && "opacity-40 cursor-not-allowed",
+          )}>
+          Add Marker
+        </button>
+      </CardContent>
+    </Card>
+  );
+};
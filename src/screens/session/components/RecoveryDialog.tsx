@@ -0,0 +1,76 @@
+import { IconAlertTriangle } from "@tabler/icons-react";
+import {
+  Dialog,
+  DialogPopup,
+  DialogHeader,
+  DialogTitle,
+  DialogDescription,
+} from "@/components/animate-ui/components/base/dialog";
+import { Button } from "@/components/ui/button";
+import type { RecoverableSession } from "@/domain";
+import { formatSamples } from "../utils";
+
+interface RecoveryDialogProps {
+  session: RecoverableSession | undefined;
+  isBusy: boolean;
+  onRecover: (session: RecoverableSession) => void;
+  onDiscard: (session: RecoverableSession) => void;
+}
+
+// Offers one interrupted recording at a time; closing is not offered so a
+// participant's data is never dismissed by an accidental click.
+export const RecoveryDialog = ({
+  session,
+  isBusy,
+  onRecover,
+  onDiscard,
+}: RecoveryDialogProps) => {
+  return (
+    <Dialog open={session !== undefined} onOpenChange={() => {}}>
+      <DialogPopup
+        className="sm:max-w-md border border-border/40 bg-background/95 backdrop-blur-sm"
+        showCloseButton={false}>
+        {session && (
+          <>
+            <DialogHeader>
+              <div className="flex items-center gap-2">
+                <IconAlertTriangle className="size-4 text-amber-500 shrink-0" />
+                <DialogTitle>Interrupted Session Found</DialogTitle>
+              </div>
+              <DialogDescription>
+                The session for{" "}
+                <span className="font-medium text-foreground">
+                  {session.subjectName}
+                </span>{" "}
+                started{" "}
+                {new Date(session.startedAtUnixMs).toLocaleString()} was not
+                saved before the app closed.{" "}
+                <span className="font-medium text-foreground">
+                  {formatSamples(session.rowCount)} sample
+                  {session.rowCount === 1 ? "" : "s"}
+                </span>{" "}
+                up to {new Date(session.lastEntryAtUnixMs).toLocaleTimeString()}{" "}
+                can be recovered.
+              </DialogDescription>
+            </DialogHeader>
+            <div className="flex gap-2 mt-1">
+              <Button
+                variant="outline"
+                disabled={isBusy}
+                onClick={() => onDiscard(session)}
+                className="flex-1">
+                Discard
+              </Button>
+              <Button
+                disabled={isBusy}
+                onClick={() => onRecover(session)}
+                className="flex-1">
+                Recover &amp; Save
+              </Button>
+            </div>
+          </>
+        )}
+      </DialogPopup>
+    </Dialog>
+  );
+};
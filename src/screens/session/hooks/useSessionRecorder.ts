@@ -1,3 +1,2 @@
 // Re-exported from the use_cases layer.
 export { useSessionRecorder } from "@/use_cases/useSessionRecorder";
-export type { BuildSessionSummaryOptions } from "@/adapters/useRecordingStore";
@@ -1,23 +1,35 @@
 import * as React from "react";
-import type { EegBandPowers, HeadsetConnectionStatus } from "@/domain";
+import type {
+  EegBandPowers,
+  FieldMapping,
+  HeadsetConnectionStatus,
+  SerialConfig,
+  SignalTier,
+  TimedEegPacket,
+} from "@/domain";
 import {
   startEegReader,
   stopEegReader,
   subscribeToEegPackets,
   subscribeToHeadsetStatus,
+  subscribeToSerialPortChanges,
+  subscribeToSignalQuality,
+  DEFAULT_DEVICE_ID,
 } from "@/adapters/tauriHeadsetAdapter";
 import { logger } from "@/lib/logger";
 
-export type EegSourceConfig = { type: "esp32"; portName: string };
-
-const POOR_SIGNAL_REJECTION_THRESHOLD = 50;
-
-// If no EEG packet arrives within this window while the reader is active,
-// the headset has likely powered off (ESP32 stays connected via USB but stops
-// emitting JSON when its Bluetooth link to the headset drops). We synthesize
-// poorSignalLevel = 200 so the signal monitor can react without waiting for a
-// packet that will never come.
-const PACKET_TIMEOUT_MS = 2500;
+// `serial` overrides the settings remembered for the port; omit it to reuse them.
+export type EegSourceConfig = {
+  type: "esp32";
+  portName: string;
+  serial?: SerialConfig;
+  // Logs every byte read from the port to this file for offline replay.
+  capturePath?: string;
+  // Adapts a sketch whose JSON keys differ from the standard schema.
+  fieldMapping?: FieldMapping;
+  // Names the headset when several are read at once; defaults to "default".
+  deviceId?: string;
+};
 
 // To convert raw µV² band powers to relative percentages (0–100) so the
 // chart always sums to 100% regardless of absolute power magnitude.
@@ -54,18 +66,51 @@ const computeRelativeBandPowers = (packet: EegBandPowers): EegBandPowers => {
   };
 };
 
+// To turn a backend link status into a sentence an operator can act on.
+export const describeConnectionStatus = (
+  status: HeadsetConnectionStatus,
+): string => {
+  switch (status.state) {
+    case "connecting":
+      return "Opening the EEG source…";
+    case "connected":
+      return status.baud
+        ? `Connected to ${status.port} at ${status.baud} baud.`
+        : `Connected to ${status.port}.`;
+    case "headsetNotDetected":
+      return "Connected but no headset was detected. Turn on the headset and make sure it is properly worn.";
+    case "portBusy":
+      return `${status.port} is in use by another application. Close any serial monitor or other app holding it and try again.`;
+    case "portVanished":
+      return `${status.port} is no longer available. Check the USB cable and select the port again.`;
+    case "reconnecting":
+      return `Reconnecting (attempt ${status.attempt}/${status.maxAttempts}): ${status.lastError}`;
+    case "reconnected":
+      return "Reconnected.";
+    case "error":
+      return status.reason;
+    case "disconnected":
+      return "Disconnected.";
+  }
+};
+
 interface EegListenerResult {
   /** Relative (%) band powers — suitable for chart display. */
   displayBandPowers: EegBandPowers | undefined;
-  /** Absolute µV² band powers — used for CSV recording and inference. */
-  rawBandPowers: EegBandPowers | undefined;
+  /** Absolute µV² band powers with receive timing — used for CSV recording and inference. */
+  rawBandPowers: TimedEegPacket | undefined;
   isConnected: boolean;
   poorSignalLevel: number;
+  /** Backend-reported tier; undefined until the first `signal-quality` event. */
+  signalTier: SignalTier | undefined;
+  /** Latest `eeg-status` payload, including the reason for a failed link. */
+  connectionStatus: HeadsetConnectionStatus | undefined;
 }
 
 /**
  * Starts and stops the EEG data reader based on `active` and exposes
- * normalized band power data. Packets with poorSignalLevel ≥ 50 are dropped.
+ * normalized band power data. Gating, tiering and the stale-data watchdog run
+ * in the backend; packets it rejects update poorSignalLevel but not the bands.
  * Reader control and event subscriptions are delegated to adapter functions.
  */
 export const useEegListener = (
@@ -76,32 +121,20 @@ export const useEegListener = (
     EegBandPowers | undefined
   >(undefined);
   const [rawBandPowers, setRawBandPowers] = React.useState<
-    EegBandPowers | undefined
+    TimedEegPacket | undefined
   >(undefined);
   const [isConnected, setIsConnected] = React.useState(false);
   const [poorSignalLevel, setPoorSignalLevel] = React.useState(200);
-
-  // Watchdog: reset on every packet; fires PACKET_TIMEOUT_MS after the last
-  // one, synthesizing poorSignalLevel = 200 so the signal monitor detects the
-  // silence even though the serial port is still open.
-  const watchdogRef = React.useRef<ReturnType<typeof setTimeout> | null>(null);
-
-  const resetWatchdog = React.useCallback(() => {
-    if (watchdogRef.current !== null) clearTimeout(watchdogRef.current);
-    watchdogRef.current = setTimeout(() => {
-      setPoorSignalLevel(200);
-    }, PACKET_TIMEOUT_MS);
-  }, []);
-
-  const clearWatchdog = React.useCallback(() => {
-    if (watchdogRef.current !== null) {
-      clearTimeout(watchdogRef.current);
-      watchdogRef.current = null;
-    }
-  }, []);
+  const [signalTier, setSignalTier] = React.useState<SignalTier | undefined>(
+    undefined,
+  );
+  const [connectionStatus, setConnectionStatus] = React.useState<
+    HeadsetConnectionStatus | undefined
+  >(undefined);
 
   // Extract primitive dependencies to avoid object reference churn.
   const esp32PortName = source.portName;
+  const deviceId = source.deviceId ?? DEFAULT_DEVICE_ID;
 
   React.useEffect(() => {
     const stopReader = () =>
@@ -111,11 +144,12 @@ export const useEegListener = (
 
     if (!active) {
       stopReader();
-      clearWatchdog();
       setDisplayBandPowers(undefined);
       setRawBandPowers(undefined);
       setIsConnected(false);
       setPoorSignalLevel(200);
+      setSignalTier(undefined);
+      setConnectionStatus(undefined);
       return;
     }
 
@@ -127,18 +161,18 @@ export const useEegListener = (
     let isCleaned = false;
     let unlistenPackets: (() => void) | undefined;
     let unlistenStatus: (() => void) | undefined;
+    let unlistenSignalQuality: (() => void) | undefined;
+    let unlistenPortChanges: (() => void) | undefined;
 
     subscribeToEegPackets((packet) => {
-      // Data packets imply a live serial stream, so treat them as authoritative
-      // even if a one-shot "connected" status event was missed during startup.
-      setIsConnected(true);
-      resetWatchdog();
       setPoorSignalLevel(packet.poorSignalLevel);
-      if (packet.poorSignalLevel < POOR_SIGNAL_REJECTION_THRESHOLD) {
-        setRawBandPowers(packet);
-        setDisplayBandPowers(computeRelativeBandPowers(packet));
+      if (packet.accepted) {
+        // Strip the verdict so recorder and inference see a plain timed packet.
+        const { accepted, rejectionReason, ...bandPowers } = packet;
+        setRawBandPowers(bandPowers);
+        setDisplayBandPowers(computeRelativeBandPowers(bandPowers));
       }
-    })
+    }, deviceId)
       .then((unlisten) => {
         if (isCleaned) {
           unlisten();
@@ -151,12 +185,32 @@ export const useEegListener = (
       );
 
     subscribeToHeadsetStatus((status: HeadsetConnectionStatus) => {
-      setIsConnected(status === "connected");
-      if (status === "disconnected") {
-        setDisplayBandPowers(undefined);
-        setRawBandPowers(undefined);
+      setConnectionStatus(status);
+      switch (status.state) {
+        case "connecting":
+          return;
+        case "reconnecting":
+        case "headsetNotDetected":
+          // The link is up or being restored, so this is not a disconnect:
+          // keep the session alive and let the signal monitor show no contact.
+          setPoorSignalLevel(200);
+          return;
+        case "connected":
+        case "reconnected":
+          setIsConnected(true);
+          return;
+        default:
+          if (status.state !== "disconnected") {
+            logger.ioError(
+              "EEG link failed",
+              describeConnectionStatus(status),
+            );
+          }
+          setIsConnected(false);
+          setDisplayBandPowers(undefined);
+          setRawBandPowers(undefined);
       }
-    })
+    }, deviceId)
       .then((unlisten) => {
         if (isCleaned) {
           unlisten();
@@ -168,6 +222,48 @@ export const useEegListener = (
         logger.ioError("Headset status subscription failed", error),
       );
 
+    // The backend watchdog reports a silent headset as a stale update, since
+    // no packet will arrive to carry the new level.
+    subscribeToSignalQuality((update) => {
+      setSignalTier(update.tier);
+      if (update.stale) setPoorSignalLevel(update.poorSignalLevel);
+    }, deviceId)
+      .then((unlisten) => {
+        if (isCleaned) {
+          unlisten();
+          return;
+        }
+        unlistenSignalQuality = unlisten;
+      })
+      .catch((error) =>
+        logger.ioError("Signal quality subscription failed", error),
+      );
+
+    // An unplugged port is reported by the hotplug watcher within a second,
+    // so show it straight away. The session only ends once the backend has
+    // released the device after its grace period and sent portVanished on
+    // eeg-status; a cable that reseats in time keeps the session.
+    subscribeToSerialPortChanges((change) => {
+      const removed = change.activePortsRemoved?.find(
+        (active) => active.deviceId === deviceId,
+      );
+      if (removed === undefined) return;
+      setConnectionStatus({
+        state: "portVanished",
+        port: removed.port,
+      });
+    })
+      .then((unlisten) => {
+        if (isCleaned) {
+          unlisten();
+          return;
+        }
+        unlistenPortChanges = unlisten;
+      })
+      .catch((error) =>
+        logger.ioError("Serial port change subscription failed", error),
+      );
+
     // Start the backend reader only after listeners are registered to avoid
     // dropping the initial "connected" status event on fast restarts.
     startEegReader(source).catch((error) =>
@@ -178,11 +274,19 @@ export const useEegListener = (
       isCleaned = true;
       unlistenPackets?.();
       unlistenStatus?.();
-      clearWatchdog();
+      unlistenSignalQuality?.();
+      unlistenPortChanges?.();
       stopReader();
     };
     // eslint-disable-next-line react-hooks/exhaustive-deps
-  }, [active, esp32PortName]);
+  }, [active, esp32PortName, deviceId]);
 
-  return { displayBandPowers, rawBandPowers, isConnected, poorSignalLevel };
+  return {
+    displayBandPowers,
+    rawBandPowers,
+    isConnected,
+    poorSignalLevel,
+    signalTier,
+    connectionStatus,
+  };
 };
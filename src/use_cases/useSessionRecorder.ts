@@ -1,34 +1,103 @@
 import * as React from "react";
 import { useRecordingStore } from "@/adapters/useRecordingStore";
-import { useHeadsetStore } from "@/adapters/useHeadsetStore";
-import type { EegBandPowers, FocusReading } from "@/domain";
+import {
+  addMarker,
+  discardRecording,
+  finishRecording,
+  pauseRecording,
+  resumeRecording,
+  startRecording,
+  subscribeToRecordingProgress,
+  DEFAULT_DEVICE_ID,
+} from "@/adapters/tauriHeadsetAdapter";
+import { logger } from "@/lib/logger";
+import type {
+  MarkerInput,
+  RecordingMarker,
+  RecordingOptions,
+  SessionSummary,
+} from "@/domain";
 
-// Thin delegate — all recording state lives in the persistent useRecordingStore
-// (Zustand) so recorder rows survive screen navigation without data loss.
+// Thin delegate — rows are labelled and journaled by the backend recorder,
+// so a webview reload or a slow render cannot lose them. The persistent
+// useRecordingStore (Zustand) only mirrors the progress it reports.
 
-export const useSessionRecorder = () => {
+export const useSessionRecorder = (
+  onInferenceIssue: (summary: string) => void,
+  deviceId: string = DEFAULT_DEVICE_ID,
+) => {
   const rowCount = useRecordingStore((store) => store.rowCount);
+  const markerCount = useRecordingStore((store) => store.markerCount);
+  const countMarker = useRecordingStore((store) => store.countMarker);
   const recentFocusLabels = useRecordingStore(
     (store) => store.recentFocusLabels,
   );
   const resetFocusWindow = useRecordingStore((store) => store.resetFocusWindow);
-  const appendToStore = useRecordingStore((store) => store.appendEegRecord);
-  const buildCsvString = useRecordingStore((store) => store.buildCsvString);
-  const buildSessionSummary = useRecordingStore(
-    (store) => store.buildSessionSummary,
+  const applyRecordingProgress = useRecordingStore(
+    (store) => store.applyRecordingProgress,
   );
   const clearRecording = useRecordingStore((store) => store.clearRecording);
 
-  const appendEegRecord = React.useCallback(
-    (bandPowers: EegBandPowers, focusReading?: FocusReading) => {
-      const { sessionMode, attentionThreshold } = useHeadsetStore.getState();
-      appendToStore(bandPowers, {
-        sessionMode,
-        attentionThreshold,
-        focusReading,
-      });
+  // Kept in a ref so a new callback identity does not resubscribe.
+  const onInferenceIssueRef = React.useRef(onInferenceIssue);
+  onInferenceIssueRef.current = onInferenceIssue;
+
+  React.useEffect(() => {
+    let isCleaned = false;
+    let unlisten: (() => void) | undefined;
+    subscribeToRecordingProgress((progress) => {
+      applyRecordingProgress(progress);
+      if (progress.inferenceError !== undefined) {
+        onInferenceIssueRef.current(
+          `Focus inference failed: ${progress.inferenceError}`,
+        );
+      }
+    }, deviceId)
+      .then((stopListening) => {
+        if (isCleaned) {
+          stopListening();
+          return;
+        }
+        unlisten = stopListening;
+      })
+      .catch((error) =>
+        logger.ioError("Recording progress subscription failed", error),
+      );
+    return () => {
+      isCleaned = true;
+      unlisten?.();
+    };
+  }, [applyRecordingProgress, deviceId]);
+
+  const start = React.useCallback(
+    (options: RecordingOptions): Promise<string> =>
+      startRecording(options, deviceId),
+    [deviceId],
+  );
+  const pause = React.useCallback(
+    (): Promise<void> => pauseRecording(deviceId),
+    [deviceId],
+  );
+  const resume = React.useCallback(
+    (): Promise<void> => resumeRecording(deviceId),
+    [deviceId],
+  );
+  const finish = React.useCallback(
+    (csvPath: string): Promise<SessionSummary> =>
+      finishRecording(csvPath, deviceId),
+    [deviceId],
+  );
+  const mark = React.useCallback(
+    async (marker: MarkerInput): Promise<RecordingMarker> => {
+      const stored = await addMarker(marker, deviceId);
+      countMarker();
+      return stored;
     },
-    [appendToStore],
+    [countMarker, deviceId],
+  );
+  const discard = React.useCallback(
+    (): Promise<void> => discardRecording(deviceId),
+    [deviceId],
   );
 
   // Majority vote over the rolling window: ≥ 3 out of 5 model labels = Focused.
@@ -49,10 +118,14 @@ export const useSessionRecorder = () => {
 
   return {
     rowCount,
+    markerCount,
     resetFocusWindow,
-    appendEegRecord,
-    buildCsvString,
-    buildSessionSummary,
+    startRecording: start,
+    pauseRecording: pause,
+    resumeRecording: resume,
+    finishRecording: finish,
+    addMarker: mark,
+    discardRecording: discard,
     clearRecording,
     rollingFocusVote,
     rollingFocusLevel,
@@ -0,0 +1,63 @@
+import * as React from "react";
+import {
+  discardRecoverableSession,
+  listRecoverableSessions,
+  recoverSession,
+} from "@/adapters/tauriSessionAdapter";
+import { useSessionStore } from "@/adapters/useSessionStore";
+import { logger } from "@/lib/logger";
+import type { RecoverableSession, SessionSummary } from "@/domain";
+
+/**
+ * Lists the recordings an earlier run left unfinished — the backend finds
+ * them at startup — and finalizes or discards them one at a time. A
+ * recovered summary joins the session store like a normal export; a failed
+ * recovery keeps the session pending so it can be retried.
+ */
+export const useSessionRecovery = () => {
+  const [pendingSessions, setPendingSessions] = React.useState<
+    RecoverableSession[]
+  >([]);
+  const [isBusy, setIsBusy] = React.useState(false);
+  const addSession = useSessionStore((store) => store.addSession);
+
+  React.useEffect(() => {
+    listRecoverableSessions()
+      .then(setPendingSessions)
+      .catch((error) =>
+        logger.ioError("Interrupted recording scan failed", error),
+      );
+  }, []);
+
+  const settle = (recordingId: string) =>
+    setPendingSessions((previous) =>
+      previous.filter((session) => session.recordingId !== recordingId),
+    );
+
+  const recover = async (
+    session: RecoverableSession,
+    csvPath: string,
+  ): Promise<SessionSummary> => {
+    setIsBusy(true);
+    try {
+      const summary = await recoverSession(session.recordingId, csvPath);
+      addSession(summary);
+      settle(session.recordingId);
+      return summary;
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  const discard = async (session: RecoverableSession): Promise<void> => {
+    setIsBusy(true);
+    try {
+      await discardRecoverableSession(session.recordingId);
+      settle(session.recordingId);
+    } finally {
+      setIsBusy(false);
+    }
+  };
+
+  return { pendingSessions, isBusy, recover, discard };
+};